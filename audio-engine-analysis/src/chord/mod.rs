@@ -0,0 +1,65 @@
+//! Name a set of notes as a chord.
+//!
+//! Chords are matched on pitch classes (the tone without octave), so inversions and
+//! doubled notes are recognized. When multiple chords match the same set of tones (C6 and
+//! Am7 for example) the chord with the lowest sounding note as root is preferred.
+use audio_engine_notes::{ChromaticChordType, ChromaticNote, ChromaticTone};
+
+use crate::transcription::{detect_notes, TranscriptionParameters};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chord {
+    pub root: ChromaticTone,
+    pub chord_type: ChromaticChordType,
+    /// Lowest sounding note, differs from the root for inversions.
+    pub bass: ChromaticNote,
+}
+
+/// Find the chord that consists of exactly the tones of the notes.
+pub fn name_chord(notes: &[ChromaticNote]) -> Option<Chord> {
+    let bass = *notes.iter().min_by_key(|note| i32::from(**note))?;
+    let tones = pitch_classes(notes.iter().map(|note| note.tone));
+
+    let mut matches = notes.iter().flat_map(|root| {
+        ChromaticChordType::ALL
+            .iter()
+            .filter_map(move |chord_type| {
+                let chord_tones = pitch_classes(chord_type.note_steps().iter().map(|step| {
+                    ChromaticTone::from(((u8::from(root.tone) as i32 + step) % 12) as u8)
+                }));
+                (chord_tones == tones).then_some(Chord {
+                    root: root.tone,
+                    chord_type: *chord_type,
+                    bass,
+                })
+            })
+    });
+    let first = matches.next()?;
+    Some(
+        matches
+            .chain(std::iter::once(first))
+            .find(|chord| chord.root == bass.tone)
+            .unwrap_or(first),
+    )
+}
+
+/// Detect the notes in the samples and name the chord they form.
+pub fn detect_chord(
+    samples: &[f32],
+    sample_rate: f32,
+    parameters: &TranscriptionParameters,
+) -> Option<Chord> {
+    let notes = detect_notes(samples, sample_rate, parameters)
+        .iter()
+        .map(|detected| detected.note)
+        .collect::<Vec<ChromaticNote>>();
+    name_chord(&notes)
+}
+
+/// Set of tones as a bit mask.
+fn pitch_classes(tones: impl Iterator<Item = ChromaticTone>) -> u16 {
+    tones.fold(0, |mask, tone| mask | 1 << u8::from(tone))
+}
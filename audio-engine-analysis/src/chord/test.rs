@@ -0,0 +1,47 @@
+use audio_engine_notes::{ChromaticChordType, ChromaticNote, ChromaticTone};
+
+use super::name_chord;
+
+fn notes(names: &[&str]) -> Vec<ChromaticNote> {
+    names
+        .iter()
+        .map(|name| ChromaticNote::from(*name))
+        .collect()
+}
+
+#[test]
+fn triads() {
+    let chord = name_chord(&notes(&["C4", "E4", "G4"])).unwrap();
+    assert_eq!(chord.root, ChromaticTone::C);
+    assert_eq!(chord.chord_type, ChromaticChordType::Major);
+
+    let chord = name_chord(&notes(&["A3", "C4", "E4"])).unwrap();
+    assert_eq!(chord.root, ChromaticTone::A);
+    assert_eq!(chord.chord_type, ChromaticChordType::Minor);
+}
+
+#[test]
+fn inversion_and_doubling() {
+    let chord = name_chord(&notes(&["E3", "G3", "C4", "E4"])).unwrap();
+    assert_eq!(chord.root, ChromaticTone::C);
+    assert_eq!(chord.chord_type, ChromaticChordType::Major);
+    assert_eq!(chord.bass, ChromaticNote::new(ChromaticTone::E, 3));
+}
+
+/// C6 and Am7 have the same tones, the bass decides.
+#[test]
+fn ambiguous_prefers_bass() {
+    let chord = name_chord(&notes(&["C4", "E4", "G4", "A4"])).unwrap();
+    assert_eq!(chord.root, ChromaticTone::C);
+    assert_eq!(chord.chord_type, ChromaticChordType::Sixth);
+
+    let chord = name_chord(&notes(&["A3", "C4", "E4", "G4"])).unwrap();
+    assert_eq!(chord.root, ChromaticTone::A);
+    assert_eq!(chord.chord_type, ChromaticChordType::MinorSeventh);
+}
+
+#[test]
+fn no_chord() {
+    assert_eq!(name_chord(&[]), None);
+    assert_eq!(name_chord(&notes(&["C4", "C#4", "D4"])), None);
+}
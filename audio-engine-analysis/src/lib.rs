@@ -0,0 +1,8 @@
+//! Analysis of recorded or rendered audio.
+//!
+//! Where the other packages generate or change audio, this package extracts musical
+//! information from it, for example the pitch that is being played.
+pub mod chord;
+pub mod onset;
+pub mod pitch;
+pub mod transcription;
@@ -0,0 +1,135 @@
+//! Detection of note onsets using spectral flux.
+//!
+//! A new note shows as a sudden increase of energy in part of the spectrum. The spectral
+//! flux sums the increase of the (log compressed) magnitudes between two frames. Peaks of
+//! the flux above its local average are onsets.
+use audio_engine_fourier::{
+    complex_number::ComplexNumberMethods,
+    stft::{Stft, StftParameters},
+    window::WindowFunction,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnsetParameters {
+    pub window_len: usize,
+    pub hop_size: usize,
+    /// Minimum height of a peak above the local average, relative to the highest flux.
+    pub threshold: f32,
+    /// Minimum time in seconds between two onsets.
+    pub min_interval: f32,
+}
+
+impl Default for OnsetParameters {
+    fn default() -> Self {
+        OnsetParameters {
+            window_len: 1024,
+            hop_size: 256,
+            threshold: 0.1,
+            min_interval: 0.05,
+        }
+    }
+}
+
+/// Number of frames on each side used to find local maxima.
+const PEAK_RADIUS: usize = 3;
+/// Number of frames on each side used for the local average.
+const AVERAGE_RADIUS: usize = 8;
+
+/// Times in seconds where notes start.
+pub fn detect_onsets(samples: &[f32], sample_rate: f32, parameters: &OnsetParameters) -> Vec<f32> {
+    let spectrogram = Stft::new(StftParameters {
+        window_len: parameters.window_len,
+        hop_size: parameters.hop_size,
+        window: WindowFunction::Hann,
+    })
+    .analyze(samples);
+
+    let magnitudes = spectrogram
+        .frames
+        .iter()
+        .map(|frame| {
+            frame
+                .iter()
+                .map(|bin| (1.0 + 100.0 * bin.amplitude()).ln())
+                .collect::<Vec<f32>>()
+        })
+        .collect::<Vec<Vec<f32>>>();
+    let flux = magnitudes
+        .iter()
+        .enumerate()
+        .map(|(index, frame)| {
+            let previous = index.checked_sub(1).map(|previous| &magnitudes[previous]);
+            frame
+                .iter()
+                .enumerate()
+                .map(|(bin, magnitude)| {
+                    (magnitude - previous.map_or(0.0, |previous| previous[bin])).max(0.0)
+                })
+                .sum::<f32>()
+        })
+        .collect::<Vec<f32>>();
+    let max_flux = flux.iter().copied().fold(0.0, f32::max);
+    if max_flux <= f32::EPSILON {
+        return Vec::new();
+    }
+
+    // Frames that extend beyond the end see the end of the signal as a click.
+    let complete_frames = (samples.len() / parameters.hop_size).min(flux.len());
+    let mut result = Vec::<f32>::new();
+    for (index, value) in flux.iter().enumerate().take(complete_frames) {
+        let peak_range =
+            index.saturating_sub(PEAK_RADIUS)..(index + PEAK_RADIUS + 1).min(flux.len());
+        if flux[peak_range].iter().any(|other| other > value) {
+            continue;
+        }
+        let average_range =
+            index.saturating_sub(AVERAGE_RADIUS)..(index + AVERAGE_RADIUS + 1).min(flux.len());
+        let average = flux[average_range.clone()].iter().sum::<f32>() / average_range.len() as f32;
+        if value - average < parameters.threshold * max_flux {
+            continue;
+        }
+        let time = spectrogram.time(index, sample_rate).max(0.0);
+        if result
+            .last()
+            .is_some_and(|last| time - last < parameters.min_interval)
+        {
+            continue;
+        }
+        result.push(time);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::TAU;
+
+    use super::{detect_onsets, OnsetParameters};
+
+    /// Each note decays until the next note starts.
+    #[test]
+    fn decaying_notes() {
+        let sample_rate = 44100.0;
+        let starts = [0.1, 0.5, 0.75, 1.2];
+        let samples = (0..(1.5 * sample_rate) as usize)
+            .map(|index| index as f32 / sample_rate)
+            .map(|time| {
+                starts
+                    .iter()
+                    .enumerate()
+                    .rfind(|(_, start)| time >= **start)
+                    .map(|(note, start)| {
+                        let frequency = 220.0 * (1.0 + note as f32 * 0.25);
+                        (TAU * frequency * time).sin() * (-(time - start) * 10.0).exp()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<f32>>();
+
+        let onsets = detect_onsets(&samples, sample_rate, &OnsetParameters::default());
+        assert_eq!(onsets.len(), starts.len(), "{onsets:?}");
+        for (onset, start) in onsets.iter().zip(starts) {
+            assert!((onset - start).abs() < 0.02, "{onset} != {start}");
+        }
+    }
+}
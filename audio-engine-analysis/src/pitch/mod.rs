@@ -0,0 +1,190 @@
+//! Pitch detection using the YIN algorithm.
+//!
+//! Picking the loudest frequency of a spectrum often detects a harmonic in stead of the
+//! fundamental (octave errors). YIN searches for the period of the signal: the smallest
+//! delay where the signal is similar to itself.
+//!
+//! 1. Difference function: `d(tau) = sum((x[j] - x[j + tau])^2)`.
+//! 2. Cumulative mean normalized difference: `d'(tau) = d(tau) * tau / sum(d(1..=tau))`.
+//!    This removes the dip at `tau = 0` and makes the values comparable to a threshold.
+//! 3. The first dip below the threshold is the period. Parabolic interpolation refines
+//!    the period between samples using the difference function.
+//!
+//! The difference function is calculated with an FFT, so a buffer costs `O(n log n)`.
+//!
+//! ```
+//! use std::f32::consts::TAU;
+//! use audio_engine_analysis::pitch::{detect_pitch, PitchParameters};
+//!
+//! let sample_rate = 44100.0;
+//! let samples = (0..4096)
+//!     .map(|index| (TAU * 445.0 * index as f32 / sample_rate).sin())
+//!     .collect::<Vec<f32>>();
+//! let estimate = detect_pitch(&samples, sample_rate, &PitchParameters::default()).unwrap();
+//! assert!((estimate.frequency - 445.0).abs() < 0.1);
+//! assert_eq!(format!("{:?}", estimate.note.tone), "A");
+//! assert!((estimate.cents - 19.6).abs() < 0.5);
+//! ```
+use audio_engine_fourier::{complex_number::ComplexNumberMethods, fft::RealFft};
+use audio_engine_notes::ChromaticNote;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchParameters {
+    /// Lowest frequency to detect. The buffer should contain at least two periods of this
+    /// frequency.
+    pub min_frequency: f32,
+    /// Highest frequency to detect.
+    pub max_frequency: f32,
+    /// Dips of the normalized difference below this threshold are accepted as period.
+    /// Lower values reject more noisy signals.
+    pub threshold: f32,
+}
+
+impl Default for PitchParameters {
+    fn default() -> Self {
+        PitchParameters {
+            min_frequency: 40.0,
+            max_frequency: 2000.0,
+            threshold: 0.15,
+        }
+    }
+}
+
+/// Detected pitch of a buffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitchEstimate {
+    /// Frequency in Hz.
+    pub frequency: f32,
+    /// How periodic the signal is, between 0.0 (noise) and 1.0 (perfectly periodic).
+    pub confidence: f32,
+    /// Closest note to the frequency.
+    pub note: ChromaticNote,
+    /// Deviation of the frequency from the note in cents (1/100 semitone).
+    pub cents: f32,
+}
+
+impl PitchEstimate {
+    pub fn new(frequency: f32, confidence: f32) -> PitchEstimate {
+        let note = ChromaticNote::from(frequency);
+        PitchEstimate {
+            frequency,
+            confidence,
+            note,
+            cents: cents(frequency, note.pitch()),
+        }
+    }
+}
+
+/// Difference in cents between two frequencies.
+pub fn cents(frequency: f32, reference: f32) -> f32 {
+    1200.0 * (frequency / reference).log2()
+}
+
+/// Detect the pitch of the samples.
+///
+/// Returns `None` when the buffer is too short for the minimum frequency or the signal is
+/// silent.
+pub fn detect_pitch(
+    samples: &[f32],
+    sample_rate: f32,
+    parameters: &PitchParameters,
+) -> Option<PitchEstimate> {
+    let min_period = ((sample_rate / parameters.max_frequency).floor() as usize).max(2);
+    let max_period = (sample_rate / parameters.min_frequency).ceil() as usize;
+    if samples.len() < 2 * max_period || min_period >= max_period {
+        return None;
+    }
+
+    let difference = difference_function(samples, max_period);
+    let normalized = cumulative_mean_normalized_difference(&difference);
+    if normalized.is_empty() {
+        return None;
+    }
+
+    let period = find_period(&normalized, min_period, max_period, parameters.threshold);
+    let confidence = (1.0 - normalized[period]).clamp(0.0, 1.0);
+    let period = parabolic_interpolation(&difference, period);
+    Some(PitchEstimate::new(sample_rate / period, confidence))
+}
+
+/// `d(tau)` for `tau` in `0..=max_period`, comparing the first `len - max_period` samples.
+fn difference_function(samples: &[f32], max_period: usize) -> Vec<f32> {
+    let window = samples.len() - max_period;
+    let fft_len = (samples.len() + window).next_power_of_two();
+    let fft = RealFft::new(fft_len);
+
+    let mut padded = vec![0.0; fft_len];
+    padded[..samples.len()].copy_from_slice(samples);
+    let signal = fft.forward(&padded);
+    padded.fill(0.0);
+    padded[..window].copy_from_slice(&samples[..window]);
+    let head = fft.forward(&padded);
+
+    // Cross correlation of the head with the signal: sum(x[j] * x[j + tau]).
+    let correlation = fft.inverse(
+        &head
+            .iter()
+            .zip(signal.iter())
+            .map(|(head, signal)| head.conjugate().multiply(*signal))
+            .collect::<Vec<_>>(),
+    );
+
+    let mut energy = vec![0.0; samples.len() + 1];
+    for (index, sample) in samples.iter().enumerate() {
+        energy[index + 1] = energy[index] + sample * sample;
+    }
+    let head_energy = energy[window];
+    (0..=max_period)
+        .map(|tau| {
+            let shifted_energy = energy[tau + window] - energy[tau];
+            (head_energy + shifted_energy - 2.0 * correlation[tau]).max(0.0)
+        })
+        .collect()
+}
+
+fn cumulative_mean_normalized_difference(difference: &[f32]) -> Vec<f32> {
+    let mut result = vec![1.0; difference.len()];
+    let mut sum = 0.0;
+    for tau in 1..difference.len() {
+        sum += difference[tau];
+        if sum <= f32::EPSILON {
+            // Silence.
+            return Vec::new();
+        }
+        result[tau] = difference[tau] * tau as f32 / sum;
+    }
+    result
+}
+
+/// First dip below the threshold, or the lowest value when no dip is below it.
+fn find_period(normalized: &[f32], min_period: usize, max_period: usize, threshold: f32) -> usize {
+    let mut tau = min_period;
+    while tau < max_period {
+        if normalized[tau] < threshold {
+            while tau + 1 < max_period && normalized[tau + 1] < normalized[tau] {
+                tau += 1;
+            }
+            return tau;
+        }
+        tau += 1;
+    }
+    (min_period..max_period)
+        .min_by(|a, b| normalized[*a].total_cmp(&normalized[*b]))
+        .unwrap()
+}
+
+/// Refine the position of the minimum with a parabola through the neighbours.
+fn parabolic_interpolation(values: &[f32], index: usize) -> f32 {
+    if index == 0 || index + 1 >= values.len() {
+        return index as f32;
+    }
+    let (left, center, right) = (values[index - 1], values[index], values[index + 1]);
+    let denominator = left - 2.0 * center + right;
+    if denominator.abs() <= f32::EPSILON {
+        return index as f32;
+    }
+    index as f32 + 0.5 * (left - right) / denominator
+}
@@ -0,0 +1,78 @@
+use std::f32::consts::TAU;
+
+use audio_engine_notes::{ChromaticNote, ChromaticTone};
+
+use super::{cents, detect_pitch, PitchParameters};
+
+const SAMPLE_RATE: f32 = 44100.0;
+
+fn harmonics(frequency: f32, amplitudes: &[f32], len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|index| {
+            let time = index as f32 / SAMPLE_RATE;
+            amplitudes
+                .iter()
+                .enumerate()
+                .map(|(harmonic, amplitude)| {
+                    amplitude * (TAU * frequency * (harmonic + 1) as f32 * time).sin()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+#[test]
+fn sine() {
+    for frequency in [55.0, 110.0, 261.62558, 440.0, 1000.0, 1760.0] {
+        let samples = harmonics(frequency, &[1.0], 4096);
+        let estimate = detect_pitch(&samples, SAMPLE_RATE, &PitchParameters::default()).unwrap();
+        assert!(
+            cents(estimate.frequency, frequency).abs() < 1.0,
+            "{frequency}: {}",
+            estimate.frequency
+        );
+        assert!(estimate.confidence > 0.95);
+    }
+}
+
+/// The second harmonic is louder than the fundamental, the loudest bin of a spectrum
+/// would be an octave too high.
+#[test]
+fn weak_fundamental() {
+    let samples = harmonics(220.0, &[0.3, 1.0, 0.6, 0.4, 0.2], 4096);
+    let estimate = detect_pitch(&samples, SAMPLE_RATE, &PitchParameters::default()).unwrap();
+    assert!(
+        (estimate.frequency - 220.0).abs() < 0.5,
+        "{}",
+        estimate.frequency
+    );
+    assert_eq!(estimate.note, ChromaticNote::new(ChromaticTone::A, 3));
+}
+
+#[test]
+fn cents_from_note() {
+    let samples = harmonics(435.0, &[1.0, 0.5], 4096);
+    let estimate = detect_pitch(&samples, SAMPLE_RATE, &PitchParameters::default()).unwrap();
+    assert_eq!(estimate.note, ChromaticNote::new(ChromaticTone::A, 4));
+    assert!((estimate.cents - cents(435.0, 440.0)).abs() < 1.0);
+}
+
+#[test]
+fn noise_has_low_confidence() {
+    let mut state = 1_u32;
+    let samples = (0..4096)
+        .map(|_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as f32 / (1 << 23) as f32 - 1.0
+        })
+        .collect::<Vec<f32>>();
+    let estimate = detect_pitch(&samples, SAMPLE_RATE, &PitchParameters::default()).unwrap();
+    assert!(estimate.confidence < 0.5, "{}", estimate.confidence);
+}
+
+#[test]
+fn silence_and_short_buffers() {
+    let parameters = PitchParameters::default();
+    assert_eq!(detect_pitch(&[0.0; 4096], SAMPLE_RATE, &parameters), None);
+    assert_eq!(detect_pitch(&[0.5; 100], SAMPLE_RATE, &parameters), None);
+}
@@ -0,0 +1,185 @@
+//! Detection of multiple notes sounding at the same time.
+//!
+//! Uses iterative harmonic-sum estimation on the magnitude spectrum:
+//!
+//! 1. Each candidate note gets a salience: the weighted sum of the spectral peaks at its
+//!    harmonics. Higher harmonics get a lower weight, so a note an octave too low (which
+//!    only matches the even harmonics) loses from the actual note. Candidates without
+//!    energy at their fundamental are skipped, otherwise a note an octave or a twelfth
+//!    below a chord collects the fundamentals of the chord as its harmonics.
+//! 2. The candidate with the highest salience is detected. Its harmonics are removed from
+//!    the spectrum, smoothed with the neighbouring harmonics so partials shared with
+//!    other notes are only partially removed.
+//! 3. Repeat until the salience drops below a fraction of the first detected note.
+use audio_engine_fourier::{
+    complex_number::ComplexNumberMethods, fft::RealFft, window::WindowFunction,
+};
+use audio_engine_notes::{ChromaticNote, ChromaticTone};
+
+#[cfg(test)]
+mod test;
+
+/// Half a semitone, the range around a harmonic where its peak is searched.
+const HALF_SEMITONE: f32 = 1.029_302_2;
+
+/// Minimum level of the fundamental relative to the loudest harmonic of a candidate.
+const MIN_FUNDAMENTAL: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TranscriptionParameters {
+    pub lowest_note: ChromaticNote,
+    pub highest_note: ChromaticNote,
+    /// Number of harmonics summed per candidate.
+    pub harmonics: usize,
+    /// Weight of each next harmonic relative to the previous one.
+    pub harmonic_weight: f32,
+    /// Notes with a salience below this fraction of the strongest note are ignored.
+    pub threshold: f32,
+    pub max_notes: usize,
+}
+
+impl Default for TranscriptionParameters {
+    fn default() -> Self {
+        TranscriptionParameters {
+            lowest_note: ChromaticNote::new(ChromaticTone::C, 2),
+            highest_note: ChromaticNote::new(ChromaticTone::C, 7),
+            harmonics: 8,
+            harmonic_weight: 0.85,
+            threshold: 0.25,
+            max_notes: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedNote {
+    pub note: ChromaticNote,
+    /// Weighted sum of the harmonics when the note was detected.
+    pub salience: f32,
+}
+
+/// Detect the notes sounding in the samples, ordered from low to high.
+///
+/// The buffer should be long enough to separate neighbouring semitones of the lowest note,
+/// 8192 samples at 44.1kHz separates notes from C3 and higher.
+pub fn detect_notes(
+    samples: &[f32],
+    sample_rate: f32,
+    parameters: &TranscriptionParameters,
+) -> Vec<DetectedNote> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let mut spectrum = magnitude_spectrum(samples);
+    let bin_frequency = sample_rate / (2 * (spectrum.len() - 1)) as f32;
+    let candidates = (i32::from(parameters.lowest_note)..=i32::from(parameters.highest_note))
+        .map(ChromaticNote::from)
+        .collect::<Vec<ChromaticNote>>();
+
+    let mut result = Vec::<DetectedNote>::new();
+    let mut first_salience = None;
+    while result.len() < parameters.max_notes {
+        let best = candidates
+            .iter()
+            .filter(|candidate| !result.iter().any(|detected| detected.note == **candidate))
+            .map(|candidate| {
+                let peaks = harmonic_peaks(&spectrum, candidate.pitch(), bin_frequency, parameters);
+                (
+                    *candidate,
+                    salience(&peaks, parameters.harmonic_weight),
+                    peaks,
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((note, salience, peaks)) = best else {
+            break;
+        };
+        let first_salience = *first_salience.get_or_insert(salience);
+        if salience <= f32::EPSILON || salience < first_salience * parameters.threshold {
+            break;
+        }
+        remove_harmonics(&mut spectrum, note.pitch(), bin_frequency, &peaks);
+        result.push(DetectedNote { note, salience });
+    }
+    result.sort_by_key(|detected| i32::from(detected.note));
+    result
+}
+
+/// Magnitudes of the windowed and zero padded samples.
+fn magnitude_spectrum(samples: &[f32]) -> Vec<f32> {
+    // Zero padding interpolates the spectrum, so peaks are found closer to their maximum.
+    let fft_len = (samples.len() * 4).next_power_of_two();
+    let window = WindowFunction::Hann.create(samples.len());
+    let mut padded = vec![0.0; fft_len];
+    for (padded, (sample, factor)) in padded.iter_mut().zip(samples.iter().zip(window)) {
+        *padded = sample * factor;
+    }
+    RealFft::new(fft_len)
+        .forward(&padded)
+        .iter()
+        .map(|bin| bin.amplitude())
+        .collect()
+}
+
+/// Bins within half a semitone of the frequency.
+fn bin_range(spectrum_len: usize, frequency: f32, bin_frequency: f32) -> std::ops::Range<usize> {
+    let start = (frequency / HALF_SEMITONE / bin_frequency).ceil() as usize;
+    let end = ((frequency * HALF_SEMITONE / bin_frequency).floor() as usize + 1).min(spectrum_len);
+    start.min(end)..end
+}
+
+/// Highest magnitude around each harmonic.
+fn harmonic_peaks(
+    spectrum: &[f32],
+    pitch: f32,
+    bin_frequency: f32,
+    parameters: &TranscriptionParameters,
+) -> Vec<f32> {
+    (1..=parameters.harmonics)
+        .map(|harmonic| {
+            spectrum[bin_range(spectrum.len(), pitch * harmonic as f32, bin_frequency)]
+                .iter()
+                .copied()
+                .fold(0.0, f32::max)
+        })
+        .collect()
+}
+
+fn salience(peaks: &[f32], harmonic_weight: f32) -> f32 {
+    let loudest = peaks.iter().copied().fold(0.0, f32::max);
+    if peaks
+        .first()
+        .is_none_or(|fundamental| *fundamental < loudest * MIN_FUNDAMENTAL)
+    {
+        return 0.0;
+    }
+    let mut weight = 1.0;
+    peaks
+        .iter()
+        .map(|peak| {
+            let result = peak * weight;
+            weight *= harmonic_weight;
+            result
+        })
+        .sum()
+}
+
+/// Remove the part of each harmonic that is expected to belong to the note.
+///
+/// A harmonic that is much louder than its neighbours is probably shared with another
+/// note, only the smoothed amplitude is removed.
+fn remove_harmonics(spectrum: &mut [f32], pitch: f32, bin_frequency: f32, peaks: &[f32]) {
+    for (index, peak) in peaks.iter().enumerate() {
+        if *peak <= 0.0 {
+            continue;
+        }
+        let neighbours = &peaks[index.saturating_sub(1)..(index + 2).min(peaks.len())];
+        let smoothed = (neighbours.iter().sum::<f32>() / neighbours.len() as f32).min(*peak);
+        let factor = 1.0 - smoothed / peak;
+        let harmonic = pitch * (index + 1) as f32;
+        let range = bin_range(spectrum.len(), harmonic, bin_frequency);
+        for bin in &mut spectrum[range] {
+            *bin *= factor;
+        }
+    }
+}
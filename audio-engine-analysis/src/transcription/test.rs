@@ -0,0 +1,92 @@
+use std::f32::consts::TAU;
+
+use audio_engine_notes::{ChromaticChordType, ChromaticNote, ChromaticTone};
+
+use crate::chord::detect_chord;
+
+use super::{detect_notes, TranscriptionParameters};
+
+const SAMPLE_RATE: f32 = 44100.0;
+
+/// Notes with decaying harmonics, like a simple string instrument.
+fn render(notes: &[ChromaticNote]) -> Vec<f32> {
+    (0..8192)
+        .map(|index| {
+            let time = index as f32 / SAMPLE_RATE;
+            notes
+                .iter()
+                .flat_map(|note| {
+                    (1..=6).map(move |harmonic| {
+                        (TAU * note.pitch() * harmonic as f32 * time).sin() / harmonic as f32
+                    })
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn detected(notes: &[ChromaticNote]) -> Vec<ChromaticNote> {
+    detect_notes(
+        &render(notes),
+        SAMPLE_RATE,
+        &TranscriptionParameters::default(),
+    )
+    .iter()
+    .map(|detected| detected.note)
+    .collect()
+}
+
+#[test]
+fn single_note() {
+    let note = ChromaticNote::new(ChromaticTone::A, 3);
+    assert_eq!(detected(&[note]), vec![note]);
+}
+
+#[test]
+fn triads() {
+    for (root, chord_type) in [
+        (
+            ChromaticNote::new(ChromaticTone::C, 4),
+            ChromaticChordType::Major,
+        ),
+        (
+            ChromaticNote::new(ChromaticTone::A, 3),
+            ChromaticChordType::Minor,
+        ),
+        (
+            ChromaticNote::new(ChromaticTone::G, 3),
+            ChromaticChordType::Seventh,
+        ),
+    ] {
+        let notes = chord_type.notes(root);
+        assert_eq!(detected(&notes), notes, "{chord_type:?}");
+
+        let chord = detect_chord(
+            &render(&notes),
+            SAMPLE_RATE,
+            &TranscriptionParameters::default(),
+        )
+        .unwrap();
+        assert_eq!(chord.root, root.tone);
+        assert_eq!(chord.chord_type, chord_type);
+    }
+}
+
+#[test]
+fn octave_is_two_notes() {
+    let notes = [
+        ChromaticNote::new(ChromaticTone::C, 3),
+        ChromaticNote::new(ChromaticTone::C, 4),
+    ];
+    assert_eq!(detected(&notes), notes);
+}
+
+#[test]
+fn silence() {
+    assert!(detect_notes(
+        &[0.0; 8192],
+        SAMPLE_RATE,
+        &TranscriptionParameters::default()
+    )
+    .is_empty());
+}
@@ -0,0 +1,56 @@
+//! Convert the samples of a (.wav) file to floats.
+use wav::BitDepth;
+
+pub trait Convert2F32 {
+    /// Samples scaled to -1.0..1.0, channels stay interleaved.
+    fn to_f32s(&self) -> Vec<f32>;
+}
+
+impl Convert2F32 for BitDepth {
+    /// 8 bit samples are unsigned and centered around 128, the other integer depths are
+    /// signed. Integers are divided by the magnitude of their most negative value, so
+    /// the most negative sample becomes -1.0.
+    fn to_f32s(&self) -> Vec<f32> {
+        match self {
+            BitDepth::Eight(data) => data
+                .iter()
+                .map(|sample| (*sample as f32 - 128.0) / 128.0)
+                .collect(),
+            BitDepth::Sixteen(data) => data.iter().map(|sample| *sample as f32 / 32768.0).collect(),
+            BitDepth::TwentyFour(data) => data
+                .iter()
+                .map(|sample| *sample as f32 / 8388608.0)
+                .collect(),
+            BitDepth::ThirtyTwoFloat(data) => data.clone(),
+            BitDepth::Empty => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use wav::BitDepth;
+
+    use super::Convert2F32;
+
+    #[test]
+    fn scaling() {
+        assert_eq!(
+            vec![-1.0, 0.0, 0.5],
+            BitDepth::Eight(vec![0, 128, 192]).to_f32s()
+        );
+        assert_eq!(
+            vec![-1.0, 0.0, 0.5],
+            BitDepth::Sixteen(vec![-32768, 0, 16384]).to_f32s()
+        );
+        assert_eq!(
+            vec![-1.0, 0.0, 0.5],
+            BitDepth::TwentyFour(vec![-8388608, 0, 4194304]).to_f32s()
+        );
+        assert_eq!(
+            vec![-1.0, 0.25],
+            BitDepth::ThirtyTwoFloat(vec![-1.0, 0.25]).to_f32s()
+        );
+        assert!(BitDepth::Empty.to_f32s().is_empty());
+    }
+}
@@ -34,7 +34,7 @@ impl Default for DelayAttackDecaySustainRelease {
 impl Envelope for DelayAttackDecaySustainRelease {
     fn level(&self, note_time: NoteTime, note_off: Option<NoteTime>) -> Level {
         if let Some(note_off) = note_off {
-            if note_time < note_off {
+            if note_time >= note_off {
                 let value = self.level(note_off, None);
                 let interp = (note_time - note_off) / self.release;
                 return (value * (1.0 - interp)).max(0.0);
@@ -53,3 +53,46 @@ impl Envelope for DelayAttackDecaySustainRelease {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::envelope::Envelope;
+
+    use super::DelayAttackDecaySustainRelease;
+
+    fn adsr() -> DelayAttackDecaySustainRelease {
+        DelayAttackDecaySustainRelease {
+            delay: 0.0,
+            attack: 1.0,
+            decay: 1.0,
+            sustain: 0.5,
+            release: 2.0,
+        }
+    }
+
+    #[test]
+    fn note_on() {
+        let envelope = adsr();
+        assert_eq!(envelope.level(0.5, Some(10.0)), 0.5);
+        assert_eq!(envelope.level(1.5, Some(10.0)), 0.75);
+        assert_eq!(envelope.level(9.0, Some(10.0)), 0.5);
+    }
+
+    #[test]
+    fn release() {
+        let envelope = adsr();
+        assert_eq!(envelope.level(10.0, Some(10.0)), 0.5);
+        assert_eq!(envelope.level(11.0, Some(10.0)), 0.25);
+        assert_eq!(envelope.level(13.0, Some(10.0)), 0.0);
+        // Releasing during the attack starts from the level at note off.
+        assert_eq!(envelope.level(1.5, Some(0.5)), 0.25);
+    }
+
+    #[test]
+    fn release_without_release_time() {
+        let envelope = DelayAttackDecaySustainRelease::default();
+        assert_eq!(envelope.level(1.0, Some(2.0)), 1.0);
+        assert_eq!(envelope.level(2.0, Some(2.0)), 0.0);
+        assert_eq!(envelope.level(3.0, Some(2.0)), 0.0);
+    }
+}
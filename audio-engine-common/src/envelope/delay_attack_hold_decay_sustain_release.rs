@@ -37,7 +37,7 @@ impl Default for DelayAttackHoldDecaySustainRelease {
 impl Envelope for DelayAttackHoldDecaySustainRelease {
     fn level(&self, note_time: NoteTime, note_off: Option<NoteTime>) -> Level {
         if let Some(note_off) = note_off {
-            if note_time < note_off {
+            if note_time >= note_off {
                 let value = self.level(note_off, None);
                 let interp = (note_time - note_off) / self.release;
                 return (value * (1.0 - interp)).max(0.0);
@@ -58,3 +58,48 @@ impl Envelope for DelayAttackHoldDecaySustainRelease {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::envelope::Envelope;
+
+    use super::DelayAttackHoldDecaySustainRelease;
+
+    fn ahdsr() -> DelayAttackHoldDecaySustainRelease {
+        DelayAttackHoldDecaySustainRelease {
+            delay: 0.0,
+            attack: 1.0,
+            hold: 1.0,
+            decay: 1.0,
+            sustain: 0.5,
+            release: 2.0,
+        }
+    }
+
+    #[test]
+    fn note_on() {
+        let envelope = ahdsr();
+        assert_eq!(envelope.level(0.5, Some(10.0)), 0.5);
+        assert_eq!(envelope.level(1.5, Some(10.0)), 1.0);
+        assert_eq!(envelope.level(2.5, Some(10.0)), 0.75);
+        assert_eq!(envelope.level(9.0, Some(10.0)), 0.5);
+    }
+
+    #[test]
+    fn release() {
+        let envelope = ahdsr();
+        assert_eq!(envelope.level(10.0, Some(10.0)), 0.5);
+        assert_eq!(envelope.level(11.0, Some(10.0)), 0.25);
+        assert_eq!(envelope.level(13.0, Some(10.0)), 0.0);
+        // Releasing during the hold starts from the maximum level.
+        assert_eq!(envelope.level(2.5, Some(1.5)), 0.5);
+    }
+
+    #[test]
+    fn release_without_release_time() {
+        let envelope = DelayAttackHoldDecaySustainRelease::default();
+        assert_eq!(envelope.level(1.0, Some(2.0)), 1.0);
+        assert_eq!(envelope.level(2.0, Some(2.0)), 0.0);
+        assert_eq!(envelope.level(3.0, Some(2.0)), 0.0);
+    }
+}
@@ -2,6 +2,7 @@ use crate::{level::Level, note_time::NoteTime};
 
 pub mod delay_attack_decay_sustain_release;
 pub mod delay_attack_hold_decay_sustain_release;
+pub mod multi_segment;
 pub mod trapezoid;
 
 pub trait Envelope {
@@ -0,0 +1,243 @@
+//! Envelope with any number of curved segments.
+use crate::{level::Level, note_time::NoteTime};
+
+use super::Envelope;
+
+/// Shape of a segment between two levels.
+///
+/// ```txt
+/// Linear     Exponential(k)  Logarithmic(k)
+///      /           ___               /
+///    /           /                  |
+///  /            |              ___/
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Curve {
+    #[default]
+    Linear,
+    /// Fast change at the start of the segment that slows down towards the end, like a
+    /// natural decay. The parameter controls the steepness and should be positive.
+    Exponential(f32),
+    /// Slow change at the start of the segment that speeds up towards the end. The
+    /// parameter controls the steepness and should be positive.
+    Logarithmic(f32),
+}
+
+impl Curve {
+    /// Map the relative time in a segment (`0.0..=1.0`) to the relative level.
+    pub fn apply(&self, time: f32) -> f32 {
+        match self {
+            Curve::Linear => time,
+            Curve::Exponential(steepness) if *steepness > 0.0 => {
+                (1.0 - (-steepness * time).exp()) / (1.0 - (-steepness).exp())
+            }
+            Curve::Logarithmic(steepness) if *steepness > 0.0 => {
+                ((steepness * time).exp() - 1.0) / (steepness.exp() - 1.0)
+            }
+            _ => time,
+        }
+    }
+}
+
+/// Part of an envelope that goes from the level of the previous segment to its own level.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Segment {
+    /// Duration of the segment.
+    pub time: NoteTime,
+    /// Level at the end of the segment.
+    pub level: Level,
+    pub curve: Curve,
+}
+
+/// Envelope with any number of segments.
+///
+/// ```txt
+///        1
+///       / \   loop
+///      /   2---3  <- sustain
+///     /         \
+///    /           4
+///   /             \
+///  S               5
+///
+///  <-- note on --> <- note off ->
+/// ```
+///
+/// Segments are played in order starting from the `start` level. When the end of the
+/// segment at the `sustain` index is reached, the level is held until the note is released.
+/// When a `loop_start` is given, the segments from the loop start up to the sustain segment
+/// are repeated instead of holding the level.
+///
+/// Segments after the sustain segment form the release. The release starts from the level
+/// that the envelope had when the note was released, also when the note is released
+/// before the sustain segment was reached.
+///
+/// Without a sustain point the envelope is a one-shot that ignores the release of the note.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSegment {
+    /// Level at the start of the note.
+    pub start: Level,
+    pub segments: Vec<Segment>,
+    /// Index of the segment whose level is held until the note is released.
+    pub sustain: Option<usize>,
+    /// Index of the first segment to repeat while the note is held.
+    pub loop_start: Option<usize>,
+}
+
+impl Default for MultiSegment {
+    fn default() -> Self {
+        MultiSegment {
+            start: 0.0,
+            segments: vec![
+                Segment {
+                    time: 0.01,
+                    level: 1.0,
+                    curve: Curve::Linear,
+                },
+                Segment {
+                    time: 0.2,
+                    level: 0.0,
+                    curve: Curve::Exponential(4.0),
+                },
+            ],
+            sustain: Some(0),
+            loop_start: None,
+        }
+    }
+}
+
+impl MultiSegment {
+    /// Level of the envelope while the note isn't released.
+    fn level_before_release(&self, note_time: NoteTime) -> Level {
+        let segments_before_release = match self.sustain {
+            Some(sustain) => &self.segments[..(sustain + 1).min(self.segments.len())],
+            None => &self.segments[..],
+        };
+        let (level, time_left) = play_segments(self.start, segments_before_release, note_time);
+        if time_left.is_none() {
+            return level;
+        }
+
+        if let (Some(sustain), Some(loop_start)) = (self.sustain, self.loop_start) {
+            if loop_start <= sustain && sustain < self.segments.len() {
+                let loop_segments = &self.segments[loop_start..=sustain];
+                let loop_duration = loop_segments
+                    .iter()
+                    .map(|segment| segment.time)
+                    .sum::<NoteTime>();
+                if loop_duration > 0.0 {
+                    let loop_time = time_left.unwrap_or_default() % loop_duration;
+                    return play_segments(level, loop_segments, loop_time).0;
+                }
+            }
+        }
+        level
+    }
+}
+
+/// Play the segments starting from the given level.
+///
+/// Returns the level at the given time and the time left when all segments have been played.
+fn play_segments(start: Level, segments: &[Segment], time: NoteTime) -> (Level, Option<NoteTime>) {
+    let mut level = start;
+    let mut time = time;
+    for segment in segments {
+        if time < segment.time {
+            let interp = segment.curve.apply(time / segment.time);
+            return (level + (segment.level - level) * interp, None);
+        }
+        time -= segment.time;
+        level = segment.level;
+    }
+    (level, Some(time))
+}
+
+impl Envelope for MultiSegment {
+    fn level(&self, note_time: NoteTime, note_off: Option<NoteTime>) -> Level {
+        match (note_off, self.sustain) {
+            (Some(note_off), Some(sustain)) if note_time >= note_off => {
+                let release_level = self.level_before_release(note_off);
+                let release_segments = self.segments.get(sustain + 1..).unwrap_or_default();
+                play_segments(release_level, release_segments, note_time - note_off).0
+            }
+            _ => self.level_before_release(note_time),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::envelope::Envelope;
+
+    use super::{Curve, MultiSegment, Segment};
+
+    fn linear(time: f32, level: f32) -> Segment {
+        Segment {
+            time,
+            level,
+            curve: Curve::Linear,
+        }
+    }
+
+    fn adsr() -> MultiSegment {
+        MultiSegment {
+            start: 0.0,
+            segments: vec![linear(1.0, 1.0), linear(1.0, 0.5), linear(2.0, 0.0)],
+            sustain: Some(1),
+            loop_start: None,
+        }
+    }
+
+    #[test]
+    fn sustain_and_release() {
+        let envelope = adsr();
+        assert_eq!(envelope.level(0.5, None), 0.5);
+        assert_eq!(envelope.level(1.5, None), 0.75);
+        assert_eq!(envelope.level(10.0, None), 0.5);
+        assert_eq!(envelope.level(11.0, Some(10.0)), 0.25);
+        assert_eq!(envelope.level(13.0, Some(10.0)), 0.0);
+    }
+
+    #[test]
+    fn release_from_current_level() {
+        let envelope = adsr();
+        // Released during the attack at level 0.5.
+        assert_eq!(envelope.level(0.5, Some(0.5)), 0.5);
+        assert_eq!(envelope.level(1.5, Some(0.5)), 0.25);
+        assert_eq!(envelope.level(2.5, Some(0.5)), 0.0);
+    }
+
+    #[test]
+    fn loop_until_release() {
+        let envelope = MultiSegment {
+            loop_start: Some(0),
+            ..adsr()
+        };
+        // Loop goes from 0.5 to 1.0 and back to 0.5.
+        assert_eq!(envelope.level(2.0, None), 0.5);
+        assert_eq!(envelope.level(2.5, None), 0.75);
+        assert_eq!(envelope.level(3.5, None), 0.75);
+        assert_eq!(envelope.level(3.5, Some(2.5)), 0.375);
+    }
+
+    #[test]
+    fn one_shot() {
+        let envelope = MultiSegment {
+            sustain: None,
+            ..adsr()
+        };
+        assert_eq!(envelope.level(1.5, Some(0.5)), 0.75);
+        assert_eq!(envelope.level(3.0, None), 0.25);
+        assert_eq!(envelope.level(5.0, None), 0.0);
+    }
+
+    #[test]
+    fn curves() {
+        for curve in [Curve::Exponential(4.0), Curve::Logarithmic(4.0)] {
+            assert!(curve.apply(0.0).abs() < 1e-6);
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-6);
+        }
+        assert!(Curve::Exponential(4.0).apply(0.5) > 0.5);
+        assert!(Curve::Logarithmic(4.0).apply(0.5) < 0.5);
+    }
+}
@@ -0,0 +1,167 @@
+//! Low frequency oscillator.
+//!
+//! A low frequency oscillator (lfo) produces a slow periodic signal that is used to
+//! modulate parameters of instruments and effects, for example vibrato (pitch), tremolo
+//! (gain), operator levels or filter cutoff frequencies.
+//!
+//! The lfo implements [Sound] so it follows the same state model as any other sound.
+//! Every note gets its own [LfoState] and sampling with the same parameters will always
+//! lead to the same result.
+use crate::{
+    digital_sound::{parameters::NoteParameters, sound::Sound, sound_state::SoundState},
+    level::Level,
+    note_time::NoteTime,
+    phase_time::PhaseTime,
+    waveform::{state::WaveformState, Waveform},
+};
+
+use self::rate::LfoRate;
+
+pub mod rate;
+pub mod target;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Lfo {
+    /// Shape of the oscillator.
+    pub waveform: Waveform,
+    pub rate: LfoRate,
+    /// Maximum output of the oscillator. See [target::LfoTarget] for the unit.
+    pub depth: Level,
+    /// Phase of the oscillator at the start of the note.
+    pub phase: PhaseTime,
+    /// Time after the start of the note before the oscillator becomes audible.
+    pub delay: NoteTime,
+    /// Time to fade the oscillator in after the delay.
+    pub fade_in: NoteTime,
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Lfo {
+            waveform: Waveform::Sine,
+            rate: LfoRate::default(),
+            depth: 0.0,
+            phase: PhaseTime::default(),
+            delay: 0.0,
+            fade_in: 0.0,
+        }
+    }
+}
+
+impl Lfo {
+    /// Level of the delay and fade in at the given note time.
+    fn fade_level(&self, note_time: NoteTime) -> Level {
+        if note_time < self.delay {
+            0.0
+        } else if note_time < self.delay + self.fade_in {
+            (note_time - self.delay) / self.fade_in
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct LfoState {
+    pub waveform: WaveformState,
+}
+
+impl SoundState for LfoState {}
+
+impl Sound for Lfo {
+    type SoundState = LfoState;
+    type Parameters = NoteParameters;
+
+    fn init_sound_state(&self) -> Self::SoundState {
+        LfoState {
+            waveform: WaveformState {
+                phase_time: self.phase,
+            },
+        }
+    }
+
+    /// Sample the oscillator. Only the note time and sample rate of the parameters are used.
+    fn sample(&self, parameters: &Self::Parameters, state: &mut Self::SoundState) -> f32 {
+        self.waveform.sample(
+            &NoteParameters {
+                note_time: parameters.note_time,
+                note_off: parameters.note_off,
+                note_pitch: self.rate.frequency(),
+                gain: self.depth * self.fade_level(parameters.note_time),
+                sample_rate: parameters.sample_rate,
+            },
+            &mut state.waveform,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        digital_sound::{parameters::NoteParameters, sound::Sound},
+        phase_time::PhaseTime,
+        waveform::Waveform,
+    };
+
+    use super::{rate::LfoRate, target::LfoTarget, Lfo};
+
+    fn sample_lfo(lfo: &Lfo, sample_rate: f32, num_samples: usize) -> Vec<f32> {
+        let mut state = lfo.init_sound_state();
+        (0..num_samples)
+            .map(|index| {
+                lfo.sample(
+                    &NoteParameters {
+                        note_time: index as f32 / sample_rate,
+                        note_off: None,
+                        note_pitch: 440.0,
+                        gain: 1.0,
+                        sample_rate,
+                    },
+                    &mut state,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn synced_rate() {
+        assert_eq!(LfoRate::Synced(120.0, 1.0).frequency(), 2.0);
+        assert_eq!(LfoRate::Synced(120.0, 4.0).frequency(), 0.5);
+    }
+
+    #[test]
+    fn square_with_phase() {
+        let lfo = Lfo {
+            waveform: Waveform::Square,
+            rate: LfoRate::Hertz(1.0),
+            depth: 0.5,
+            phase: PhaseTime { time: 0.5 },
+            ..Lfo::default()
+        };
+        assert_eq!(sample_lfo(&lfo, 4.0, 4), vec![0.5, 0.5, -0.5, -0.5]);
+    }
+
+    #[test]
+    fn delay_and_fade_in() {
+        let lfo = Lfo {
+            waveform: Waveform::Square,
+            rate: LfoRate::Hertz(0.0),
+            depth: 1.0,
+            delay: 0.5,
+            fade_in: 0.5,
+            ..Lfo::default()
+        };
+        assert_eq!(
+            sample_lfo(&lfo, 4.0, 6),
+            vec![0.0, 0.0, -0.0, -0.5, -1.0, -1.0]
+        );
+    }
+
+    #[test]
+    fn targets() {
+        assert_eq!(LfoTarget::Pitch.apply(440.0, 12.0), 880.0);
+        assert_eq!(LfoTarget::Amplitude.apply(0.5, -0.5), 0.25);
+        assert_eq!(LfoTarget::Cutoff.apply(1000.0, -1.0), 500.0);
+        assert_eq!(LfoTarget::Linear.apply(0.5, 0.25), 0.75);
+    }
+}
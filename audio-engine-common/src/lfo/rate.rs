@@ -0,0 +1,35 @@
+//! Rate of a low frequency oscillator.
+use crate::beats_per_minute::BeatsPerMinute;
+
+/// How fast an [super::Lfo] cycles.
+#[derive(Debug, Copy, Clone)]
+pub enum LfoRate {
+    /// Number of cycles per second.
+    Hertz(f32),
+    /// Synced to a tempo. A single cycle takes the given number of beats.
+    ///
+    /// `LfoRate::Synced(120.0, 0.5)` cycles twice per beat at 120 beats per minute.
+    Synced(BeatsPerMinute, f32),
+}
+
+impl Default for LfoRate {
+    fn default() -> Self {
+        LfoRate::Hertz(5.0)
+    }
+}
+
+impl LfoRate {
+    /// Frequency of the oscillator in Hz.
+    pub fn frequency(&self) -> f32 {
+        match self {
+            LfoRate::Hertz(frequency) => *frequency,
+            LfoRate::Synced(beats_per_minute, beats) => {
+                if *beats == 0.0 {
+                    0.0
+                } else {
+                    beats_per_minute / 60.0 / beats
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! How the output of a low frequency oscillator is applied to a parameter.
+
+/// Scale that is used when applying the output of an [super::Lfo] to a parameter.
+///
+/// The output of an lfo is bipolar and in the range of `-depth..=depth`. Each target
+/// interprets the depth in the unit that is common for that kind of parameter.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum LfoTarget {
+    /// Depth is in semitones (vibrato).
+    #[default]
+    Pitch,
+    /// Depth is relative to the current value (tremolo). Use it for gains and operator levels.
+    Amplitude,
+    /// Depth is in octaves. Use it for filter cutoff frequencies.
+    Cutoff,
+    /// Depth is added as is. Use it for any other parameter.
+    Linear,
+}
+
+impl LfoTarget {
+    /// Apply the modulation to the given value.
+    pub fn apply(&self, value: f32, modulation: f32) -> f32 {
+        match self {
+            LfoTarget::Pitch => value * (modulation / 12.0).exp2(),
+            LfoTarget::Amplitude => value * (1.0 + modulation).max(0.0),
+            LfoTarget::Cutoff => value * modulation.exp2(),
+            LfoTarget::Linear => value + modulation,
+        }
+    }
+}
@@ -1,11 +1,14 @@
 pub mod beats_per_minute;
 pub mod buffer;
+pub mod convert_f32s;
 pub mod convolve;
 pub mod digital_sound;
 pub mod duration;
 pub mod envelope;
 pub mod id;
 pub mod level;
+pub mod lfo;
+pub mod modulation;
 pub mod note_time;
 pub mod phase_time;
 pub mod song_time;
@@ -0,0 +1,26 @@
+//! Destinations of a modulation matrix.
+
+/// Name of the parameter to modulate the pitch of a note. Amount is in semitones.
+pub const PITCH: &str = "pitch";
+/// Name of the parameter to modulate the gain of a note. Amount is relative to the gain.
+pub const GAIN: &str = "gain";
+
+/// Instrument or effect that has parameters that can be modulated by name.
+///
+/// Modulation is stored in the state of the destination. The destination itself isn't
+/// changed so the same instrument can be shared between notes.
+pub trait ModulationDestination {
+    type State;
+
+    /// Names of the parameters that can be modulated.
+    fn parameter_names(&self) -> Vec<String>;
+
+    /// Remove all modulation from the state. Called every sample before the routes are
+    /// applied.
+    fn clear_modulation(&self, state: &mut Self::State);
+
+    /// Add modulation to the named parameter.
+    ///
+    /// Returns false when the destination doesn't have a parameter with the given name.
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool;
+}
@@ -0,0 +1,280 @@
+//! Modulation matrix.
+//!
+//! A modulation matrix routes sources (lfos, envelopes, velocity and key tracking) to
+//! named parameters of an instrument or effect. Routes are plain data so patches can be
+//! build without adding new types per instrument.
+//!
+//! ```
+//! use audio_engine_common::{
+//!     envelope::trapezoid::Trapezoid,
+//!     lfo::Lfo,
+//!     modulation::{
+//!         destination::PITCH,
+//!         source::{ModulationSource, Polarity},
+//!         ModulationMatrix, ModulationRoute,
+//!     },
+//! };
+//!
+//! // Vibrato of a quarter semitone.
+//! let matrix = ModulationMatrix::<Trapezoid> {
+//!     lfos: vec![Lfo {
+//!         depth: 1.0,
+//!         ..Lfo::default()
+//!     }],
+//!     envelopes: vec![],
+//!     routes: vec![ModulationRoute {
+//!         source: ModulationSource::Lfo(0),
+//!         parameter: String::from(PITCH),
+//!         amount: 0.25,
+//!         polarity: Polarity::Bipolar,
+//!     }],
+//! };
+//! ```
+use crate::{
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    envelope::Envelope,
+    lfo::{target::LfoTarget, Lfo, LfoState},
+};
+
+use self::{
+    destination::{ModulationDestination, GAIN, PITCH},
+    source::{ModulationSource, Polarity},
+};
+
+pub mod destination;
+pub mod source;
+
+/// Pitch of C4 used as center of [ModulationSource::KeyTrack].
+const KEY_TRACK_CENTER_PITCH: f32 = 261.62558;
+
+/// Connection between a source and a parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModulationRoute {
+    pub source: ModulationSource,
+    /// Name of the parameter to modulate. See [destination] for the parameters that
+    /// every instrument has.
+    pub parameter: String,
+    /// Value of the source is multiplied by the amount before it is applied.
+    pub amount: f32,
+    pub polarity: Polarity,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModulationMatrix<E>
+where
+    E: Envelope,
+{
+    pub lfos: Vec<Lfo>,
+    pub envelopes: Vec<E>,
+    pub routes: Vec<ModulationRoute>,
+}
+
+impl<E> Default for ModulationMatrix<E>
+where
+    E: Envelope,
+{
+    fn default() -> Self {
+        ModulationMatrix {
+            lfos: Vec::default(),
+            envelopes: Vec::default(),
+            routes: Vec::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ModulationMatrixState {
+    pub lfos: Vec<LfoState>,
+    /// Output of each lfo of the current sample.
+    pub lfo_values: Vec<f32>,
+}
+
+impl<E> ModulationMatrix<E>
+where
+    E: Envelope,
+{
+    pub fn init_state(&self) -> ModulationMatrixState {
+        ModulationMatrixState {
+            lfos: self.lfos.iter().map(|lfo| lfo.init_sound_state()).collect(),
+            lfo_values: vec![0.0; self.lfos.len()],
+        }
+    }
+
+    /// Parameters that are routed to, but don't exist in the destination.
+    pub fn unknown_parameters<D>(&self, destination: &D) -> Vec<String>
+    where
+        D: ModulationDestination,
+    {
+        let names = destination.parameter_names();
+        self.routes
+            .iter()
+            .map(|route| &route.parameter)
+            .filter(|parameter| {
+                parameter.as_str() != PITCH
+                    && parameter.as_str() != GAIN
+                    && !names.contains(parameter)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Evaluate all routes of the current sample and apply them.
+    ///
+    /// Routes to [PITCH] and [GAIN] are applied to the returned note parameters, other
+    /// routes are applied to the state of the destination.
+    pub fn apply<D>(
+        &self,
+        parameters: &NoteParameters,
+        state: &mut ModulationMatrixState,
+        destination: &D,
+        destination_state: &mut D::State,
+    ) -> NoteParameters
+    where
+        D: ModulationDestination,
+    {
+        for ((lfo, lfo_state), value) in self
+            .lfos
+            .iter()
+            .zip(state.lfos.iter_mut())
+            .zip(state.lfo_values.iter_mut())
+        {
+            *value = lfo.sample(parameters, lfo_state);
+        }
+
+        destination.clear_modulation(destination_state);
+        let mut note_pitch = parameters.note_pitch;
+        let mut gain = parameters.gain;
+        for route in &self.routes {
+            let modulation = self.source_value(route, parameters, state) * route.amount;
+            match route.parameter.as_str() {
+                PITCH => note_pitch = LfoTarget::Pitch.apply(note_pitch, modulation),
+                GAIN => gain = LfoTarget::Amplitude.apply(gain, modulation),
+                parameter => {
+                    destination.modulate(parameter, modulation, destination_state);
+                }
+            }
+        }
+
+        NoteParameters {
+            note_time: parameters.note_time,
+            note_off: parameters.note_off,
+            note_pitch,
+            gain,
+            sample_rate: parameters.sample_rate,
+        }
+    }
+
+    fn source_value(
+        &self,
+        route: &ModulationRoute,
+        parameters: &NoteParameters,
+        state: &ModulationMatrixState,
+    ) -> f32 {
+        let value = match route.source {
+            ModulationSource::Lfo(index) => {
+                state.lfo_values.get(index as usize).copied().unwrap_or(0.0)
+            }
+            ModulationSource::Envelope(index) => {
+                self.envelopes.get(index as usize).map_or(0.0, |envelope| {
+                    envelope.level(parameters.note_time, parameters.note_off)
+                })
+            }
+            ModulationSource::Velocity => parameters.gain,
+            ModulationSource::KeyTrack => (parameters.note_pitch / KEY_TRACK_CENTER_PITCH).log2(),
+        };
+        route.polarity.convert(value, route.source.polarity())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        digital_sound::parameters::NoteParameters,
+        envelope::trapezoid::Trapezoid,
+        modulation::{
+            destination::{ModulationDestination, GAIN, PITCH},
+            source::{ModulationSource, Polarity},
+            ModulationMatrix, ModulationRoute,
+        },
+    };
+
+    struct Destination;
+    impl ModulationDestination for Destination {
+        type State = f32;
+
+        fn parameter_names(&self) -> Vec<String> {
+            vec![String::from("level")]
+        }
+
+        fn clear_modulation(&self, state: &mut Self::State) {
+            *state = 0.0;
+        }
+
+        fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+            if parameter == "level" {
+                *state += modulation;
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    fn route(source: ModulationSource, parameter: &str, amount: f32) -> ModulationRoute {
+        ModulationRoute {
+            source,
+            parameter: String::from(parameter),
+            amount,
+            polarity: source.polarity(),
+        }
+    }
+
+    #[test]
+    fn velocity_and_key_track() {
+        let matrix = ModulationMatrix::<Trapezoid> {
+            routes: vec![
+                route(ModulationSource::KeyTrack, PITCH, 12.0),
+                route(ModulationSource::Velocity, GAIN, -0.5),
+                route(ModulationSource::Velocity, "level", 2.0),
+                ModulationRoute {
+                    polarity: Polarity::Bipolar,
+                    ..route(ModulationSource::Velocity, "level", 1.0)
+                },
+            ],
+            ..ModulationMatrix::default()
+        };
+        let mut state = matrix.init_state();
+        let mut level = 0.0;
+        let result = matrix.apply(
+            &NoteParameters {
+                note_time: 0.0,
+                note_off: None,
+                note_pitch: 440.0,
+                gain: 0.5,
+                sample_rate: 44100.0,
+            },
+            &mut state,
+            &Destination,
+            &mut level,
+        );
+        assert!((result.note_pitch - 440.0 * 440.0 / 261.62558).abs() < 0.01);
+        assert_eq!(result.gain, 0.375);
+        assert_eq!(level, 1.0);
+    }
+
+    #[test]
+    fn unknown_parameters() {
+        let matrix = ModulationMatrix::<Trapezoid> {
+            routes: vec![
+                route(ModulationSource::Velocity, GAIN, 1.0),
+                route(ModulationSource::Velocity, "level", 1.0),
+                route(ModulationSource::Velocity, "cutoff", 1.0),
+            ],
+            ..ModulationMatrix::default()
+        };
+        assert_eq!(
+            matrix.unknown_parameters(&Destination),
+            vec![String::from("cutoff")]
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Sources of a modulation matrix.
+
+/// Signal that can be routed to a parameter.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ModulationSource {
+    /// Lfo with the given index in [super::ModulationMatrix::lfos]. Bipolar.
+    Lfo(u8),
+    /// Envelope with the given index in [super::ModulationMatrix::envelopes]. Unipolar.
+    Envelope(u8),
+    /// Gain the note was started with. Unipolar.
+    Velocity,
+    /// Distance of the note pitch to C4 in octaves. Bipolar.
+    KeyTrack,
+}
+
+/// Range of a source when it is applied to a parameter.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Polarity {
+    /// Source is mapped to `0.0..=1.0`.
+    #[default]
+    Unipolar,
+    /// Source is mapped to `-1.0..=1.0`.
+    Bipolar,
+}
+
+impl ModulationSource {
+    /// Polarity of the values produced by the source.
+    pub fn polarity(&self) -> Polarity {
+        match self {
+            ModulationSource::Lfo(_) | ModulationSource::KeyTrack => Polarity::Bipolar,
+            ModulationSource::Envelope(_) | ModulationSource::Velocity => Polarity::Unipolar,
+        }
+    }
+}
+
+impl Polarity {
+    /// Convert a value produced by a source with the given polarity to this polarity.
+    pub fn convert(&self, value: f32, from: Polarity) -> f32 {
+        match (from, self) {
+            (Polarity::Unipolar, Polarity::Bipolar) => value * 2.0 - 1.0,
+            (Polarity::Bipolar, Polarity::Unipolar) => (value + 1.0) * 0.5,
+            _ => value,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use audio_engine_discrete_time::{
+    biquad::{Biquad, BiquadType},
+    bode_plot::BodePlot,
+};
+
+fn main() {
+    let sample_rate = 44100.0;
+    let plot = BodePlot::default();
+    for (filename, biquad_type) in [
+        ("bode-plot-low-pass.png", BiquadType::LowPass),
+        ("bode-plot-high-pass.png", BiquadType::HighPass),
+        ("bode-plot-band-pass.png", BiquadType::BandPass),
+        ("bode-plot-notch.png", BiquadType::Notch),
+        ("bode-plot-peak.png", BiquadType::Peak),
+        ("bode-plot-low-shelf.png", BiquadType::LowShelf),
+        ("bode-plot-high-shelf.png", BiquadType::HighShelf),
+        ("bode-plot-all-pass.png", BiquadType::AllPass),
+    ] {
+        let biquad = Biquad::with_gain(biquad_type, 1000.0, 2.0, 6.0);
+        let transfer_function = biquad.transfer_function(sample_rate);
+        println!(
+            "{filename}: stable={} poles={:?}",
+            transfer_function.is_stable(),
+            transfer_function.poles()
+        );
+        plot.export(&transfer_function, Path::new(filename))
+            .unwrap();
+    }
+}
@@ -0,0 +1,330 @@
+//! Analog (continuous time) transfer functions and their conversion to discrete time.
+//!
+//! Physical models are often described by an analog filter in the Laplace variable `s`.
+//! Three conversions to a discrete time [TransferFunction] are available:
+//!
+//! - Bilinear transform: `s = K(z - 1)/(z + 1)`. The whole frequency axis is compressed into
+//!   0..nyquist, so there is no aliasing, but frequencies are warped. With prewarping `K` is
+//!   chosen so a single frequency keeps its place.
+//! - Impulse invariance: the impulse response is sampled, `h[n] = T*h(nT)`. Keeps the
+//!   shape of the impulse response, but high frequencies alias.
+//! - Matched-Z: each pole and zero is mapped with `z = e^(sT)`. Zeros at infinity are
+//!   placed at nyquist (`z = -1`) and the gain is matched at a chosen frequency.
+use std::{
+    f64::consts::TAU,
+    fmt::Display,
+    ops::{Add, Div, Mul},
+};
+
+use crate::{
+    complex::Complex,
+    components::Components,
+    transfer_function::TransferFunction,
+    zeros_poles_gain::{expand, real_part, ZerosPolesGain},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalogTransferFunction {
+    pub numerator: Components,
+    pub denominator: Components,
+}
+
+impl AnalogTransferFunction {
+    /// The Laplace variable `s`, used to build transfer functions like `1.0 / &(&s + 1.0)`.
+    pub fn s() -> AnalogTransferFunction {
+        AnalogTransferFunction {
+            numerator: Components {
+                components: vec![0.0, 1.0],
+            },
+            denominator: Components {
+                components: vec![1.0],
+            },
+        }
+    }
+
+    /// Complex response of the transfer function at the given frequency in Hz.
+    pub fn response(&self, frequency: f64) -> Complex {
+        let s = Complex::new(0.0, TAU * frequency);
+        self.numerator.evaluate(s) / self.denominator.evaluate(s)
+    }
+
+    /// Gain in dB at the given frequency in Hz.
+    pub fn magnitude(&self, frequency: f64) -> f64 {
+        20.0 * self.response(frequency).norm().log10()
+    }
+
+    pub fn zeros_poles_gain(&self) -> ZerosPolesGain {
+        ZerosPolesGain {
+            zeros: self.numerator.roots(),
+            poles: self.denominator.roots(),
+            gain: self.numerator.components[self.numerator.degree()]
+                / self.denominator.components[self.denominator.degree()],
+        }
+    }
+
+    /// Convert with the bilinear transform, see [ZerosPolesGain::bilinear].
+    pub fn bilinear(&self, sample_rate: f64, prewarp_frequency: Option<f64>) -> TransferFunction {
+        self.zeros_poles_gain()
+            .bilinear(sample_rate, prewarp_frequency)
+            .to_transfer_function(1.0 / sample_rate)
+    }
+
+    /// Convert by sampling the impulse response, see [ZerosPolesGain::impulse_invariance].
+    pub fn impulse_invariance(&self, sample_rate: f64) -> TransferFunction {
+        self.zeros_poles_gain().impulse_invariance(sample_rate)
+    }
+
+    /// Convert by mapping the poles and zeros, see [ZerosPolesGain::matched_z].
+    pub fn matched_z(&self, sample_rate: f64, match_frequency: f64) -> TransferFunction {
+        self.zeros_poles_gain()
+            .matched_z(sample_rate, match_frequency)
+            .to_transfer_function(1.0 / sample_rate)
+    }
+}
+
+impl Display for AnalogTransferFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let numerator = self.numerator.to_string().replace('z', "s");
+        let denominator = self.denominator.to_string().replace('z', "s");
+        if denominator == "1" {
+            f.write_fmt(format_args!("f(s) = {}", numerator))
+        } else {
+            f.write_fmt(format_args!(
+                "f(s) = \\frac{{{}}}{{{}}}",
+                numerator, denominator
+            ))
+        }
+    }
+}
+
+/// Constant transfer function.
+impl From<f64> for AnalogTransferFunction {
+    fn from(value: f64) -> Self {
+        AnalogTransferFunction {
+            numerator: Components {
+                components: vec![value],
+            },
+            denominator: Components {
+                components: vec![1.0],
+            },
+        }
+    }
+}
+
+impl From<&ZerosPolesGain> for AnalogTransferFunction {
+    fn from(zeros_poles_gain: &ZerosPolesGain) -> Self {
+        AnalogTransferFunction {
+            numerator: zeros_poles_gain.numerator(),
+            denominator: zeros_poles_gain.denominator(),
+        }
+    }
+}
+
+impl ZerosPolesGain {
+    /// Complex response of the analog transfer function at the given frequency in Hz.
+    pub fn analog_response(&self, frequency: f64) -> Complex {
+        self.evaluate(Complex::new(0.0, TAU * frequency))
+    }
+
+    /// Convert the analog transfer function with the bilinear transform.
+    ///
+    /// Without prewarping `K = 2 * sample_rate`. When a prewarp frequency (in Hz) is given,
+    /// the analog response at that frequency is the discrete response at the same frequency.
+    /// Use the cutoff or center frequency of the filter.
+    pub fn bilinear(&self, sample_rate: f64, prewarp_frequency: Option<f64>) -> ZerosPolesGain {
+        let k = match prewarp_frequency {
+            Some(frequency) => {
+                let omega = TAU * frequency;
+                omega / (omega / (2.0 * sample_rate)).tan()
+            }
+            None => 2.0 * sample_rate,
+        };
+        let k = Complex::from(k);
+        let map = |root: &Complex| (k + *root) / (k - *root);
+        let product = |roots: &[Complex]| {
+            roots
+                .iter()
+                .fold(Complex::ONE, |product, root| product * (k - *root))
+        };
+
+        let mut zeros = self.zeros.iter().map(map).collect::<Vec<Complex>>();
+        zeros.resize(self.poles.len().max(zeros.len()), Complex::from(-1.0));
+        ZerosPolesGain {
+            zeros,
+            poles: self.poles.iter().map(map).collect(),
+            gain: self.gain * (product(&self.zeros) / product(&self.poles)).re,
+        }
+    }
+
+    /// Convert the analog transfer function by mapping each pole and zero with `z = e^(sT)`.
+    ///
+    /// Zeros at infinity are placed at `z = -1`. The gain is chosen so the magnitude at
+    /// `match_frequency` (in Hz) is the same as the analog magnitude; use 0 for a low pass,
+    /// nyquist for a high pass or the center frequency for a band pass.
+    pub fn matched_z(&self, sample_rate: f64, match_frequency: f64) -> ZerosPolesGain {
+        let timestep = 1.0 / sample_rate;
+        let map = |root: &Complex| (*root * timestep).exp();
+        let mut zeros = self.zeros.iter().map(map).collect::<Vec<Complex>>();
+        zeros.resize(self.poles.len().max(zeros.len()), Complex::from(-1.0));
+        let mut result = ZerosPolesGain {
+            zeros,
+            poles: self.poles.iter().map(map).collect(),
+            gain: 1.0,
+        };
+
+        let analog = self.analog_response(match_frequency);
+        let discrete = result.evaluate(Complex::from_polar(1.0, TAU * match_frequency * timestep));
+        let sign = if (analog / discrete).re < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        result.gain = sign * analog.norm() / discrete.norm();
+        result
+    }
+
+    /// Convert the analog transfer function so the impulse response is sampled:
+    /// `h[n] = T*h(nT)`.
+    ///
+    /// Each pole gets a term in the partial fraction expansion:
+    ///
+    /// ```text
+    ///  r              T*r*z
+    /// ----- -> ---------------
+    /// s - p    z - e^(p*T)
+    /// ```
+    ///
+    /// The poles must be distinct. When there are as many zeros as poles, the response has
+    /// an impulse at t=0 which is added as a constant.
+    ///
+    /// # Panics
+    ///
+    /// When there are more zeros than poles.
+    pub fn impulse_invariance(&self, sample_rate: f64) -> TransferFunction {
+        assert!(
+            self.zeros.len() <= self.poles.len(),
+            "impulse invariance needs a proper transfer function"
+        );
+        let timestep = 1.0 / sample_rate;
+        let discrete_poles = self
+            .poles
+            .iter()
+            .map(|pole| (*pole * timestep).exp())
+            .collect::<Vec<Complex>>();
+
+        let mut numerator = vec![Complex::ZERO; self.poles.len() + 1];
+        if self.zeros.len() == self.poles.len() {
+            for (sum, coefficient) in numerator.iter_mut().zip(expand(&discrete_poles)) {
+                *sum = *sum + coefficient * self.gain;
+            }
+        }
+        for (index, pole) in self.poles.iter().enumerate() {
+            let residue = self
+                .poles
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .fold(
+                    self.evaluate_zeros(*pole) * self.gain,
+                    |residue, (_, other)| residue / (*pole - *other),
+                );
+            let others = discrete_poles
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .map(|(_, other)| *other)
+                .collect::<Vec<Complex>>();
+            // Multiplied by z, so shifted one power up.
+            for (sum, coefficient) in numerator.iter_mut().skip(1).zip(expand(&others)) {
+                *sum = *sum + coefficient * residue * timestep;
+            }
+        }
+
+        TransferFunction {
+            timestep,
+            numerator: real_part(&numerator),
+            denominator: real_part(&expand(&discrete_poles)),
+        }
+    }
+
+    /// Product of `(s - zero)` for all zeros.
+    fn evaluate_zeros(&self, s: Complex) -> Complex {
+        self.zeros
+            .iter()
+            .fold(Complex::ONE, |product, zero| product * (s - *zero))
+    }
+}
+
+// #region operators
+impl Mul<f64> for &AnalogTransferFunction {
+    type Output = AnalogTransferFunction;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        AnalogTransferFunction {
+            numerator: &self.numerator * rhs,
+            denominator: self.denominator.clone(),
+        }
+    }
+}
+
+impl Mul<&AnalogTransferFunction> for f64 {
+    type Output = AnalogTransferFunction;
+
+    fn mul(self, rhs: &AnalogTransferFunction) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl Mul<&AnalogTransferFunction> for &AnalogTransferFunction {
+    type Output = AnalogTransferFunction;
+
+    fn mul(self, rhs: &AnalogTransferFunction) -> Self::Output {
+        AnalogTransferFunction {
+            numerator: &self.numerator * &rhs.numerator,
+            denominator: &self.denominator * &rhs.denominator,
+        }
+    }
+}
+
+impl Add<f64> for &AnalogTransferFunction {
+    type Output = AnalogTransferFunction;
+
+    fn add(self, rhs: f64) -> Self::Output {
+        AnalogTransferFunction {
+            numerator: &self.numerator + &(&self.denominator * rhs),
+            denominator: self.denominator.clone(),
+        }
+    }
+}
+
+impl Add<&AnalogTransferFunction> for &AnalogTransferFunction {
+    type Output = AnalogTransferFunction;
+
+    fn add(self, rhs: &AnalogTransferFunction) -> Self::Output {
+        AnalogTransferFunction {
+            numerator: &(&self.numerator * &rhs.denominator)
+                + &(&rhs.numerator * &self.denominator),
+            denominator: &self.denominator * &rhs.denominator,
+        }
+    }
+}
+
+impl Div<&AnalogTransferFunction> for f64 {
+    type Output = AnalogTransferFunction;
+
+    fn div(self, rhs: &AnalogTransferFunction) -> Self::Output {
+        &AnalogTransferFunction::from(self) / rhs
+    }
+}
+
+impl Div<&AnalogTransferFunction> for &AnalogTransferFunction {
+    type Output = AnalogTransferFunction;
+
+    fn div(self, rhs: &AnalogTransferFunction) -> Self::Output {
+        AnalogTransferFunction {
+            numerator: &self.numerator * &rhs.denominator,
+            denominator: &self.denominator * &rhs.numerator,
+        }
+    }
+}
+// #endregion
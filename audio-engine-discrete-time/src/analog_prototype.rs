@@ -0,0 +1,302 @@
+//! Analog low pass prototypes of any order.
+//!
+//! Prototypes are normalized to an angular frequency of 1 rad/s and can be moved to the
+//! wanted frequency and turned into a high pass, band pass or band stop with the frequency
+//! transformations on [ZerosPolesGain]. The result is converted to discrete time with
+//! [ZerosPolesGain::bilinear] or one of the other conversions in [crate::analog].
+//!
+//! ```text
+//! let sections = AnalogPrototype::Butterworth
+//!     .design(8)
+//!     .to_low_pass(1000.0)
+//!     .bilinear(44100.0, Some(1000.0))
+//!     .second_order_sections();
+//! ```
+//!
+//! The elliptic design follows Orfanidis, "Lecture notes on elliptic filter design"; the
+//! Jacobi elliptic functions are calculated with descending Landen transformations.
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::{complex::Complex, zeros_poles_gain::ZerosPolesGain};
+
+/// Number of Landen transformations; the modulus is below 1e-15 well before that.
+const LANDEN_ITERATIONS: usize = 8;
+/// Number of terms of the nome series used to solve the degree equation.
+const NOME_TERMS: usize = 7;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AnalogPrototype {
+    /// Maximally flat pass band. The gain at 1 rad/s is -3dB.
+    Butterworth,
+    /// Equiripple pass band with a ripple in dB, monotonic stop band. The gain at 1 rad/s is
+    /// the bottom of the ripple.
+    ChebyshevI { ripple: f64 },
+    /// Monotonic pass band, equiripple stop band at least the attenuation in dB below the
+    /// pass band. The stop band starts at 1 rad/s.
+    ChebyshevII { attenuation: f64 },
+    /// Equiripple pass band and stop band; the steepest transition for the given order. The
+    /// pass band ends at 1 rad/s.
+    Elliptic { ripple: f64, attenuation: f64 },
+}
+
+impl AnalogPrototype {
+    /// Zeros, poles and gain of the prototype with the given number of poles.
+    pub fn design(&self, order: usize) -> ZerosPolesGain {
+        match *self {
+            AnalogPrototype::Butterworth => butterworth(order),
+            AnalogPrototype::ChebyshevI { ripple } => chebyshev_1(order, ripple),
+            AnalogPrototype::ChebyshevII { attenuation } => chebyshev_2(order, attenuation),
+            AnalogPrototype::Elliptic {
+                ripple,
+                attenuation,
+            } => elliptic(order, ripple, attenuation),
+        }
+    }
+}
+
+fn butterworth(order: usize) -> ZerosPolesGain {
+    let poles = (0..order)
+        .map(|index| {
+            Complex::from_polar(
+                1.0,
+                PI * (2 * index + order + 1) as f64 / (2 * order) as f64,
+            )
+        })
+        .collect();
+    ZerosPolesGain {
+        zeros: Vec::new(),
+        poles,
+        gain: 1.0,
+    }
+}
+
+/// Angles of the poles of a Chebyshev filter.
+fn chebyshev_angles(order: usize) -> impl Iterator<Item = f64> {
+    (0..order).map(move |index| PI * (2 * index + 1) as f64 / (2 * order) as f64)
+}
+
+fn chebyshev_1(order: usize, ripple: f64) -> ZerosPolesGain {
+    let epsilon = (10.0_f64.powf(ripple / 10.0) - 1.0).sqrt();
+    let mu = (1.0 / epsilon).asinh() / order as f64;
+    let poles = chebyshev_angles(order)
+        .map(|angle| Complex::new(-mu.sinh() * angle.sin(), mu.cosh() * angle.cos()))
+        .collect();
+    with_dc_gain(Vec::new(), poles, passband_dc_gain(order, epsilon))
+}
+
+fn chebyshev_2(order: usize, attenuation: f64) -> ZerosPolesGain {
+    let epsilon = 1.0 / (10.0_f64.powf(attenuation / 10.0) - 1.0).sqrt();
+    let mu = (1.0 / epsilon).asinh() / order as f64;
+    let poles = chebyshev_angles(order)
+        .map(|angle| Complex::ONE / Complex::new(-mu.sinh() * angle.sin(), mu.cosh() * angle.cos()))
+        .collect();
+    // The middle angle of an odd order has its zero at infinity.
+    let zeros = chebyshev_angles(order)
+        .enumerate()
+        .filter(|(index, _)| order.is_multiple_of(2) || *index != order / 2)
+        .map(|(_, angle)| Complex::new(0.0, 1.0 / angle.cos()))
+        .collect();
+    with_dc_gain(zeros, poles, 1.0)
+}
+
+fn elliptic(order: usize, ripple: f64, attenuation: f64) -> ZerosPolesGain {
+    let epsilon_pass = (10.0_f64.powf(ripple / 10.0) - 1.0).sqrt();
+    let epsilon_stop = (10.0_f64.powf(attenuation / 10.0) - 1.0).sqrt();
+    let k1 = epsilon_pass / epsilon_stop;
+    let k = elliptic_degree(order, k1);
+
+    let pairs = order / 2;
+    let v0 = (-Complex::I * asne(Complex::I / epsilon_pass, k1)) / order as f64;
+    let mut zeros = Vec::new();
+    let mut poles = Vec::new();
+    for index in 1..=pairs {
+        let u = (2 * index - 1) as f64 / order as f64;
+        let zero = Complex::I / (cde(Complex::from(u), k) * k);
+        let pole = Complex::I * cde(Complex::from(u) - Complex::I * v0, k);
+        zeros.extend([zero, zero.conj()]);
+        poles.extend([pole, pole.conj()]);
+    }
+    if order % 2 == 1 {
+        let pole = Complex::I * sne(Complex::I * v0, k);
+        poles.push(Complex::from(pole.re));
+    }
+    with_dc_gain(zeros, poles, passband_dc_gain(order, epsilon_pass))
+}
+
+/// An odd order starts the pass band at the top of the ripple, an even order at the bottom.
+fn passband_dc_gain(order: usize, epsilon: f64) -> f64 {
+    if order % 2 == 1 {
+        1.0
+    } else {
+        1.0 / (1.0 + epsilon * epsilon).sqrt()
+    }
+}
+
+/// Choose the gain so the response at s=0 is `dc_gain`.
+fn with_dc_gain(zeros: Vec<Complex>, poles: Vec<Complex>, dc_gain: f64) -> ZerosPolesGain {
+    let mut result = ZerosPolesGain {
+        zeros,
+        poles,
+        gain: 1.0,
+    };
+    result.gain = dc_gain / result.evaluate(Complex::ZERO).re;
+    result
+}
+
+/// Complete elliptic integral of the first kind K(k) and its complement K'(k) = K(k').
+fn elliptic_integrals(k: f64) -> (f64, f64) {
+    let agm = |mut a: f64, mut b: f64| {
+        while (a - b).abs() > 1e-15 * a {
+            (a, b) = ((a + b) / 2.0, (a * b).sqrt());
+        }
+        a
+    };
+    let k_complement = (1.0 - k * k).sqrt();
+    (FRAC_PI_2 / agm(1.0, k_complement), FRAC_PI_2 / agm(1.0, k))
+}
+
+/// Solve the degree equation `K'(k)/K(k) = K'(k1)/(N*K(k1))` for the selectivity k, via
+/// the nome `q = exp(-PI*K'(k)/K(k))`.
+fn elliptic_degree(order: usize, k1: f64) -> f64 {
+    let (integral, integral_complement) = elliptic_integrals(k1);
+    let q = (-PI * integral_complement / integral / order as f64).exp();
+    let numerator = (0..NOME_TERMS)
+        .map(|m| q.powi((m * (m + 1)) as i32))
+        .sum::<f64>();
+    let denominator = 1.0 + 2.0 * (1..NOME_TERMS).map(|m| q.powi((m * m) as i32)).sum::<f64>();
+    4.0 * q.sqrt() * (numerator / denominator).powi(2)
+}
+
+/// Descending Landen sequence of moduli, starting with the modulus after k.
+fn landen(k: f64) -> Vec<f64> {
+    let mut k_complement = (1.0 - k * k).sqrt();
+    (0..LANDEN_ITERATIONS)
+        .map(|_| {
+            let k = (1.0 - k_complement) / (1.0 + k_complement);
+            k_complement = (1.0 - k * k).sqrt();
+            k
+        })
+        .collect()
+}
+
+/// Ascend from the sine or cosine (modulus 0) to the Jacobi function with modulus k.
+fn ascend(mut w: Complex, k: f64) -> Complex {
+    for v in landen(k).iter().rev() {
+        w = w * (1.0 + v) / (Complex::ONE + w * w * *v);
+    }
+    w
+}
+
+/// Jacobi elliptic function `cd(u*K, k)`.
+fn cde(u: Complex, k: f64) -> Complex {
+    ascend((u * FRAC_PI_2).cos(), k)
+}
+
+/// Jacobi elliptic function `sn(u*K, k)`.
+fn sne(u: Complex, k: f64) -> Complex {
+    ascend((u * FRAC_PI_2).sin(), k)
+}
+
+/// Inverse of [sne], the result is in units of K.
+fn asne(mut w: Complex, k: f64) -> Complex {
+    let moduli = landen(k);
+    let mut previous = k;
+    for v in moduli {
+        w = w / (Complex::ONE + (Complex::ONE - w * w * (previous * previous)).sqrt())
+            * (2.0 / (1.0 + v));
+        previous = v;
+    }
+    Complex::ONE - w.acos() * (2.0 / PI)
+}
+
+/// Frequency transformations of a low pass prototype normalized to 1 rad/s. Frequencies are
+/// in Hz.
+impl ZerosPolesGain {
+    /// Low pass with the prototype's edge at the cutoff frequency.
+    pub fn to_low_pass(&self, cutoff: f64) -> ZerosPolesGain {
+        let omega = TAU * cutoff;
+        ZerosPolesGain {
+            zeros: self.zeros.iter().map(|zero| *zero * omega).collect(),
+            poles: self.poles.iter().map(|pole| *pole * omega).collect(),
+            gain: self.gain * omega.powi(self.relative_degree() as i32),
+        }
+    }
+
+    /// High pass with the prototype's edge at the cutoff frequency.
+    pub fn to_high_pass(&self, cutoff: f64) -> ZerosPolesGain {
+        let omega = Complex::from(TAU * cutoff);
+        let mut zeros = self
+            .zeros
+            .iter()
+            .map(|zero| omega / *zero)
+            .collect::<Vec<Complex>>();
+        zeros.resize(self.poles.len(), Complex::ZERO);
+        ZerosPolesGain {
+            zeros,
+            poles: self.poles.iter().map(|pole| omega / *pole).collect(),
+            gain: self.gain * (negated_product(&self.zeros) / negated_product(&self.poles)).re,
+        }
+    }
+
+    /// Band pass around the center frequency; the edges of the prototype are at the edges
+    /// of the bandwidth (geometrically around the center).
+    pub fn to_band_pass(&self, center: f64, bandwidth: f64) -> ZerosPolesGain {
+        let omega = TAU * center;
+        let half_bandwidth = TAU * bandwidth / 2.0;
+        let transform = |roots: &[Complex]| {
+            roots
+                .iter()
+                .flat_map(|root| {
+                    let scaled = *root * half_bandwidth;
+                    let offset = (scaled * scaled - Complex::from(omega * omega)).sqrt();
+                    [scaled + offset, scaled - offset]
+                })
+                .collect::<Vec<Complex>>()
+        };
+        let mut zeros = transform(&self.zeros);
+        zeros.resize(zeros.len() + self.relative_degree(), Complex::ZERO);
+        ZerosPolesGain {
+            zeros,
+            poles: transform(&self.poles),
+            gain: self.gain * (2.0 * half_bandwidth).powi(self.relative_degree() as i32),
+        }
+    }
+
+    /// Band stop around the center frequency; the edges of the prototype are at the edges
+    /// of the bandwidth (geometrically around the center).
+    pub fn to_band_stop(&self, center: f64, bandwidth: f64) -> ZerosPolesGain {
+        let omega = TAU * center;
+        let half_bandwidth = TAU * bandwidth / 2.0;
+        let transform = |roots: &[Complex]| {
+            roots
+                .iter()
+                .flat_map(|root| {
+                    let scaled = Complex::from(half_bandwidth) / *root;
+                    let offset = (scaled * scaled - Complex::from(omega * omega)).sqrt();
+                    [scaled + offset, scaled - offset]
+                })
+                .collect::<Vec<Complex>>()
+        };
+        let mut zeros = transform(&self.zeros);
+        for _ in 0..self.relative_degree() {
+            zeros.extend([Complex::new(0.0, omega), Complex::new(0.0, -omega)]);
+        }
+        ZerosPolesGain {
+            zeros,
+            poles: transform(&self.poles),
+            gain: self.gain * (negated_product(&self.zeros) / negated_product(&self.poles)).re,
+        }
+    }
+
+    /// Number of zeros at infinity.
+    fn relative_degree(&self) -> usize {
+        self.poles.len().saturating_sub(self.zeros.len())
+    }
+}
+
+/// Product of `(0 - root)`, the polynomial with the given roots evaluated at s=0.
+fn negated_product(roots: &[Complex]) -> Complex {
+    roots
+        .iter()
+        .fold(Complex::ONE, |product, root| product * -*root)
+}
@@ -0,0 +1,167 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    analog_prototype::AnalogPrototype, complex::Complex, zeros_poles_gain::ZerosPolesGain,
+};
+
+/// Gain in dB at the given angular frequency.
+fn magnitude(prototype: &ZerosPolesGain, omega: f64) -> f64 {
+    20.0 * prototype.evaluate(Complex::new(0.0, omega)).norm().log10()
+}
+
+/// Lowest and highest gain in dB between the angular frequencies.
+fn gain_range(prototype: &ZerosPolesGain, from: f64, to: f64) -> (f64, f64) {
+    (0..=1000)
+        .map(|index| from * (to / from).powf(index as f64 / 1000.0))
+        .map(|omega| magnitude(prototype, omega))
+        .fold((f64::MAX, f64::MIN), |(min, max), gain| {
+            (min.min(gain), max.max(gain))
+        })
+}
+
+fn assert_stable(prototype: &ZerosPolesGain, order: usize) {
+    assert_eq!(order, prototype.poles.len());
+    assert!(prototype.poles.iter().all(|pole| pole.re < 0.0));
+}
+
+#[test]
+fn test_butterworth() {
+    for order in 1..=9 {
+        let prototype = AnalogPrototype::Butterworth.design(order);
+        assert_stable(&prototype, order);
+        assert!(prototype.zeros.is_empty());
+        assert!(magnitude(&prototype, 0.0).abs() < 1e-9);
+        assert!((magnitude(&prototype, 1.0) + 3.0103).abs() < 1e-3);
+        // Roll off of 6dB per octave per order.
+        let roll_off = magnitude(&prototype, 100.0) - magnitude(&prototype, 200.0);
+        assert!(
+            (roll_off - 6.0206 * order as f64).abs() < 1e-2,
+            "{roll_off}"
+        );
+    }
+}
+
+#[test]
+fn test_chebyshev_1() {
+    for order in 1..=8 {
+        let prototype = AnalogPrototype::ChebyshevI { ripple: 1.0 }.design(order);
+        assert_stable(&prototype, order);
+        let (min, max) = gain_range(&prototype, 1e-3, 1.0);
+        // The peaks of the ripple are between the sampled frequencies.
+        assert!(max.abs() < 1e-4, "{order}: {max}");
+        assert!((min + 1.0).abs() < 1e-6, "{order}: {min}");
+        assert!((magnitude(&prototype, 1.0) + 1.0).abs() < 1e-9);
+        assert!(magnitude(&prototype, 1.5) < -1.0);
+    }
+}
+
+#[test]
+fn test_chebyshev_2() {
+    for order in 1..=8 {
+        let prototype = AnalogPrototype::ChebyshevII { attenuation: 40.0 }.design(order);
+        assert_stable(&prototype, order);
+        assert_eq!(order / 2 * 2, prototype.zeros.len());
+        assert!(magnitude(&prototype, 0.0).abs() < 1e-9);
+        let (_, max) = gain_range(&prototype, 1.0, 1e3);
+        assert!((max + 40.0).abs() < 1e-3, "{order}: {max}");
+    }
+}
+
+#[test]
+fn test_elliptic() {
+    for order in 1..=8 {
+        let prototype = AnalogPrototype::Elliptic {
+            ripple: 0.5,
+            attenuation: 60.0,
+        }
+        .design(order);
+        assert_stable(&prototype, order);
+        assert_eq!(order / 2 * 2, prototype.zeros.len());
+        assert!(prototype.zeros.iter().all(|zero| zero.re.abs() < 1e-9));
+
+        let (min, max) = gain_range(&prototype, 1e-3, 1.0);
+        // The peaks of the ripple are between the sampled frequencies.
+        assert!(max.abs() < 1e-4, "{order}: {max}");
+        assert!((min + 0.5).abs() < 1e-6, "{order}: {min}");
+        assert!((magnitude(&prototype, 1.0) + 0.5).abs() < 1e-6);
+        if order > 1 {
+            // The stop band starts at the lowest zero.
+            let stop_band = prototype
+                .zeros
+                .iter()
+                .map(|zero| zero.im.abs())
+                .fold(f64::MAX, f64::min);
+            let (_, max) = gain_range(&prototype, stop_band, 1e4);
+            assert!(max < -60.0 + 1e-3, "{order}: {max}");
+        }
+    }
+}
+
+#[test]
+fn test_elliptic_is_steeper() {
+    let order = 4;
+    let elliptic = AnalogPrototype::Elliptic {
+        ripple: 1.0,
+        attenuation: 40.0,
+    }
+    .design(order);
+    let chebyshev = AnalogPrototype::ChebyshevI { ripple: 1.0 }.design(order);
+    let butterworth = AnalogPrototype::Butterworth.design(order);
+    assert!(magnitude(&elliptic, 1.5) < magnitude(&chebyshev, 1.5));
+    assert!(magnitude(&chebyshev, 1.5) < magnitude(&butterworth, 1.5));
+}
+
+#[test]
+fn test_low_pass() {
+    let low_pass = AnalogPrototype::Butterworth.design(3).to_low_pass(1000.0);
+    let omega = TAU * 1000.0;
+    assert!(magnitude(&low_pass, 0.0).abs() < 1e-9);
+    assert!((magnitude(&low_pass, omega) + 3.0103).abs() < 1e-3);
+    assert!(magnitude(&low_pass, 4.0 * omega) < -36.0);
+}
+
+#[test]
+fn test_high_pass() {
+    let high_pass = AnalogPrototype::ChebyshevI { ripple: 1.0 }
+        .design(5)
+        .to_high_pass(1000.0);
+    let omega = TAU * 1000.0;
+    assert_eq!(5, high_pass.zeros.len());
+    assert!(magnitude(&high_pass, 1e9).abs() < 1e-6);
+    assert!((magnitude(&high_pass, omega) + 1.0).abs() < 1e-6);
+    assert!(magnitude(&high_pass, omega / 4.0) < -40.0);
+}
+
+#[test]
+fn test_band_pass() {
+    let center = 1000.0;
+    let bandwidth = 200.0;
+    let band_pass = AnalogPrototype::Butterworth
+        .design(2)
+        .to_band_pass(center, bandwidth);
+    assert_eq!(4, band_pass.poles.len());
+    assert_eq!(2, band_pass.zeros.len());
+    assert!(magnitude(&band_pass, TAU * center).abs() < 1e-9);
+
+    // The edges are geometrically around the center, `high - low = bandwidth`.
+    let low = -bandwidth / 2.0 + ((bandwidth / 2.0).powi(2) + center * center).sqrt();
+    let high = low + bandwidth;
+    assert!((magnitude(&band_pass, TAU * low) + 3.0103).abs() < 1e-3);
+    assert!((magnitude(&band_pass, TAU * high) + 3.0103).abs() < 1e-3);
+}
+
+#[test]
+fn test_band_stop() {
+    let center = 1000.0;
+    let bandwidth = 200.0;
+    let band_stop = AnalogPrototype::Butterworth
+        .design(2)
+        .to_band_stop(center, bandwidth);
+    assert_eq!(4, band_stop.zeros.len());
+    assert!(magnitude(&band_stop, 0.0).abs() < 1e-9);
+    assert!(magnitude(&band_stop, TAU * 1e6).abs() < 1e-6);
+    assert!(magnitude(&band_stop, TAU * center) < -200.0);
+
+    let low = -bandwidth / 2.0 + ((bandwidth / 2.0).powi(2) + center * center).sqrt();
+    assert!((magnitude(&band_stop, TAU * low) + 3.0103).abs() < 1e-3);
+}
@@ -0,0 +1,186 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    analog::AnalogTransferFunction, analog_prototype::AnalogPrototype, complex::Complex,
+    direct_form::DirectFormFilter, second_order_sections::SecondOrderSections,
+};
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// `ω/(s + ω)`, a one pole low pass with the given cutoff in Hz.
+fn one_pole(cutoff: f64) -> AnalogTransferFunction {
+    let s = AnalogTransferFunction::s();
+    let omega = TAU * cutoff;
+    omega / &(&s + omega)
+}
+
+#[test]
+fn test_display() {
+    let s = AnalogTransferFunction::s();
+    assert_eq!("f(s) = s", s.to_string());
+    assert_eq!("f(s) = \\frac{2}{1 +s}", (2.0 / &(&s + 1.0)).to_string());
+}
+
+#[test]
+fn test_zeros_poles_gain() {
+    let s = AnalogTransferFunction::s();
+    // 2(s + 1)/((s + 2)(s + 3))
+    let transfer_function = &(2.0 * &(&s + 1.0)) / &(&(&s + 2.0) * &(&s + 3.0));
+    let zeros_poles_gain = transfer_function.zeros_poles_gain();
+    assert_eq!(2.0, zeros_poles_gain.gain);
+    assert!((zeros_poles_gain.zeros[0].re + 1.0).abs() < 1e-12);
+    let mut poles = zeros_poles_gain
+        .poles
+        .iter()
+        .map(|pole| pole.re)
+        .collect::<Vec<f64>>();
+    poles.sort_by(f64::total_cmp);
+    assert!((poles[0] + 3.0).abs() < 1e-12);
+    assert!((poles[1] + 2.0).abs() < 1e-12);
+
+    let back = AnalogTransferFunction::from(&zeros_poles_gain);
+    for frequency in [0.0, 0.1, 1.0, 10.0] {
+        let difference = back.response(frequency) - transfer_function.response(frequency);
+        assert!(difference.norm() < 1e-12);
+    }
+}
+
+#[test]
+fn test_bilinear() {
+    let analog = one_pole(1000.0);
+    let discrete = analog.bilinear(SAMPLE_RATE, None);
+    assert_eq!(1.0 / SAMPLE_RATE, discrete.timestep);
+    assert!(discrete.is_stable());
+    assert!(discrete.magnitude(0.0).abs() < 1e-9);
+    // Nyquist maps to infinity.
+    assert!(discrete.magnitude(SAMPLE_RATE / 2.0) < -200.0);
+    // Without prewarping the cutoff is a bit lower.
+    assert!(discrete.magnitude(1000.0) < analog.magnitude(1000.0));
+    assert!((discrete.magnitude(1000.0) - analog.magnitude(1000.0)).abs() < 0.05);
+}
+
+#[test]
+fn test_bilinear_prewarp() {
+    let cutoff = 8000.0;
+    let analog = one_pole(cutoff);
+    let warped = analog.bilinear(SAMPLE_RATE, None);
+    let prewarped = analog.bilinear(SAMPLE_RATE, Some(cutoff));
+    assert!((warped.magnitude(cutoff) - analog.magnitude(cutoff)).abs() > 0.4);
+    assert!((prewarped.magnitude(cutoff) - analog.magnitude(cutoff)).abs() < 1e-9);
+    assert!((prewarped.phase(cutoff) - analog.response(cutoff).arg()).abs() < 1e-9);
+}
+
+#[test]
+fn test_impulse_invariance() {
+    // Impulse response of ω/(s + ω) is ω*e^(-ωt).
+    let cutoff = 500.0;
+    let omega = TAU * cutoff;
+    let discrete = one_pole(cutoff).impulse_invariance(SAMPLE_RATE);
+    let mut filter = DirectFormFilter::from(discrete);
+    for index in 0..100 {
+        let input = if index == 0 { 1.0 } else { 0.0 };
+        let time = index as f64 / SAMPLE_RATE;
+        let expected = omega * (-omega * time).exp() / SAMPLE_RATE;
+        assert!((filter.filter(input) - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_impulse_invariance_resonator() {
+    // Damped resonator with complex poles and a zero, the impulse response is
+    // e^(-at)*cos(bt) for (s + a)/((s + a)^2 + b^2).
+    let s = AnalogTransferFunction::s();
+    let a = 200.0;
+    let b = TAU * 440.0;
+    let transfer_function = &(&s + a) / &(&(&(&s + a) * &(&s + a)) + b * b);
+    let discrete = transfer_function.impulse_invariance(SAMPLE_RATE);
+    let mut filter = DirectFormFilter::from(discrete);
+    for index in 0..1000 {
+        let input = if index == 0 { 1.0 } else { 0.0 };
+        let time = index as f64 / SAMPLE_RATE;
+        let expected = (-a * time).exp() * (b * time).cos() / SAMPLE_RATE;
+        assert!((filter.filter(input) - expected).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_impulse_invariance_direct_term() {
+    // (s + 2ω)/(s + ω) = 1 + ω/(s + ω)
+    let s = AnalogTransferFunction::s();
+    let omega = TAU * 500.0;
+    let transfer_function = &(&s + 2.0 * omega) / &(&s + omega);
+    let discrete = transfer_function.impulse_invariance(SAMPLE_RATE);
+    let mut filter = DirectFormFilter::from(discrete);
+    for index in 0..100 {
+        let input = if index == 0 { 1.0 } else { 0.0 };
+        let time = index as f64 / SAMPLE_RATE;
+        let mut expected = omega * (-omega * time).exp() / SAMPLE_RATE;
+        if index == 0 {
+            expected += 1.0;
+        }
+        assert!((filter.filter(input) - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_matched_z() {
+    let zeros_poles_gain = AnalogPrototype::Butterworth
+        .design(2)
+        .to_low_pass(1000.0)
+        .matched_z(SAMPLE_RATE, 0.0);
+    // Zeros at infinity move to nyquist.
+    assert_eq!(2, zeros_poles_gain.zeros.len());
+    assert!(zeros_poles_gain
+        .zeros
+        .iter()
+        .all(|zero| (zero.re + 1.0).abs() < 1e-12));
+    let pole = (zeros_poles_gain.poles[0].ln() * SAMPLE_RATE).norm();
+    assert!((pole - TAU * 1000.0).abs() < 1e-6);
+
+    let discrete = zeros_poles_gain.to_transfer_function(1.0 / SAMPLE_RATE);
+    assert!(discrete.is_stable());
+    assert!(discrete.magnitude(0.0).abs() < 1e-9);
+    assert!((discrete.magnitude(1000.0) + 3.0).abs() < 0.2);
+}
+
+#[test]
+fn test_matched_z_high_pass() {
+    let nyquist = SAMPLE_RATE / 2.0;
+    let discrete = AnalogPrototype::Butterworth
+        .design(2)
+        .to_high_pass(100.0)
+        .matched_z(SAMPLE_RATE, nyquist)
+        .to_transfer_function(1.0 / SAMPLE_RATE);
+    assert!(discrete.magnitude(nyquist).abs() < 1e-3);
+    assert!(discrete.magnitude(10.0) < -35.0);
+}
+
+#[test]
+fn test_high_order_design() {
+    // Going through the polynomial would lose the poles close to z=1; the sections are
+    // created from the exact roots.
+    let cutoff = 100.0;
+    let zeros_poles_gain = AnalogPrototype::Elliptic {
+        ripple: 0.5,
+        attenuation: 80.0,
+    }
+    .design(10)
+    .to_low_pass(cutoff)
+    .bilinear(SAMPLE_RATE, Some(cutoff));
+    let sections = zeros_poles_gain.second_order_sections();
+    assert_eq!(5, sections.len());
+
+    let mut filter = SecondOrderSections::<f64>::from(&zeros_poles_gain);
+    let mut energy = 0.0;
+    let mut sum = 0.0;
+    for index in 0..SAMPLE_RATE as usize {
+        let output = filter.filter(if index == 0 { 1.0 } else { 0.0 });
+        energy += output * output;
+        sum += output;
+    }
+    // Stable and a DC gain of -0.5dB (even order).
+    assert!(energy.is_finite());
+    assert!((20.0 * sum.log10() + 0.5).abs() < 1e-3, "{sum}");
+    let response = zeros_poles_gain.evaluate(Complex::from_polar(1.0, TAU * cutoff / SAMPLE_RATE));
+    assert!((20.0 * response.norm().log10() + 0.5).abs() < 1e-6);
+}
@@ -0,0 +1,214 @@
+//! Second order filters designed with the formulas of the Audio EQ Cookbook by Robert
+//! Bristow-Johnson (<https://www.w3.org/TR/audio-eq-cookbook/>).
+//!
+//! The cookbook filters are analog prototypes that are converted to the z-domain with the
+//! bilinear transform. The cutoff frequency is prewarped, so the filter has its cutoff at the
+//! requested frequency.
+
+use std::f64::consts::{FRAC_1_SQRT_2, TAU};
+
+use crate::{components::Components, filter::Filter, transfer_function::TransferFunction};
+
+/// Highest frequency relative to the sample rate that can be designed. The formulas become
+/// unstable when reaching the nyquist frequency.
+const MAX_RELATIVE_FREQUENCY: f64 = 0.49;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum BiquadType {
+    #[default]
+    LowPass,
+    HighPass,
+    /// Band pass with a peak gain of 0dB.
+    BandPass,
+    Notch,
+    /// Boosts or cuts the frequencies around the center frequency by the gain.
+    Peak,
+    /// Boosts or cuts the frequencies below the cutoff frequency by the gain.
+    LowShelf,
+    /// Boosts or cuts the frequencies above the cutoff frequency by the gain.
+    HighShelf,
+    /// Passes all frequencies, only changes the phase around the center frequency.
+    AllPass,
+}
+
+/// Design parameters of a second order filter.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Biquad {
+    pub biquad_type: BiquadType,
+    /// Cutoff or center frequency in Hz.
+    pub frequency: f64,
+    /// Quality factor, higher values give a narrower band or a resonance at the cutoff.
+    pub q: f64,
+    /// Gain in dB, only used by the peak and shelf filters.
+    pub gain: f64,
+}
+
+impl Default for Biquad {
+    fn default() -> Self {
+        Biquad {
+            biquad_type: BiquadType::LowPass,
+            frequency: 1000.0,
+            q: FRAC_1_SQRT_2,
+            gain: 0.0,
+        }
+    }
+}
+
+/// Coefficients of `H(z) = (b0 + b1*z^-1 + b2*z^-2) / (a0 + a1*z^-1 + a2*z^-2)`.
+///
+/// The coefficients are normalized, `a[0]` is always 1.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BiquadCoefficients {
+    pub b: [f64; 3],
+    pub a: [f64; 3],
+}
+
+impl Biquad {
+    pub fn new(biquad_type: BiquadType, frequency: f64, q: f64) -> Biquad {
+        Biquad {
+            biquad_type,
+            frequency,
+            q,
+            gain: 0.0,
+        }
+    }
+
+    pub fn with_gain(biquad_type: BiquadType, frequency: f64, q: f64, gain: f64) -> Biquad {
+        Biquad {
+            biquad_type,
+            frequency,
+            q,
+            gain,
+        }
+    }
+
+    /// Calculate the coefficients of the filter.
+    ///
+    /// The frequency is limited to just below the nyquist frequency of the sample rate.
+    pub fn coefficients(&self, sample_rate: f64) -> BiquadCoefficients {
+        let frequency = self
+            .frequency
+            .clamp(f64::EPSILON, sample_rate * MAX_RELATIVE_FREQUENCY);
+        let w0 = TAU * frequency / sample_rate;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * self.q.max(f64::EPSILON));
+        let amplitude = 10.0_f64.powf(self.gain / 40.0);
+
+        let (b, a) = match self.biquad_type {
+            BiquadType::LowPass => (
+                [(1.0 - cos_w0) / 2.0, 1.0 - cos_w0, (1.0 - cos_w0) / 2.0],
+                [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha],
+            ),
+            BiquadType::HighPass => (
+                [(1.0 + cos_w0) / 2.0, -(1.0 + cos_w0), (1.0 + cos_w0) / 2.0],
+                [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha],
+            ),
+            BiquadType::BandPass => (
+                [alpha, 0.0, -alpha],
+                [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha],
+            ),
+            BiquadType::Notch => (
+                [1.0, -2.0 * cos_w0, 1.0],
+                [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha],
+            ),
+            BiquadType::Peak => (
+                [
+                    1.0 + alpha * amplitude,
+                    -2.0 * cos_w0,
+                    1.0 - alpha * amplitude,
+                ],
+                [
+                    1.0 + alpha / amplitude,
+                    -2.0 * cos_w0,
+                    1.0 - alpha / amplitude,
+                ],
+            ),
+            BiquadType::LowShelf => {
+                let sqrt_alpha = 2.0 * amplitude.sqrt() * alpha;
+                (
+                    [
+                        amplitude * ((amplitude + 1.0) - (amplitude - 1.0) * cos_w0 + sqrt_alpha),
+                        2.0 * amplitude * ((amplitude - 1.0) - (amplitude + 1.0) * cos_w0),
+                        amplitude * ((amplitude + 1.0) - (amplitude - 1.0) * cos_w0 - sqrt_alpha),
+                    ],
+                    [
+                        (amplitude + 1.0) + (amplitude - 1.0) * cos_w0 + sqrt_alpha,
+                        -2.0 * ((amplitude - 1.0) + (amplitude + 1.0) * cos_w0),
+                        (amplitude + 1.0) + (amplitude - 1.0) * cos_w0 - sqrt_alpha,
+                    ],
+                )
+            }
+            BiquadType::HighShelf => {
+                let sqrt_alpha = 2.0 * amplitude.sqrt() * alpha;
+                (
+                    [
+                        amplitude * ((amplitude + 1.0) + (amplitude - 1.0) * cos_w0 + sqrt_alpha),
+                        -2.0 * amplitude * ((amplitude - 1.0) + (amplitude + 1.0) * cos_w0),
+                        amplitude * ((amplitude + 1.0) + (amplitude - 1.0) * cos_w0 - sqrt_alpha),
+                    ],
+                    [
+                        (amplitude + 1.0) - (amplitude - 1.0) * cos_w0 + sqrt_alpha,
+                        2.0 * ((amplitude - 1.0) - (amplitude + 1.0) * cos_w0),
+                        (amplitude + 1.0) - (amplitude - 1.0) * cos_w0 - sqrt_alpha,
+                    ],
+                )
+            }
+            BiquadType::AllPass => (
+                [1.0 - alpha, -2.0 * cos_w0, 1.0 + alpha],
+                [1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha],
+            ),
+        };
+
+        BiquadCoefficients {
+            b: b.map(|b| b / a[0]),
+            a: a.map(|a_n| a_n / a[0]),
+        }
+    }
+
+    pub fn transfer_function(&self, sample_rate: f64) -> TransferFunction {
+        TransferFunction::from_biquad(&self.coefficients(sample_rate), 1.0 / sample_rate)
+    }
+}
+
+impl TransferFunction {
+    /// Transfer function `(b0*z^2 + b1*z + b2) / (a0*z^2 + a1*z + a2)` of the coefficients.
+    pub fn from_biquad(coefficients: &BiquadCoefficients, timestep: f64) -> TransferFunction {
+        let mut numerator = coefficients.b.to_vec();
+        let mut denominator = coefficients.a.to_vec();
+        numerator.reverse();
+        denominator.reverse();
+        TransferFunction {
+            timestep,
+            numerator: Components {
+                components: numerator,
+            },
+            denominator: Components {
+                components: denominator,
+            },
+        }
+    }
+}
+
+impl From<BiquadCoefficients> for Filter<f64> {
+    fn from(coefficients: BiquadCoefficients) -> Self {
+        Filter {
+            x: vec![0.0; 3],
+            y: vec![0.0; 3],
+            a: coefficients.a.to_vec(),
+            b: coefficients.b.to_vec(),
+            n: 2,
+        }
+    }
+}
+
+impl From<BiquadCoefficients> for Filter<f32> {
+    fn from(coefficients: BiquadCoefficients) -> Self {
+        Filter {
+            x: vec![0.0; 3],
+            y: vec![0.0; 3],
+            a: coefficients.a.map(|a| a as f32).to_vec(),
+            b: coefficients.b.map(|b| b as f32).to_vec(),
+            n: 2,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::f64::consts::{FRAC_1_SQRT_2, TAU};
+
+use crate::{
+    biquad::{Biquad, BiquadCoefficients, BiquadType},
+    filter::Filter,
+};
+
+const SAMPLE_RATE: f64 = 44100.0;
+
+/// Magnitude in dB of the frequency response at the given frequency.
+fn gain_at(coefficients: &BiquadCoefficients, frequency: f64) -> f64 {
+    let w = TAU * frequency / SAMPLE_RATE;
+    let evaluate = |c: &[f64; 3]| {
+        let re = c[0] + c[1] * (-w).cos() + c[2] * (-2.0 * w).cos();
+        let im = c[1] * (-w).sin() + c[2] * (-2.0 * w).sin();
+        (re * re + im * im).sqrt()
+    };
+    20.0 * (evaluate(&coefficients.b) / evaluate(&coefficients.a)).log10()
+}
+
+fn assert_gain(coefficients: &BiquadCoefficients, frequency: f64, expected: f64) {
+    let gain = gain_at(coefficients, frequency);
+    assert!(
+        (gain - expected).abs() < 0.01,
+        "gain at {frequency}Hz is {gain}dB, expected {expected}dB"
+    );
+}
+
+#[test]
+fn test_low_pass() {
+    let coefficients =
+        Biquad::new(BiquadType::LowPass, 1000.0, FRAC_1_SQRT_2).coefficients(SAMPLE_RATE);
+    assert_eq!(1.0, coefficients.a[0]);
+    assert_gain(&coefficients, 10.0, 0.0);
+    assert_gain(&coefficients, 1000.0, -3.01);
+    assert!(gain_at(&coefficients, 10000.0) < -35.0);
+}
+
+#[test]
+fn test_high_pass() {
+    let coefficients =
+        Biquad::new(BiquadType::HighPass, 1000.0, FRAC_1_SQRT_2).coefficients(SAMPLE_RATE);
+    assert!(gain_at(&coefficients, 100.0) < -35.0);
+    assert_gain(&coefficients, 1000.0, -3.01);
+    assert_gain(&coefficients, 20000.0, 0.0);
+}
+
+#[test]
+fn test_band_pass_and_notch() {
+    let band_pass = Biquad::new(BiquadType::BandPass, 1000.0, 2.0).coefficients(SAMPLE_RATE);
+    assert_gain(&band_pass, 1000.0, 0.0);
+    assert!(gain_at(&band_pass, 100.0) < -20.0);
+
+    let notch = Biquad::new(BiquadType::Notch, 1000.0, 2.0).coefficients(SAMPLE_RATE);
+    assert!(gain_at(&notch, 1000.0) < -100.0);
+    assert_gain(&notch, 10.0, 0.0);
+}
+
+#[test]
+fn test_peak_and_shelves() {
+    let peak = Biquad::with_gain(BiquadType::Peak, 1000.0, 1.0, 6.0).coefficients(SAMPLE_RATE);
+    assert_gain(&peak, 1000.0, 6.0);
+    assert_gain(&peak, 10.0, 0.0);
+
+    let low_shelf = Biquad::with_gain(BiquadType::LowShelf, 1000.0, FRAC_1_SQRT_2, -12.0)
+        .coefficients(SAMPLE_RATE);
+    assert_gain(&low_shelf, 10.0, -12.0);
+    assert_gain(&low_shelf, 20000.0, 0.0);
+
+    let high_shelf = Biquad::with_gain(BiquadType::HighShelf, 1000.0, FRAC_1_SQRT_2, 12.0)
+        .coefficients(SAMPLE_RATE);
+    assert_gain(&high_shelf, 10.0, 0.0);
+    assert_gain(&high_shelf, 20000.0, 12.0);
+}
+
+#[test]
+fn test_all_pass() {
+    let coefficients = Biquad::new(BiquadType::AllPass, 1000.0, 1.0).coefficients(SAMPLE_RATE);
+    for frequency in [10.0, 500.0, 1000.0, 5000.0, 20000.0] {
+        assert_gain(&coefficients, frequency, 0.0);
+    }
+}
+
+#[test]
+fn test_frequency_above_nyquist_is_stable() {
+    let coefficients = Biquad::new(BiquadType::LowPass, 30000.0, 4.0).coefficients(SAMPLE_RATE);
+    let mut filter = Filter::<f64>::from(coefficients);
+    let mut last = 0.0;
+    for index in 0..44100 {
+        last = filter.filter(if index == 0 { 1.0 } else { 0.0 });
+    }
+    assert!(last.abs() < 1e-6);
+}
+
+#[test]
+fn test_transfer_function_filter() {
+    let biquad = Biquad::new(BiquadType::LowPass, 500.0, 2.0);
+    let mut from_transfer_function = Filter::from(biquad.transfer_function(SAMPLE_RATE));
+    let mut from_coefficients = Filter::<f64>::from(biquad.coefficients(SAMPLE_RATE));
+    for index in 0..100 {
+        let sample = (index as f64 * 0.3).sin();
+        let expected = from_coefficients.filter(sample);
+        let result = from_transfer_function.filter(sample);
+        assert!((expected - result).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,195 @@
+//! Export the frequency response of a transfer function as a Bode plot.
+//!
+//! The image has two panels that share a logarithmic frequency axis. The top panel shows the
+//! magnitude in dB, the bottom panel the phase between -180 and 180 degrees. Vertical grid
+//! lines are drawn at each 1, 2, .. 9 times a power of 10 Hz, darker at each power of 10.
+use std::{f64::consts::PI, fs::File, io::BufWriter, ops::Range, path::Path};
+
+use crate::{frequency_response::log_frequencies, transfer_function::TransferFunction};
+
+type Color = [u8; 3];
+
+const BACKGROUND: Color = [255, 255, 255];
+const GRID: Color = [230, 230, 230];
+const GRID_MAJOR: Color = [180, 180, 180];
+const MAGNITUDE: Color = [32, 64, 192];
+const PHASE: Color = [192, 32, 32];
+
+/// Distance in dB between horizontal grid lines of the magnitude panel.
+const MAGNITUDE_GRID: f64 = 6.0;
+/// Distance in degrees between horizontal grid lines of the phase panel.
+const PHASE_GRID: f64 = 45.0;
+
+/// Lowest frequency in Hz that can be plotted, lower minimum frequencies are clamped.
+const MIN_FREQUENCY: f64 = 0.001;
+/// Smallest width and height of the image in pixels.
+const MIN_SIZE: u32 = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BodePlot {
+    /// Lowest frequency in Hz, at least 0.001 Hz.
+    pub min_frequency: f64,
+    /// Highest frequency in Hz, limited to the nyquist frequency of the transfer function.
+    pub max_frequency: f64,
+    /// Lowest gain in dB shown in the magnitude panel.
+    pub min_magnitude: f64,
+    /// Highest gain in dB shown in the magnitude panel.
+    pub max_magnitude: f64,
+    /// Width of the image in pixels, at least 2.
+    pub width: u32,
+    /// Height of the image in pixels, at least 2. Each panel uses half of the height.
+    pub height: u32,
+}
+
+impl Default for BodePlot {
+    fn default() -> Self {
+        BodePlot {
+            min_frequency: 20.0,
+            max_frequency: 20000.0,
+            min_magnitude: -60.0,
+            max_magnitude: 12.0,
+            width: 1024,
+            height: 768,
+        }
+    }
+}
+
+impl BodePlot {
+    /// Width and height of the image in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width.max(MIN_SIZE), self.height.max(MIN_SIZE))
+    }
+
+    /// Render the plot as rows of RGB pixels, see [BodePlot::size] for the dimensions.
+    pub fn render(&self, transfer_function: &TransferFunction) -> Vec<u8> {
+        let (width, height) = self.size();
+        let width = width as usize;
+        let height = height as usize;
+        let min_frequency = self.min_frequency.max(MIN_FREQUENCY);
+        let panel_height = height / 2;
+        let magnitude_panel = 0..panel_height;
+        let phase_panel = panel_height..height;
+        let mut image = Image {
+            data: [BACKGROUND].repeat(width * height),
+            width,
+        };
+
+        let nyquist = 0.5 / transfer_function.timestep;
+        let max_frequency = self.max_frequency.min(nyquist);
+        let frequencies = log_frequencies(min_frequency, max_frequency, width);
+
+        // Vertical grid lines.
+        let mut decade = 10.0_f64.powf(min_frequency.log10().floor());
+        while decade <= max_frequency {
+            for multiple in 1..10 {
+                let frequency = decade * multiple as f64;
+                if frequency < min_frequency || frequency > max_frequency {
+                    continue;
+                }
+                let x = ((frequency / min_frequency).ln() / (max_frequency / min_frequency).ln()
+                    * (width - 1) as f64)
+                    .round() as usize;
+                let color = if multiple == 1 { GRID_MAJOR } else { GRID };
+                image.vertical_line(x, 0..height, color);
+            }
+            decade *= 10.0;
+        }
+
+        // Horizontal grid lines.
+        let magnitude_range = self.min_magnitude..self.max_magnitude;
+        let mut magnitude = (self.min_magnitude / MAGNITUDE_GRID).ceil() * MAGNITUDE_GRID;
+        while magnitude <= self.max_magnitude {
+            let y = to_y(magnitude, &magnitude_range, &magnitude_panel);
+            let color = if magnitude == 0.0 { GRID_MAJOR } else { GRID };
+            image.horizontal_line(y, color);
+            magnitude += MAGNITUDE_GRID;
+        }
+        let phase_range = -180.0..180.0;
+        let mut phase = -180.0;
+        while phase <= 180.0 {
+            let y = to_y(phase, &phase_range, &phase_panel);
+            let color = if phase == 0.0 { GRID_MAJOR } else { GRID };
+            image.horizontal_line(y, color);
+            phase += PHASE_GRID;
+        }
+        image.horizontal_line(panel_height, GRID_MAJOR);
+
+        // Curves, consecutive points are connected with a vertical line.
+        let mut previous: Option<(usize, usize)> = None;
+        for (x, frequency) in frequencies.iter().enumerate() {
+            let response = transfer_function.response(*frequency);
+            let magnitude = 20.0 * response.norm().log10();
+            let phase = response.arg() * 180.0 / PI;
+
+            let magnitude_y = to_y(magnitude, &magnitude_range, &magnitude_panel);
+            let phase_y = to_y(phase, &phase_range, &phase_panel);
+            match previous {
+                Some((previous_magnitude_y, previous_phase_y)) => {
+                    image.connect(x, previous_magnitude_y, magnitude_y, MAGNITUDE);
+                    // Don't connect when the phase wraps around.
+                    if previous_phase_y.abs_diff(phase_y) < panel_height / 2 {
+                        image.connect(x, previous_phase_y, phase_y, PHASE);
+                    } else {
+                        image.vertical_line(x, phase_y..phase_y + 1, PHASE);
+                    }
+                }
+                None => {
+                    image.vertical_line(x, magnitude_y..magnitude_y + 1, MAGNITUDE);
+                    image.vertical_line(x, phase_y..phase_y + 1, PHASE);
+                }
+            }
+            previous = Some((magnitude_y, phase_y));
+        }
+
+        image.data.concat()
+    }
+
+    /// Write the plot as a png file.
+    pub fn export(
+        &self,
+        transfer_function: &TransferFunction,
+        path: &Path,
+    ) -> Result<(), png::EncodingError> {
+        let file = File::create(path)?;
+        let (width, height) = self.size();
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.render(transfer_function))
+    }
+}
+
+/// Row of the value inside the panel, higher values are drawn higher. Values outside the
+/// range are clamped to the border of the panel.
+fn to_y(value: f64, range: &Range<f64>, panel: &Range<usize>) -> usize {
+    let position = ((value - range.start) / (range.end - range.start)).clamp(0.0, 1.0);
+    let position = if position.is_nan() { 0.0 } else { position };
+    panel.end - 1 - (position * (panel.len() - 1) as f64).round() as usize
+}
+
+struct Image {
+    data: Vec<Color>,
+    width: usize,
+}
+
+impl Image {
+    fn vertical_line(&mut self, x: usize, rows: Range<usize>, color: Color) {
+        for y in rows {
+            if let Some(pixel) = self.data.get_mut(y * self.width + x) {
+                *pixel = color;
+            }
+        }
+    }
+
+    fn horizontal_line(&mut self, y: usize, color: Color) {
+        if let Some(row) = self.data.chunks_mut(self.width).nth(y) {
+            row.fill(color);
+        }
+    }
+
+    /// Draw the column between the previous and the current row.
+    fn connect(&mut self, x: usize, from_y: usize, to_y: usize, color: Color) {
+        self.vertical_line(x, from_y.min(to_y)..from_y.max(to_y) + 1, color);
+    }
+}
@@ -0,0 +1,56 @@
+use std::fs::File;
+
+use crate::{
+    biquad::{Biquad, BiquadType},
+    bode_plot::BodePlot,
+};
+
+#[test]
+fn test_render() {
+    let plot = BodePlot {
+        width: 200,
+        height: 100,
+        ..BodePlot::default()
+    };
+    let low_pass = Biquad::new(BiquadType::LowPass, 1000.0, 0.7).transfer_function(44100.0);
+    let image = plot.render(&low_pass);
+    assert_eq!(200 * 100 * 3, image.len());
+
+    // The magnitude curve starts at 0dB, at 12/72 of the magnitude panel from the top.
+    let pixel = |x: usize, y: usize| &image[(y * 200 + x) * 3..(y * 200 + x) * 3 + 3];
+    let zero_db_y = 49 - (49.0 * 60.0 / 72.0_f64).round() as usize;
+    assert_eq!([32, 64, 192], pixel(0, zero_db_y));
+}
+
+#[test]
+fn test_render_clamps_settings() {
+    let plot = BodePlot {
+        min_frequency: 0.0,
+        width: 0,
+        height: 1,
+        ..BodePlot::default()
+    };
+    assert_eq!((2, 2), plot.size());
+    let low_pass = Biquad::new(BiquadType::LowPass, 1000.0, 0.7).transfer_function(44100.0);
+    assert_eq!(2 * 2 * 3, plot.render(&low_pass).len());
+
+    let plot = BodePlot {
+        min_frequency: -20.0,
+        ..BodePlot::default()
+    };
+    assert_eq!(1024 * 768 * 3, plot.render(&low_pass).len());
+}
+
+#[test]
+fn test_export() {
+    let path = std::env::temp_dir().join("audio-engine-discrete-time-bode-plot.png");
+    let plot = BodePlot::default();
+    let high_pass = Biquad::new(BiquadType::HighPass, 200.0, 2.0).transfer_function(44100.0);
+    plot.export(&high_pass, &path).unwrap();
+
+    let decoder = png::Decoder::new(File::open(&path).unwrap());
+    let reader = decoder.read_info().unwrap();
+    assert_eq!(plot.width, reader.info().width);
+    assert_eq!(plot.height, reader.info().height);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,176 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// Complex number used to evaluate transfer functions on the unit circle and to hold their
+/// poles and zeros.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+    pub const ONE: Complex = Complex { re: 1.0, im: 0.0 };
+    pub const I: Complex = Complex { re: 0.0, im: 1.0 };
+
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    /// Complex number with the given magnitude and angle in radians.
+    pub fn from_polar(norm: f64, arg: f64) -> Complex {
+        Complex {
+            re: norm * arg.cos(),
+            im: norm * arg.sin(),
+        }
+    }
+
+    /// Magnitude (absolute value) of the complex number.
+    pub fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Angle of the complex number in radians.
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Complex {
+        Complex {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+
+    /// Square root with a non-negative real part.
+    pub fn sqrt(&self) -> Complex {
+        Complex::from_polar(self.norm().sqrt(), self.arg() / 2.0)
+    }
+
+    pub fn exp(&self) -> Complex {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Natural logarithm, the imaginary part is in -π..=π.
+    pub fn ln(&self) -> Complex {
+        Complex {
+            re: self.norm().ln(),
+            im: self.arg(),
+        }
+    }
+
+    pub fn sin(&self) -> Complex {
+        Complex {
+            re: self.re.sin() * self.im.cosh(),
+            im: self.re.cos() * self.im.sinh(),
+        }
+    }
+
+    pub fn cos(&self) -> Complex {
+        Complex {
+            re: self.re.cos() * self.im.cosh(),
+            im: -self.re.sin() * self.im.sinh(),
+        }
+    }
+
+    pub fn acos(&self) -> Complex {
+        -Complex::I * (*self + Complex::I * (Complex::ONE - *self * *self).sqrt()).ln()
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.im.is_sign_negative() {
+            f.write_fmt(format_args!("{}-{}i", self.re, -self.im))
+        } else {
+            f.write_fmt(format_args!("{}+{}i", self.re, self.im))
+        }
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Complex { re, im: 0.0 }
+    }
+}
+
+impl Add<Complex> for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Complex) -> Self::Output {
+        Complex {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl Sub<Complex> for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Complex) -> Self::Output {
+        Complex {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Self::Output {
+        Complex {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
+
+impl Mul<Complex> for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: Complex) -> Self::Output {
+        Complex {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Complex {
+            re: self.re * rhs,
+            im: self.im * rhs,
+        }
+    }
+}
+
+impl Div<f64> for Complex {
+    type Output = Complex;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Complex {
+            re: self.re / rhs,
+            im: self.im / rhs,
+        }
+    }
+}
+
+impl Div<Complex> for Complex {
+    type Output = Complex;
+
+    fn div(self, rhs: Complex) -> Self::Output {
+        let divisor = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex {
+            re: (self.re * rhs.re + self.im * rhs.im) / divisor,
+            im: (self.im * rhs.re - self.re * rhs.im) / divisor,
+        }
+    }
+}
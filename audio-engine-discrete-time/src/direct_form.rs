@@ -0,0 +1,198 @@
+//! Filter runtime using the direct form II transposed structure.
+//!
+//! ```text
+//! y[n]    = b0*x[n] + s0[n-1]
+//! s0[n]   = b1*x[n] - a1*y[n] + s1[n-1]
+//! ...
+//! s(N-1)[n] = bN*x[n] - aN*y[n]
+//! ```
+//!
+//! Compared to [crate::filter::Filter] the state is allocated once and updated in place, no
+//! history is shifted for each sample. Coefficients can be changed while the filter is
+//! running; the change can be spread over a number of samples to prevent clicks.
+use std::ops::{Add, Mul, Sub};
+
+use crate::{filter::Filter, transfer_function::TransferFunction};
+
+/// Numeric type the filters can run on.
+pub trait FilterValue:
+    Copy + Default + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn from_f64(value: f64) -> Self;
+}
+
+impl FilterValue for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl FilterValue for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+/// Linear change of coefficients towards a target.
+#[derive(Debug, Clone)]
+pub(crate) struct CoefficientRamp<C> {
+    pub target: C,
+    pub step: C,
+    pub remaining: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectFormFilter<F> {
+    /// Numerator coefficients, `b[0]` belongs to the current input.
+    b: Vec<F>,
+    /// Denominator coefficients. `a[0]` is assumed to be 1 and isn't used.
+    a: Vec<F>,
+    state: Vec<F>,
+    ramp: Option<CoefficientRamp<(Vec<F>, Vec<F>)>>,
+}
+
+impl<F> DirectFormFilter<F>
+where
+    F: FilterValue,
+{
+    /// Create a filter with the given coefficients. The shorter list of coefficients is
+    /// extended with zeros, highest coefficients that are zero in both lists are removed.
+    pub fn new(b: &[F], a: &[F]) -> DirectFormFilter<F> {
+        let (b, a) = trim_coefficients(b, a);
+        DirectFormFilter {
+            state: vec![F::default(); b.len() - 1],
+            b,
+            a,
+            ramp: None,
+        }
+    }
+
+    /// Number of previous samples the output depends on.
+    pub fn order(&self) -> usize {
+        self.state.len()
+    }
+
+    pub fn b(&self) -> &[F] {
+        &self.b
+    }
+
+    pub fn a(&self) -> &[F] {
+        &self.a
+    }
+
+    /// Clear the state, the filter continues as if it only received zeros.
+    pub fn reset(&mut self) {
+        self.state.fill(F::default());
+    }
+
+    /// Change the coefficients, linearly over `ramp_len` samples. The state is kept, so the
+    /// output continues from the current output.
+    ///
+    /// # Panics
+    ///
+    /// When the coefficients would change the order of the filter.
+    pub fn set_coefficients(&mut self, b: &[F], a: &[F], ramp_len: usize) {
+        let len = self.b.len();
+        let (mut b, mut a) = trim_coefficients(b, a);
+        assert!(b.len() <= len, "order of the filter changed");
+        b.resize(len, F::default());
+        a.resize(len, F::default());
+
+        if ramp_len == 0 {
+            self.b = b;
+            self.a = a;
+            self.ramp = None;
+        } else {
+            let scale = F::from_f64(1.0 / ramp_len as f64);
+            let step = |target: &[F], current: &[F]| {
+                target
+                    .iter()
+                    .zip(current)
+                    .map(|(target, current)| (*target - *current) * scale)
+                    .collect::<Vec<F>>()
+            };
+            self.ramp = Some(CoefficientRamp {
+                step: (step(&b, &self.b), step(&a, &self.a)),
+                target: (b, a),
+                remaining: ramp_len,
+            });
+        }
+    }
+
+    fn advance_ramp(&mut self) {
+        let Some(ramp) = &mut self.ramp else {
+            return;
+        };
+        ramp.remaining -= 1;
+        if ramp.remaining == 0 {
+            if let Some(ramp) = self.ramp.take() {
+                (self.b, self.a) = ramp.target;
+            }
+        } else {
+            for (b, step) in self.b.iter_mut().zip(&ramp.step.0) {
+                *b = *b + *step;
+            }
+            for (a, step) in self.a.iter_mut().zip(&ramp.step.1) {
+                *a = *a + *step;
+            }
+        }
+    }
+
+    pub fn filter(&mut self, value_in: F) -> F {
+        self.advance_ramp();
+        let order = self.state.len();
+        if order == 0 {
+            return self.b[0] * value_in;
+        }
+        let result = self.b[0] * value_in + self.state[0];
+        for index in 0..order - 1 {
+            self.state[index] =
+                self.b[index + 1] * value_in - self.a[index + 1] * result + self.state[index + 1];
+        }
+        self.state[order - 1] = self.b[order] * value_in - self.a[order] * result;
+        result
+    }
+
+    /// Filter the samples in place.
+    pub fn filter_block(&mut self, samples: &mut [F]) {
+        for sample in samples {
+            *sample = self.filter(*sample);
+        }
+    }
+}
+
+/// Extend the shorter list of coefficients with zeros and remove the highest coefficients
+/// that are zero in both lists.
+fn trim_coefficients<F>(b: &[F], a: &[F]) -> (Vec<F>, Vec<F>)
+where
+    F: FilterValue,
+{
+    let mut len = b.len().max(a.len()).max(1);
+    let mut b = b.to_vec();
+    let mut a = a.to_vec();
+    b.resize(len, F::default());
+    a.resize(len, F::default());
+    while len > 1 && b[len - 1] == F::default() && a[len - 1] == F::default() {
+        len -= 1;
+    }
+    b.truncate(len);
+    a.truncate(len);
+    (b, a)
+}
+
+/// Copy the coefficients of the filter; the history of the filter isn't copied.
+impl<F> From<&Filter<F>> for DirectFormFilter<F>
+where
+    F: FilterValue,
+{
+    fn from(filter: &Filter<F>) -> Self {
+        let len = filter.n + 1;
+        DirectFormFilter::new(&filter.b[..len], &filter.a[..len])
+    }
+}
+
+impl From<TransferFunction> for DirectFormFilter<f64> {
+    fn from(transfer_function: TransferFunction) -> Self {
+        DirectFormFilter::from(&Filter::from(transfer_function))
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    biquad::{Biquad, BiquadCoefficients, BiquadType},
+    direct_form::DirectFormFilter,
+    filter::Filter,
+    transfer_function::TransferFunction,
+};
+
+/// H1=Hl*Hd^ap_num*Hfd1; see `transfer_function_test::test_h1`.
+fn h1() -> TransferFunction {
+    let fs = 44100.0;
+    let z = TransferFunction::new(1.0 / fs);
+    let c = -0.3242;
+    let ad = -0.30;
+    let gl = -0.99;
+    let al = -0.001;
+    let ap_num = 12;
+
+    let hl = gl * (1.0 + al) / &(1.0 + al * z.pow(-1));
+    let hd = (ad + z.pow(-1)) / (1.0 + ad * z.pow(-1));
+    let hfd1 = (c + z.pow(-1)) / (1.0 + c * z.pow(-1));
+    &(&hl * &hd.pow(ap_num)) * &hfd1
+}
+
+fn input(index: usize) -> f64 {
+    if index < 50 {
+        (index as f64 * 0.7).sin()
+    } else {
+        0.0
+    }
+}
+
+#[test]
+fn test_matches_filter() {
+    let mut filter = Filter::from(h1());
+    let mut direct_form = DirectFormFilter::from(h1());
+    // The common factor z^13 of the numerator and denominator isn't part of the filter.
+    assert_eq!(14, direct_form.order());
+    for index in 0..1000 {
+        let expected = filter.filter(input(index));
+        let result = direct_form.filter(input(index));
+        assert!(
+            (expected - result).abs() < 1e-12,
+            "{index}: {expected} != {result}"
+        );
+    }
+}
+
+#[test]
+fn test_matches_filter_f32() {
+    let coefficients = Biquad::new(BiquadType::LowPass, 200.0, 4.0).coefficients(44100.0);
+    let mut filter = Filter::<f32>::from(coefficients);
+    let mut direct_form = DirectFormFilter::from(&filter);
+    for index in 0..1000 {
+        let expected = filter.filter(input(index) as f32);
+        let result = direct_form.filter(input(index) as f32);
+        assert!((expected - result).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_filter_block() {
+    let mut filter = DirectFormFilter::from(h1());
+    let mut block_filter = filter.clone();
+    let expected = (0..256)
+        .map(|index| filter.filter(input(index)))
+        .collect::<Vec<f64>>();
+    let mut samples = (0..256).map(input).collect::<Vec<f64>>();
+    for block in samples.chunks_mut(100) {
+        block_filter.filter_block(block);
+    }
+    assert_eq!(expected, samples);
+}
+
+#[test]
+fn test_reset() {
+    let mut filter = DirectFormFilter::from(h1());
+    let expected = (0..100)
+        .map(|index| filter.filter(input(index)))
+        .collect::<Vec<f64>>();
+    filter.reset();
+    let result = (0..100)
+        .map(|index| filter.filter(input(index)))
+        .collect::<Vec<f64>>();
+    assert_eq!(expected, result);
+}
+
+/// Largest difference between two output samples after changing the coefficients.
+fn max_step(mut filter: DirectFormFilter<f64>, to: &BiquadCoefficients, ramp_len: usize) -> f64 {
+    filter.set_coefficients(&to.b, &to.a, ramp_len);
+    let mut previous = 1.0;
+    let mut result: f64 = 0.0;
+    for _ in 0..400 {
+        let sample = filter.filter(1.0);
+        result = result.max((sample - previous).abs());
+        previous = sample;
+    }
+    result
+}
+
+#[test]
+fn test_ramp_coefficients() {
+    let sample_rate = 44100.0;
+    let from = Biquad::new(BiquadType::LowPass, 500.0, 0.7).coefficients(sample_rate);
+    let to = Biquad::new(BiquadType::LowPass, 5000.0, 0.7).coefficients(sample_rate);
+    let mut filter = DirectFormFilter::new(&from.b, &from.a);
+    // Settle on a DC input, the output of a low pass is the input.
+    for _ in 0..10000 {
+        filter.filter(1.0);
+    }
+
+    assert!(max_step(filter.clone(), &to, 0) > 0.5);
+    assert!(max_step(filter.clone(), &to, 64) < 0.05);
+
+    filter.set_coefficients(&to.b, &to.a, 64);
+    for _ in 0..400 {
+        filter.filter(1.0);
+    }
+    assert_eq!(to.b, filter.b());
+    assert_eq!(to.a, filter.a());
+    assert!((filter.filter(1.0) - 1.0).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic]
+fn test_ramp_order_change() {
+    let mut filter = DirectFormFilter::new(&[1.0, 0.5], &[1.0, -0.5]);
+    filter.set_coefficients(&[1.0, 0.5, 0.25], &[1.0, 0.0, 0.0, 0.0], 0);
+}
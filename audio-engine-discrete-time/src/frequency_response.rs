@@ -0,0 +1,118 @@
+//! Frequency response of transfer functions.
+//!
+//! The response at a frequency is found by evaluating the transfer function on the unit
+//! circle at `z = e^(jω)` with `ω = 2π * frequency * timestep`.
+use std::f64::consts::TAU;
+
+use crate::{complex::Complex, components::Components, transfer_function::TransferFunction};
+
+/// Response of a transfer function at a single frequency.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FrequencyResponse {
+    /// Frequency in Hz.
+    pub frequency: f64,
+    /// Gain in dB.
+    pub magnitude: f64,
+    /// Phase shift in radians, unwrapped when part of a list of responses.
+    pub phase: f64,
+    /// Delay of the envelope of a signal around the frequency, in samples.
+    pub group_delay: f64,
+}
+
+impl Components {
+    /// Evaluate the polynomial at the given value.
+    pub fn evaluate(&self, z: Complex) -> Complex {
+        self.components
+            .iter()
+            .rev()
+            .fold(Complex::ZERO, |result, factor| {
+                result * z + (*factor).into()
+            })
+    }
+
+    /// Polynomial of the derivative.
+    pub fn derivative(&self) -> Components {
+        Components {
+            components: self
+                .components
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, factor)| power as f64 * factor)
+                .collect(),
+        }
+    }
+}
+
+impl TransferFunction {
+    /// Point on the unit circle for the given frequency in Hz.
+    fn unit_circle(&self, frequency: f64) -> Complex {
+        Complex::from_polar(1.0, TAU * frequency * self.timestep)
+    }
+
+    /// Complex response of the transfer function at the given frequency in Hz.
+    pub fn response(&self, frequency: f64) -> Complex {
+        let z = self.unit_circle(frequency);
+        self.numerator.evaluate(z) / self.denominator.evaluate(z)
+    }
+
+    /// Gain in dB at the given frequency in Hz.
+    pub fn magnitude(&self, frequency: f64) -> f64 {
+        20.0 * self.response(frequency).norm().log10()
+    }
+
+    /// Phase shift in radians (-π..=π) at the given frequency in Hz.
+    pub fn phase(&self, frequency: f64) -> f64 {
+        self.response(frequency).arg()
+    }
+
+    /// Group delay in samples at the given frequency in Hz.
+    ///
+    /// Calculated as `Re(z*D'(z)/D(z)) - Re(z*N'(z)/N(z))`, which is the negative derivative
+    /// of the phase to the angular frequency.
+    pub fn group_delay(&self, frequency: f64) -> f64 {
+        let z = self.unit_circle(frequency);
+        let delay = |polynomial: &Components| {
+            (z * polynomial.derivative().evaluate(z) / polynomial.evaluate(z)).re
+        };
+        delay(&self.denominator) - delay(&self.numerator)
+    }
+
+    /// Response at each of the given frequencies. The phase is unwrapped, so it is
+    /// continuous when the frequencies are in increasing order.
+    pub fn frequency_response(&self, frequencies: &[f64]) -> Vec<FrequencyResponse> {
+        let mut previous_phase: Option<f64> = None;
+        frequencies
+            .iter()
+            .map(|frequency| {
+                let response = self.response(*frequency);
+                let mut phase = response.arg();
+                if let Some(previous_phase) = previous_phase {
+                    phase += TAU * ((previous_phase - phase) / TAU).round();
+                }
+                previous_phase = Some(phase);
+                FrequencyResponse {
+                    frequency: *frequency,
+                    magnitude: 20.0 * response.norm().log10(),
+                    phase,
+                    group_delay: self.group_delay(*frequency),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Logarithmically spaced frequencies between min and max frequency (inclusive).
+pub fn log_frequencies(min_frequency: f64, max_frequency: f64, count: usize) -> Vec<f64> {
+    let ratio = max_frequency / min_frequency;
+    (0..count)
+        .map(|index| {
+            let position = if count > 1 {
+                index as f64 / (count - 1) as f64
+            } else {
+                0.0
+            };
+            min_frequency * ratio.powf(position)
+        })
+        .collect()
+}
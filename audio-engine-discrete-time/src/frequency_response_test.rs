@@ -0,0 +1,60 @@
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use crate::{
+    biquad::{Biquad, BiquadType},
+    frequency_response::log_frequencies,
+    transfer_function::TransferFunction,
+};
+
+const SAMPLE_RATE: f64 = 44100.0;
+
+#[test]
+fn test_delay() {
+    let z = TransferFunction::new(1.0 / SAMPLE_RATE);
+    let delay = z.pow(-5);
+    for frequency in [100.0, 1000.0, 10000.0] {
+        assert!(delay.magnitude(frequency).abs() < 1e-9);
+        assert!((delay.group_delay(frequency) - 5.0).abs() < 1e-9);
+    }
+    // A quarter of the sample rate is a quarter turn back per sample, -5/4 turn wraps
+    // around to -1/4 turn.
+    let phase = delay.phase(SAMPLE_RATE / 4.0);
+    assert!((phase + PI / 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_low_pass() {
+    let low_pass =
+        Biquad::new(BiquadType::LowPass, 1000.0, FRAC_1_SQRT_2).transfer_function(SAMPLE_RATE);
+    assert!(low_pass.magnitude(10.0).abs() < 0.01);
+    assert!((low_pass.magnitude(1000.0) + 3.01).abs() < 0.01);
+    // A second order low pass shifts the phase by 90 degrees at the cutoff frequency.
+    assert!((low_pass.phase(1000.0) + PI / 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_unwrapped_phase() {
+    let z = TransferFunction::new(1.0 / SAMPLE_RATE);
+    let delay = z.pow(-10);
+    let frequencies = log_frequencies(20.0, 20000.0, 200);
+    let response = delay.frequency_response(&frequencies);
+    assert_eq!(200, response.len());
+    assert_eq!(20.0, response[0].frequency);
+    assert!((response[199].frequency - 20000.0).abs() < 1e-9);
+    for (point, frequency) in response.iter().zip(frequencies) {
+        let expected = -PI * 2.0 * 10.0 * frequency / SAMPLE_RATE;
+        assert!((point.phase - expected).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_all_pass_group_delay() {
+    let all_pass = Biquad::new(BiquadType::AllPass, 1000.0, 1.0).transfer_function(SAMPLE_RATE);
+    // Compare with the derivative of the phase.
+    let frequency = 2000.0;
+    let step = 0.01;
+    let phase_difference = all_pass.phase(frequency + step) - all_pass.phase(frequency - step);
+    let omega_difference = 2.0 * PI * 2.0 * step / SAMPLE_RATE;
+    let expected = -phase_difference / omega_difference;
+    assert!((all_pass.group_delay(frequency) - expected).abs() < 1e-4);
+}
@@ -1,6 +1,13 @@
 //! Discrete time math library
 //!
-//! Simulates matlabs tf, tfdata and filter functions.
+//! Simulates matlabs tf, tfdata and filter functions. Standard audio filters can be designed
+//! with [biquad::Biquad]. Transfer functions can be analysed by their frequency response,
+//! poles and zeros, and exported as a Bode plot with [bode_plot::BodePlot].
+//! [direct_form::DirectFormFilter] and [second_order_sections::SecondOrderSections] run
+//! filters without shifting the history for each sample.
+//! Analog filters, like [analog_prototype::AnalogPrototype] designs or physical models in
+//! [analog::AnalogTransferFunction], are converted to discrete time with the bilinear
+//! transform, impulse invariance or matched-Z.
 //! The implementation is limited in scope. Only operations needed by other crates have been
 //! implemented.
 //! 
@@ -8,10 +15,36 @@
 //! We might want to skip tddata and run the filter directly
 //! on the tf struct.
 
+pub mod analog;
+pub mod analog_prototype;
+pub mod biquad;
+pub mod bode_plot;
+pub mod complex;
 pub mod component;
 pub mod components;
+pub mod direct_form;
 pub mod filter;
+pub mod frequency_response;
+pub mod roots;
+pub mod second_order_sections;
 pub mod transfer_function;
+pub mod zeros_poles_gain;
 
+#[cfg(test)]
+pub mod analog_prototype_test;
+#[cfg(test)]
+pub mod analog_test;
+#[cfg(test)]
+pub mod biquad_test;
+#[cfg(test)]
+pub mod bode_plot_test;
+#[cfg(test)]
+pub mod direct_form_test;
+#[cfg(test)]
+pub mod frequency_response_test;
+#[cfg(test)]
+pub mod roots_test;
+#[cfg(test)]
+pub mod second_order_sections_test;
 #[cfg(test)]
 pub mod transfer_function_test;
@@ -0,0 +1,105 @@
+//! Poles and zeros of transfer functions.
+//!
+//! The roots of the polynomials are found with the Aberth-Ehrlich method. All roots are
+//! refined at the same time; each root is pushed away from the other approximations so
+//! they don't converge to the same root.
+use std::f64::consts::TAU;
+
+use crate::{complex::Complex, components::Components, transfer_function::TransferFunction};
+
+const MAX_ITERATIONS: usize = 500;
+const TOLERANCE: f64 = 1e-14;
+
+impl Components {
+    /// Degree of the polynomial, ignoring highest powers with a factor of zero.
+    pub fn degree(&self) -> usize {
+        self.components
+            .iter()
+            .rposition(|factor| *factor != 0.0)
+            .unwrap_or(0)
+    }
+
+    /// Complex roots of the polynomial.
+    pub fn roots(&self) -> Vec<Complex> {
+        let degree = self.degree();
+        let lowest = self
+            .components
+            .iter()
+            .position(|factor| *factor != 0.0)
+            .unwrap_or(0)
+            .min(degree);
+
+        // Lowest powers with a factor of zero are roots at the origin.
+        let mut result = vec![Complex::ZERO; lowest];
+        let polynomial = Components {
+            components: self.components[lowest..=degree].to_vec(),
+        };
+        result.extend(aberth(&polynomial));
+        result
+    }
+}
+
+fn aberth(polynomial: &Components) -> Vec<Complex> {
+    let degree = polynomial.components.len() - 1;
+    if degree == 0 {
+        return Vec::new();
+    }
+    let derivative = polynomial.derivative();
+    let highest = polynomial.components[degree];
+
+    // Start on a circle with the radius of the Cauchy bound, slightly rotated to avoid
+    // starting symmetric to the real axis.
+    let radius = 1.0
+        + polynomial.components[..degree]
+            .iter()
+            .map(|factor| (factor / highest).abs())
+            .fold(0.0, f64::max);
+    let mut roots = (0..degree)
+        .map(|index| Complex::from_polar(radius, TAU * (index as f64 + 0.25) / degree as f64))
+        .collect::<Vec<Complex>>();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut largest_step: f64 = 0.0;
+        for index in 0..degree {
+            let root = roots[index];
+            let value = polynomial.evaluate(root);
+            if value == Complex::ZERO {
+                continue;
+            }
+            let ratio = value / derivative.evaluate(root);
+            let repulsion = roots
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .fold(Complex::ZERO, |sum, (_, other)| {
+                    sum + Complex::ONE / (root - *other)
+                });
+            let step = ratio / (Complex::ONE - ratio * repulsion);
+            if step.re.is_finite() && step.im.is_finite() {
+                roots[index] = root - step;
+                largest_step = largest_step.max(step.norm() / root.norm().max(1.0));
+            }
+        }
+        if largest_step < TOLERANCE {
+            break;
+        }
+    }
+    roots
+}
+
+impl TransferFunction {
+    /// Values of z where the transfer function becomes infinite.
+    pub fn poles(&self) -> Vec<Complex> {
+        self.denominator.roots()
+    }
+
+    /// Values of z where the transfer function becomes zero.
+    pub fn zeros(&self) -> Vec<Complex> {
+        self.numerator.roots()
+    }
+
+    /// A discrete time system is stable when all poles are inside the unit circle.
+    pub fn is_stable(&self) -> bool {
+        self.poles().iter().all(|pole| pole.norm() < 1.0)
+    }
+}
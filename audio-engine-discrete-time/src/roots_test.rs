@@ -0,0 +1,85 @@
+use crate::{
+    biquad::{Biquad, BiquadType},
+    complex::Complex,
+    components::Components,
+    transfer_function::TransferFunction,
+};
+
+fn assert_roots(mut roots: Vec<Complex>, mut expected: Vec<Complex>) {
+    let key = |root: &Complex| {
+        (root.re * 1e6).round() as i64 * 1_000_000_000 + (root.im * 1e6).round() as i64
+    };
+    roots.sort_by_key(key);
+    expected.sort_by_key(key);
+    assert_eq!(roots.len(), expected.len(), "{roots:?}");
+    for (root, expected) in roots.iter().zip(expected) {
+        assert!((*root - expected).norm() < 1e-6, "{root} != {expected}");
+    }
+}
+
+#[test]
+fn test_real_roots() {
+    // (z - 1)(z + 2)(z - 0.5) = z^3 + 0.5z^2 - 2.5z + 1
+    let polynomial = Components {
+        components: vec![1.0, -2.5, 0.5, 1.0],
+    };
+    assert_eq!(3, polynomial.degree());
+    assert_roots(
+        polynomial.roots(),
+        vec![1.0.into(), (-2.0).into(), 0.5.into()],
+    );
+}
+
+#[test]
+fn test_complex_roots() {
+    // z^2 + 1
+    let polynomial = Components {
+        components: vec![1.0, 0.0, 1.0],
+    };
+    assert_roots(
+        polynomial.roots(),
+        vec![Complex::new(0.0, 1.0), Complex::new(0.0, -1.0)],
+    );
+}
+
+#[test]
+fn test_roots_at_origin() {
+    // z^3 - 0.5z^2, trailing zero factors are ignored.
+    let polynomial = Components {
+        components: vec![0.0, 0.0, -0.5, 1.0, 0.0],
+    };
+    assert_eq!(3, polynomial.degree());
+    assert_roots(
+        polynomial.roots(),
+        vec![Complex::ZERO, Complex::ZERO, 0.5.into()],
+    );
+}
+
+#[test]
+fn test_poles_and_zeros() {
+    let z = TransferFunction::new(1.0 / 44100.0);
+    // Hd=(ad+z^-1)/(1+ad*z^-1);
+    let ad = -0.30;
+    let hd = (ad + z.pow(-1)) / &(1.0 + ad * z.pow(-1));
+    assert_roots(hd.poles(), vec![0.3.into(), Complex::ZERO]);
+    assert_roots(hd.zeros(), vec![(1.0 / 0.3).into(), Complex::ZERO]);
+    assert!(hd.is_stable());
+
+    let unstable = 1.0 / &(1.0 + -1.5 * z.pow(-1));
+    assert!(!unstable.is_stable());
+}
+
+#[test]
+fn test_biquad_poles() {
+    for biquad_type in [BiquadType::LowPass, BiquadType::Peak, BiquadType::HighShelf] {
+        let biquad = Biquad::with_gain(biquad_type, 1000.0, 10.0, 6.0);
+        let transfer_function = biquad.transfer_function(44100.0);
+        let coefficients = biquad.coefficients(44100.0);
+        assert!(transfer_function.is_stable());
+        // Poles of a biquad are a complex conjugate pair: z^2 + a1*z + a2.
+        let poles = transfer_function.poles();
+        assert_eq!(2, poles.len());
+        assert!((poles[0] - poles[1].conj()).norm() < 1e-9);
+        assert!((poles[0].norm().powi(2) - coefficients.a[2]).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,286 @@
+//! Cascade of second order sections.
+//!
+//! A high order filter is sensitive to rounding of its coefficients; the poles of a single
+//! high order polynomial move a lot when a coefficient changes a little. Splitting the
+//! filter into sections of two poles and two zeros keeps each section well conditioned.
+use crate::{
+    biquad::BiquadCoefficients,
+    complex::Complex,
+    direct_form::{CoefficientRamp, FilterValue},
+    transfer_function::TransferFunction,
+    zeros_poles_gain::ZerosPolesGain,
+};
+
+/// Roots with a smaller imaginary part (relative to their magnitude) are real.
+const REAL_TOLERANCE: f64 = 1e-9;
+
+/// Second order filter using the direct form II transposed structure.
+#[derive(Debug, Clone)]
+pub struct SecondOrderSection<F> {
+    b: [F; 3],
+    /// `a[0]` is assumed to be 1 and isn't used.
+    a: [F; 3],
+    state: [F; 2],
+    ramp: Option<CoefficientRamp<([F; 3], [F; 3])>>,
+}
+
+impl<F> SecondOrderSection<F>
+where
+    F: FilterValue,
+{
+    pub fn new(b: [F; 3], a: [F; 3]) -> SecondOrderSection<F> {
+        SecondOrderSection {
+            b,
+            a,
+            state: [F::default(); 2],
+            ramp: None,
+        }
+    }
+
+    pub fn b(&self) -> &[F; 3] {
+        &self.b
+    }
+
+    pub fn a(&self) -> &[F; 3] {
+        &self.a
+    }
+
+    /// Clear the state, the filter continues as if it only received zeros.
+    pub fn reset(&mut self) {
+        self.state = [F::default(); 2];
+    }
+
+    /// Change the coefficients, linearly over `ramp_len` samples.
+    pub fn set_coefficients(&mut self, coefficients: &BiquadCoefficients, ramp_len: usize) {
+        let b = coefficients.b.map(F::from_f64);
+        let a = coefficients.a.map(F::from_f64);
+        if ramp_len == 0 {
+            self.b = b;
+            self.a = a;
+            self.ramp = None;
+        } else {
+            let scale = F::from_f64(1.0 / ramp_len as f64);
+            let step = |target: [F; 3], current: [F; 3]| {
+                [0, 1, 2].map(|index| (target[index] - current[index]) * scale)
+            };
+            self.ramp = Some(CoefficientRamp {
+                step: (step(b, self.b), step(a, self.a)),
+                target: (b, a),
+                remaining: ramp_len,
+            });
+        }
+    }
+
+    fn advance_ramp(&mut self) {
+        let Some(ramp) = &mut self.ramp else {
+            return;
+        };
+        ramp.remaining -= 1;
+        if ramp.remaining == 0 {
+            (self.b, self.a) = ramp.target;
+            self.ramp = None;
+        } else {
+            for index in 0..3 {
+                self.b[index] = self.b[index] + ramp.step.0[index];
+                self.a[index] = self.a[index] + ramp.step.1[index];
+            }
+        }
+    }
+
+    pub fn filter(&mut self, value_in: F) -> F {
+        self.advance_ramp();
+        let result = self.b[0] * value_in + self.state[0];
+        self.state[0] = self.b[1] * value_in - self.a[1] * result + self.state[1];
+        self.state[1] = self.b[2] * value_in - self.a[2] * result;
+        result
+    }
+
+    /// Filter the samples in place.
+    pub fn filter_block(&mut self, samples: &mut [F]) {
+        for sample in samples {
+            *sample = self.filter(*sample);
+        }
+    }
+}
+
+impl<F> From<BiquadCoefficients> for SecondOrderSection<F>
+where
+    F: FilterValue,
+{
+    fn from(coefficients: BiquadCoefficients) -> Self {
+        SecondOrderSection::new(
+            coefficients.b.map(F::from_f64),
+            coefficients.a.map(F::from_f64),
+        )
+    }
+}
+
+/// Filters the signal with each section in order.
+#[derive(Debug, Clone)]
+pub struct SecondOrderSections<F> {
+    pub sections: Vec<SecondOrderSection<F>>,
+}
+
+impl<F> SecondOrderSections<F>
+where
+    F: FilterValue,
+{
+    pub fn reset(&mut self) {
+        self.sections.iter_mut().for_each(SecondOrderSection::reset);
+    }
+
+    /// Change the coefficients of each section, linearly over `ramp_len` samples.
+    ///
+    /// # Panics
+    ///
+    /// When the number of sections changed.
+    pub fn set_coefficients(&mut self, coefficients: &[BiquadCoefficients], ramp_len: usize) {
+        assert_eq!(self.sections.len(), coefficients.len());
+        for (section, coefficients) in self.sections.iter_mut().zip(coefficients) {
+            section.set_coefficients(coefficients, ramp_len);
+        }
+    }
+
+    pub fn filter(&mut self, value_in: F) -> F {
+        self.sections
+            .iter_mut()
+            .fold(value_in, |value, section| section.filter(value))
+    }
+
+    /// Filter the samples in place, one section at a time.
+    pub fn filter_block(&mut self, samples: &mut [F]) {
+        for section in &mut self.sections {
+            section.filter_block(samples);
+        }
+    }
+}
+
+impl<F> From<&[BiquadCoefficients]> for SecondOrderSections<F>
+where
+    F: FilterValue,
+{
+    fn from(coefficients: &[BiquadCoefficients]) -> Self {
+        SecondOrderSections {
+            sections: coefficients
+                .iter()
+                .map(|coefficients| SecondOrderSection::from(*coefficients))
+                .collect(),
+        }
+    }
+}
+
+impl<F> From<&TransferFunction> for SecondOrderSections<F>
+where
+    F: FilterValue,
+{
+    fn from(transfer_function: &TransferFunction) -> Self {
+        SecondOrderSections::from(transfer_function.second_order_sections().as_slice())
+    }
+}
+
+impl<F> From<&ZerosPolesGain> for SecondOrderSections<F>
+where
+    F: FilterValue,
+{
+    fn from(zeros_poles_gain: &ZerosPolesGain) -> Self {
+        SecondOrderSections::from(zeros_poles_gain.second_order_sections().as_slice())
+    }
+}
+
+impl TransferFunction {
+    /// Split the transfer function into second order sections, see
+    /// [ZerosPolesGain::second_order_sections].
+    pub fn second_order_sections(&self) -> Vec<BiquadCoefficients> {
+        self.zeros_poles_gain().second_order_sections()
+    }
+}
+
+impl ZerosPolesGain {
+    /// Split the discrete time transfer function into second order sections.
+    ///
+    /// Complex poles and zeros are kept together with their conjugate. Each pair of poles is
+    /// combined with the pair of zeros closest to it, starting with the poles closest to the
+    /// unit circle. Sections are ordered from the poles furthest from to closest to the unit
+    /// circle, the gain is part of the first section.
+    ///
+    /// When there are less zeros than poles (or the other way around) the shorter list is
+    /// extended with roots at the origin, so an improper transfer function is delayed.
+    pub fn second_order_sections(&self) -> Vec<BiquadCoefficients> {
+        let mut poles = self.poles.clone();
+        let mut zeros = self.zeros.clone();
+        let len = poles.len().max(zeros.len());
+        poles.resize(len, Complex::ZERO);
+        zeros.resize(len, Complex::ZERO);
+
+        let mut pole_pairs = root_pairs(poles);
+        let mut zero_pairs = root_pairs(zeros);
+        pole_pairs.sort_by(|a, b| max_norm(a).total_cmp(&max_norm(b)));
+
+        let mut sections = pole_pairs
+            .iter()
+            .rev()
+            .map(|pole_pair| {
+                let closest = zero_pairs
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| {
+                        distance(a, pole_pair).total_cmp(&distance(b, pole_pair))
+                    })
+                    .map(|(index, _)| index)
+                    .unwrap();
+                let zero_pair = zero_pairs.swap_remove(closest);
+                BiquadCoefficients {
+                    b: polynomial(&zero_pair),
+                    a: polynomial(pole_pair),
+                }
+            })
+            .collect::<Vec<BiquadCoefficients>>();
+        sections.reverse();
+        if sections.is_empty() {
+            sections.push(BiquadCoefficients {
+                b: [1.0, 0.0, 0.0],
+                a: [1.0, 0.0, 0.0],
+            });
+        }
+
+        sections[0].b = sections[0].b.map(|b| b * self.gain);
+        sections
+    }
+}
+
+/// Group the roots in conjugate pairs and pairs of real roots. An odd number of real roots
+/// leaves a single root.
+fn root_pairs(roots: Vec<Complex>) -> Vec<Vec<Complex>> {
+    let is_real = |root: &Complex| root.im.abs() <= REAL_TOLERANCE * root.norm().max(1.0);
+    let mut real = roots
+        .iter()
+        .filter(|root| is_real(root))
+        .map(|root| Complex::from(root.re))
+        .collect::<Vec<Complex>>();
+    real.sort_by(|a, b| a.re.total_cmp(&b.re));
+
+    let mut result = roots
+        .iter()
+        .filter(|root| !is_real(root) && root.im > 0.0)
+        .map(|root| vec![*root, root.conj()])
+        .collect::<Vec<Vec<Complex>>>();
+    result.extend(real.chunks(2).map(|pair| pair.to_vec()));
+    result
+}
+
+fn max_norm(roots: &[Complex]) -> f64 {
+    roots.iter().map(Complex::norm).fold(0.0, f64::max)
+}
+
+fn distance(a: &[Complex], b: &[Complex]) -> f64 {
+    (a[0] - b[0]).norm()
+}
+
+/// Coefficients of `(1 - r1*z^-1)(1 - r2*z^-1)` for one or two roots.
+fn polynomial(roots: &[Complex]) -> [f64; 3] {
+    match roots {
+        [root] => [1.0, -root.re, 0.0],
+        [root_1, root_2] => [1.0, -(*root_1 + *root_2).re, (*root_1 * *root_2).re],
+        _ => [1.0, 0.0, 0.0],
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    biquad::{Biquad, BiquadCoefficients, BiquadType},
+    direct_form::DirectFormFilter,
+    second_order_sections::{SecondOrderSection, SecondOrderSections},
+    transfer_function::TransferFunction,
+};
+
+const SAMPLE_RATE: f64 = 44100.0;
+
+fn input(index: usize) -> f64 {
+    if index < 50 {
+        (index as f64 * 0.7).sin()
+    } else {
+        0.0
+    }
+}
+
+fn biquads() -> Vec<Biquad> {
+    vec![
+        Biquad::new(BiquadType::LowPass, 2000.0, 0.54),
+        Biquad::new(BiquadType::LowPass, 2000.0, 1.31),
+        Biquad::with_gain(BiquadType::Peak, 300.0, 4.0, -6.0),
+    ]
+}
+
+#[test]
+fn test_section_matches_direct_form() {
+    let coefficients = Biquad::new(BiquadType::HighPass, 300.0, 2.0).coefficients(SAMPLE_RATE);
+    let mut section = SecondOrderSection::<f64>::from(coefficients);
+    let mut direct_form = DirectFormFilter::new(&coefficients.b, &coefficients.a);
+    for index in 0..500 {
+        assert_eq!(
+            direct_form.filter(input(index)),
+            section.filter(input(index))
+        );
+    }
+}
+
+#[test]
+fn test_cascade() {
+    let coefficients = biquads()
+        .iter()
+        .map(|biquad| biquad.coefficients(SAMPLE_RATE))
+        .collect::<Vec<BiquadCoefficients>>();
+    let mut cascade = SecondOrderSections::<f64>::from(coefficients.as_slice());
+    let mut block_cascade = cascade.clone();
+    let mut sections = coefficients
+        .iter()
+        .map(|coefficients| SecondOrderSection::<f64>::from(*coefficients))
+        .collect::<Vec<SecondOrderSection<f64>>>();
+
+    let mut samples = (0..500).map(input).collect::<Vec<f64>>();
+    block_cascade.filter_block(&mut samples);
+    for (index, block_result) in samples.iter().enumerate() {
+        let expected = sections
+            .iter_mut()
+            .fold(input(index), |value, section| section.filter(value));
+        assert_eq!(expected, cascade.filter(input(index)));
+        assert!((expected - block_result).abs() < 1e-15);
+    }
+}
+
+#[test]
+fn test_from_transfer_function() {
+    let transfer_function = biquads()
+        .iter()
+        .map(|biquad| biquad.transfer_function(SAMPLE_RATE))
+        .reduce(|a, b| a * b)
+        .unwrap();
+    let sections = transfer_function.second_order_sections();
+    assert_eq!(3, sections.len());
+
+    // The roots of the product are less precise than the coefficients of the biquads. The
+    // four zeros at -1 of the low pass filters are only found up to about 1e-4.
+    let mut cascade = SecondOrderSections::<f64>::from(&transfer_function);
+    let mut expected_cascade = SecondOrderSections::<f64>::from(
+        biquads()
+            .iter()
+            .map(|biquad| biquad.coefficients(SAMPLE_RATE))
+            .collect::<Vec<BiquadCoefficients>>()
+            .as_slice(),
+    );
+    for index in 0..2000 {
+        let expected = expected_cascade.filter(input(index));
+        let result = cascade.filter(input(index));
+        assert!(
+            (expected - result).abs() < 1e-5 * expected.abs().max(1e-3),
+            "{index}: {expected} != {result}"
+        );
+    }
+}
+
+#[test]
+fn test_odd_order_and_gain() {
+    let z = TransferFunction::new(1.0 / SAMPLE_RATE);
+    // Hl=gl*(1+al)/(1+al*z^-1) with a delay of 2 samples.
+    let gl = -0.99;
+    let al = -0.001;
+    let transfer_function = gl * (1.0 + al) / &(1.0 + al * z.pow(-1)) * &z.pow(-2);
+    let sections = transfer_function.second_order_sections();
+    assert_eq!(2, sections.len());
+
+    let mut cascade = SecondOrderSections::<f64>::from(sections.as_slice());
+    let mut direct_form = DirectFormFilter::from(transfer_function);
+    for index in 0..100 {
+        let expected = direct_form.filter(input(index));
+        let result = cascade.filter(input(index));
+        assert!((expected - result).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_constant() {
+    let z = TransferFunction::new(1.0 / SAMPLE_RATE);
+    let transfer_function = 0.5 * z.pow(0);
+    let mut cascade = SecondOrderSections::<f32>::from(&transfer_function);
+    assert_eq!(0.5, cascade.filter(1.0));
+}
+
+/// Largest difference between two output samples after changing the coefficients.
+fn max_step(mut section: SecondOrderSection<f32>, to: &BiquadCoefficients, ramp_len: usize) -> f32 {
+    section.set_coefficients(to, ramp_len);
+    let mut previous = 1.0;
+    let mut result: f32 = 0.0;
+    for _ in 0..400 {
+        let sample = section.filter(1.0);
+        result = result.max((sample - previous).abs());
+        previous = sample;
+    }
+    result
+}
+
+#[test]
+fn test_ramp_coefficients() {
+    let from = Biquad::new(BiquadType::LowPass, 500.0, 0.7).coefficients(SAMPLE_RATE);
+    let to = Biquad::new(BiquadType::LowPass, 5000.0, 0.7).coefficients(SAMPLE_RATE);
+    let mut section = SecondOrderSection::<f32>::from(from);
+    // Settle on a DC input, the output of a low pass is the input.
+    for _ in 0..10000 {
+        section.filter(1.0);
+    }
+
+    assert!(max_step(section.clone(), &to, 0) > 0.5);
+    assert!(max_step(section.clone(), &to, 64) < 0.05);
+
+    section.set_coefficients(&to, 64);
+    for _ in 0..400 {
+        section.filter(1.0);
+    }
+    assert_eq!(&to.b.map(|b| b as f32), section.b());
+    assert!((section.filter(1.0) - 1.0).abs() < 1e-4);
+}
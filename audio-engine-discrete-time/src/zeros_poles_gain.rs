@@ -0,0 +1,91 @@
+//! Transfer functions described by their zeros, poles and gain.
+//!
+//! ```text
+//!            (x - z1)(x - z2)..
+//! H(x) = k * ------------------
+//!            (x - p1)(x - p2)..
+//! ```
+//!
+//! The same description is used for analog (`x = s`) and discrete time (`x = z`) transfer
+//! functions. Filter designs keep their roots in this form; expanding a high order filter
+//! into polynomials and finding the roots again loses a lot of precision.
+use crate::{complex::Complex, components::Components, transfer_function::TransferFunction};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ZerosPolesGain {
+    pub zeros: Vec<Complex>,
+    pub poles: Vec<Complex>,
+    /// Ratio between the highest factors of the numerator and denominator.
+    pub gain: f64,
+}
+
+impl ZerosPolesGain {
+    /// Evaluate the transfer function at the given value.
+    pub fn evaluate(&self, x: Complex) -> Complex {
+        let product = |roots: &[Complex]| {
+            roots
+                .iter()
+                .fold(Complex::ONE, |product, root| product * (x - *root))
+        };
+        product(&self.zeros) / product(&self.poles) * self.gain
+    }
+
+    /// Numerator polynomial, complex zeros are expected to come with their conjugate.
+    pub fn numerator(&self) -> Components {
+        &real_part(&expand(&self.zeros)) * self.gain
+    }
+
+    /// Denominator polynomial, complex poles are expected to come with their conjugate.
+    pub fn denominator(&self) -> Components {
+        real_part(&expand(&self.poles))
+    }
+
+    /// Discrete time transfer function with the given timestep.
+    pub fn to_transfer_function(&self, timestep: f64) -> TransferFunction {
+        TransferFunction {
+            timestep,
+            numerator: self.numerator(),
+            denominator: self.denominator(),
+        }
+    }
+}
+
+impl TransferFunction {
+    pub fn zeros_poles_gain(&self) -> ZerosPolesGain {
+        ZerosPolesGain {
+            zeros: self.zeros(),
+            poles: self.poles(),
+            gain: self.numerator.components[self.numerator.degree()]
+                / self.denominator.components[self.denominator.degree()],
+        }
+    }
+}
+
+/// Complex coefficients, in ascending powers, of the polynomial with the given roots and a
+/// highest factor of 1.
+pub(crate) fn expand(roots: &[Complex]) -> Vec<Complex> {
+    let mut result = vec![Complex::ONE];
+    for root in roots {
+        // Multiply with (x - root).
+        result.push(Complex::ZERO);
+        for index in (0..result.len()).rev() {
+            let lower = if index > 0 {
+                result[index - 1]
+            } else {
+                Complex::ZERO
+            };
+            result[index] = lower - result[index] * *root;
+        }
+    }
+    result
+}
+
+/// Real parts of the coefficients; the imaginary parts cancel out for conjugate roots.
+pub(crate) fn real_part(coefficients: &[Complex]) -> Components {
+    Components {
+        components: coefficients
+            .iter()
+            .map(|coefficient| coefficient.re)
+            .collect(),
+    }
+}
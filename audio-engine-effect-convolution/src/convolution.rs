@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use audio_engine_common::{
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    lfo::{target::LfoTarget, Lfo},
+    modulation::destination::ModulationDestination,
+};
+use audio_engine_effect::effect::Effect;
+
+use crate::{
+    convolution_state::ConvolutionState,
+    impulse_response::ImpulseResponse,
+    partitioned_convolution::{PartitionedConvolution, PartitionedImpulseResponse},
+};
+
+/// Convolution effect, convolves the signal with an impulse response. With the impulse
+/// response of a room this is a convolution reverb.
+///
+/// The convolution is uniformly partitioned, the output has no latency.
+#[derive(Debug, Clone)]
+pub struct Convolution {
+    pub is_enabled: bool,
+    /// Impulse response to convolve with. It is resampled when its sample rate differs from
+    /// the sample rate the effect is applied at.
+    pub impulse_response: Option<Arc<ImpulseResponse>>,
+    /// Samples per partition of the impulse response. Smaller blocks need more calculations
+    /// per sample, larger blocks less, but each block does more work at once.
+    pub block_size: usize,
+    /// Dry/wet mix (0..=1), 0 is only the dry signal and 1 only the convolved signal.
+    pub mix: f32,
+    /// Optional lfo modulating the mix. Depth is relative to the mix.
+    pub lfo: Option<Lfo>,
+}
+
+impl Default for Convolution {
+    fn default() -> Self {
+        Convolution {
+            is_enabled: false,
+            impulse_response: None,
+            block_size: 64,
+            mix: 0.3,
+            lfo: None,
+        }
+    }
+}
+
+impl Convolution {
+    /// Partition the impulse response when the design changed.
+    fn update_state(&self, sample_rate: f32, effect_state: &mut ConvolutionState) {
+        let Some(impulse_response) = &self.impulse_response else {
+            effect_state.design = None;
+            effect_state.convolution = None;
+            return;
+        };
+        let is_current =
+            effect_state
+                .design
+                .as_ref()
+                .is_some_and(|(design, design_sample_rate, block_size)| {
+                    Arc::ptr_eq(design, impulse_response)
+                        && *design_sample_rate == sample_rate
+                        && *block_size == self.block_size
+                });
+        if is_current {
+            return;
+        }
+        effect_state.design = Some((impulse_response.clone(), sample_rate, self.block_size));
+        let resampled = impulse_response.resample(sample_rate);
+        effect_state.convolution = Some(PartitionedConvolution::new(Arc::new(
+            PartitionedImpulseResponse::new(&resampled.samples, self.block_size),
+        )));
+    }
+
+    fn sample(&self, input: f32, sample_rate: f32, effect_state: &mut ConvolutionState) -> f32 {
+        let mut mix = LfoTarget::Amplitude.apply(self.mix, effect_state.mix_modulation);
+        if let Some(lfo) = &self.lfo {
+            let modulation = lfo.sample(
+                &NoteParameters {
+                    note_time: effect_state.lfo_time,
+                    note_off: None,
+                    note_pitch: 0.0,
+                    gain: 1.0,
+                    sample_rate,
+                },
+                &mut effect_state.lfo,
+            );
+            effect_state.lfo_time += 1.0 / sample_rate;
+            mix = LfoTarget::Amplitude.apply(mix, modulation);
+        }
+        let mix = mix.clamp(0.0, 1.0);
+
+        let wet = match &mut effect_state.convolution {
+            Some(convolution) => convolution.process(input),
+            None => 0.0,
+        };
+        input * (1.0 - mix) + wet * mix
+    }
+}
+
+impl Effect for Convolution {
+    type EffectState = ConvolutionState;
+
+    fn effect_create_state(&self) -> Self::EffectState {
+        ConvolutionState {
+            lfo: self
+                .lfo
+                .map(|lfo| lfo.init_sound_state())
+                .unwrap_or_default(),
+            ..ConvolutionState::default()
+        }
+    }
+
+    fn effect_apply(
+        &self,
+        audio_buffer: &mut [f32],
+        sample_rate: f32,
+        effect_state: &mut Self::EffectState,
+    ) {
+        if !self.is_enabled {
+            return;
+        }
+        self.update_state(sample_rate, effect_state);
+        audio_buffer.iter_mut().for_each(|out_sample| {
+            *out_sample = self.sample(*out_sample, sample_rate, effect_state);
+        });
+    }
+}
+
+impl ModulationDestination for Convolution {
+    type State = ConvolutionState;
+
+    /// The mix can be modulated relative to the mix of the effect.
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("mix")]
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        state.mix_modulation = 0.0;
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        match parameter {
+            "mix" => state.mix_modulation += modulation,
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use audio_engine_common::modulation::destination::ModulationDestination;
+    use audio_engine_effect::effect::Effect;
+
+    use crate::impulse_response::ImpulseResponse;
+
+    use super::Convolution;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// Exponentially decaying noise, like the tail of a room.
+    fn room(len: usize) -> Arc<ImpulseResponse> {
+        let mut seed = 1_u32;
+        let samples = (0..len)
+            .map(|index| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+                noise * (-(index as f32) / 2000.0).exp()
+            })
+            .collect();
+        Arc::new(ImpulseResponse::new(samples, SAMPLE_RATE))
+    }
+
+    fn wet_convolution(impulse_response: Arc<ImpulseResponse>) -> Convolution {
+        Convolution {
+            is_enabled: true,
+            impulse_response: Some(impulse_response),
+            mix: 1.0,
+            ..Convolution::default()
+        }
+    }
+
+    #[test]
+    fn disabled_convolution_passes_signal() {
+        let convolution = Convolution {
+            impulse_response: Some(room(100)),
+            ..Convolution::default()
+        };
+        let mut samples = [1.0, 0.5, 0.0, -0.5];
+        let mut state = convolution.effect_create_state();
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([1.0, 0.5, 0.0, -0.5], samples);
+    }
+
+    #[test]
+    fn impulse_gives_impulse_response() {
+        let impulse_response = room(10000);
+        let convolution = wet_convolution(impulse_response.clone());
+        let mut state = convolution.effect_create_state();
+        let mut samples = vec![0.0; 12000];
+        samples[0] = 1.0;
+        // Apply in buffers of a single sample, like the tracker does.
+        for sample in samples.chunks_mut(1) {
+            convolution.effect_apply(sample, SAMPLE_RATE, &mut state);
+        }
+        for (expected, actual) in impulse_response.samples.iter().zip(&samples) {
+            assert!((expected - actual).abs() < 1e-5, "{expected} != {actual}");
+        }
+        assert!(samples[10000..].iter().all(|sample| sample.abs() < 1e-5));
+    }
+
+    #[test]
+    fn mix() {
+        let convolution = Convolution {
+            mix: 0.25,
+            ..wet_convolution(Arc::new(ImpulseResponse::new(vec![0.0, 1.0], SAMPLE_RATE)))
+        };
+        let mut state = convolution.effect_create_state();
+        let mut samples = [1.0, 0.0, 0.0];
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([0.75, 0.25, 0.0], samples);
+    }
+
+    #[test]
+    fn resamples_impulse_response() {
+        let convolution = wet_convolution(Arc::new(ImpulseResponse::new(
+            vec![1.0; 100],
+            SAMPLE_RATE / 2.0,
+        )));
+        let mut state = convolution.effect_create_state();
+        let mut samples = vec![0.0; 300];
+        samples[0] = 1.0;
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert!(samples[150] > 0.0);
+        assert!(samples[250..].iter().all(|sample| sample.abs() < 1e-5));
+    }
+
+    #[test]
+    fn changing_impulse_response() {
+        let mut convolution = wet_convolution(room(100));
+        let mut state = convolution.effect_create_state();
+        let mut samples = [1.0];
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+
+        convolution.impulse_response = Some(Arc::new(ImpulseResponse::new(vec![0.5], SAMPLE_RATE)));
+        let mut samples = [1.0];
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([0.5], samples);
+
+        convolution.impulse_response = None;
+        let mut samples = [1.0];
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([0.0], samples);
+    }
+
+    #[test]
+    fn modulation() {
+        let convolution = wet_convolution(room(100));
+        let mut state = convolution.effect_create_state();
+        assert!(convolution.modulate("mix", -1.0, &mut state));
+        assert!(!convolution.modulate("level", 1.0, &mut state));
+
+        // The mix is modulated to 0, only the dry signal remains.
+        let mut samples = [1.0, 0.0, 0.0, 0.0];
+        convolution.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([1.0, 0.0, 0.0, 0.0], samples);
+
+        convolution.clear_modulation(&mut state);
+        assert_eq!(0.0, state.mix_modulation);
+    }
+}
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use audio_engine_common::{lfo::LfoState, note_time::NoteTime};
+use audio_engine_effect::effect_state::EffectState;
+
+use crate::{impulse_response::ImpulseResponse, partitioned_convolution::PartitionedConvolution};
+
+#[derive(Default, Debug, Clone)]
+pub struct ConvolutionState {
+    /// Convolution of `design`, created when the effect is first applied.
+    pub convolution: Option<PartitionedConvolution>,
+    /// Impulse response, sample rate and block size the convolution is created for.
+    pub design: Option<(Arc<ImpulseResponse>, f32, usize)>,
+    pub lfo: LfoState,
+    /// Time since the state was created, used to sample the lfo.
+    pub lfo_time: NoteTime,
+    /// Mix modulation of a modulation matrix, relative to the mix.
+    pub mix_modulation: f32,
+}
+impl EffectState for ConvolutionState {}
@@ -0,0 +1,123 @@
+use std::{fs::File, io, path::Path};
+
+use audio_engine_common::convert_f32s::Convert2F32;
+
+/// Mono impulse response, for example a recording of a room, a speaker cabinet or the
+/// soundboard of an instrument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImpulseResponse {
+    pub samples: Vec<f32>,
+    /// Sample rate the impulse response is recorded at.
+    pub sample_rate: f32,
+}
+
+impl ImpulseResponse {
+    pub fn new(samples: Vec<f32>, sample_rate: f32) -> ImpulseResponse {
+        ImpulseResponse {
+            samples,
+            sample_rate,
+        }
+    }
+
+    /// Impulse response from samples in double precision, for example a static array.
+    pub fn from_f64(samples: &[f64], sample_rate: f32) -> ImpulseResponse {
+        ImpulseResponse::new(
+            samples.iter().map(|sample| *sample as f32).collect(),
+            sample_rate,
+        )
+    }
+
+    /// Read an impulse response from a wav file. Multiple channels are mixed to mono.
+    pub fn from_wav(path: impl AsRef<Path>) -> io::Result<ImpulseResponse> {
+        let mut file = File::open(path)?;
+        let (header, data) = wav::read(&mut file)?;
+        let samples = data.to_f32s();
+        let channel_count = header.channel_count.max(1) as usize;
+        let samples = samples
+            .chunks(channel_count)
+            .map(|frame| frame.iter().sum::<f32>() / channel_count as f32)
+            .collect();
+        Ok(ImpulseResponse::new(samples, header.sampling_rate as f32))
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Duration of the impulse response in seconds.
+    pub fn duration(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate
+    }
+
+    /// Impulse response at another sample rate, using linear interpolation.
+    ///
+    /// The samples are scaled by the ratio of the sample rates, so the convolution keeps
+    /// the same gain: an impulse response with twice the samples would otherwise be twice
+    /// as loud.
+    pub fn resample(&self, sample_rate: f32) -> ImpulseResponse {
+        if sample_rate == self.sample_rate || self.samples.is_empty() {
+            return ImpulseResponse::new(self.samples.clone(), sample_rate);
+        }
+        let step = self.sample_rate / sample_rate;
+        let gain = step;
+        let len = (self.samples.len() as f32 / step).ceil() as usize;
+        let last = self.samples.len() - 1;
+        let samples = (0..len)
+            .map(|index| {
+                let position = index as f32 * step;
+                let before = (position as usize).min(last);
+                let after = (before + 1).min(last);
+                let fraction = position - before as f32;
+                let sample =
+                    self.samples[before] * (1.0 - fraction) + self.samples[after] * fraction;
+                sample * gain
+            })
+            .collect();
+        ImpulseResponse::new(samples, sample_rate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ImpulseResponse;
+
+    #[test]
+    fn resample_keeps_gain() {
+        let impulse_response = ImpulseResponse::new(vec![1.0; 100], 22050.0);
+        let resampled = impulse_response.resample(44100.0);
+        assert_eq!(44100.0, resampled.sample_rate);
+        assert_eq!(200, resampled.len());
+        let sum = resampled.samples.iter().sum::<f32>();
+        assert!((sum - 100.0).abs() < 1.0, "{sum}");
+    }
+
+    #[test]
+    fn resample_interpolates() {
+        let impulse_response = ImpulseResponse::new(vec![0.0, 1.0, 0.0], 22050.0);
+        let resampled = impulse_response.resample(44100.0);
+        assert_eq!(vec![0.0, 0.25, 0.5, 0.25, 0.0, 0.0], resampled.samples);
+    }
+
+    #[test]
+    fn resample_same_sample_rate() {
+        let impulse_response = ImpulseResponse::new(vec![0.5, 0.25], 44100.0);
+        assert_eq!(impulse_response, impulse_response.resample(44100.0));
+    }
+
+    #[test]
+    fn read_wav() {
+        let path = std::env::temp_dir().join("audio-engine-effect-convolution-test.wav");
+        let header = wav::Header::new(wav::header::WAV_FORMAT_PCM, 2, 48000, 16);
+        let data = wav::BitDepth::Sixteen(vec![16384, 0, -16384, -16384]);
+        wav::write(header, &data, &mut std::fs::File::create(&path).unwrap()).unwrap();
+
+        let impulse_response = ImpulseResponse::from_wav(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(48000.0, impulse_response.sample_rate);
+        assert_eq!(vec![0.25, -0.5], impulse_response.samples);
+    }
+}
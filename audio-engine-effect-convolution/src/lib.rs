@@ -0,0 +1,4 @@
+pub mod convolution;
+pub mod convolution_state;
+pub mod impulse_response;
+pub mod partitioned_convolution;
@@ -0,0 +1,282 @@
+//! Uniformly partitioned convolution.
+//!
+//! The impulse response is split into partitions of `block_size` samples. The first
+//! partition is convolved directly in the time domain, so the output has no latency. The
+//! other partitions are convolved in the frequency domain with overlap-save: each completed
+//! block of input is transformed once and kept in a frequency domain delay line. The tail of
+//! the next block is the sum of the delayed spectra multiplied with the spectra of the
+//! partitions.
+//!
+//! Per sample this costs `block_size` multiplications for the first partition. Per block it
+//! costs a forward and an inverse FFT of `2 * block_size` samples and a multiplication per
+//! partition and bin, compared to a multiplication per sample of the impulse response for
+//! each sample with a direct convolution. All buffers are allocated when the convolution
+//! is created, processing doesn't allocate memory.
+use std::sync::Arc;
+
+use audio_engine_fourier::{
+    complex_number::{ComplexNumber, ComplexNumberMethods},
+    fft::RealFft,
+};
+
+/// Impulse response split into partitions, ready to be shared by multiple convolutions.
+#[derive(Debug, Clone)]
+pub struct PartitionedImpulseResponse {
+    block_size: usize,
+    /// Transform of `2 * block_size` samples.
+    fft: RealFft,
+    /// First partition, convolved in the time domain.
+    head: Vec<f32>,
+    /// Spectra of the other partitions, each zero padded to `2 * block_size` samples.
+    partitions: Vec<Vec<ComplexNumber>>,
+    len: usize,
+}
+
+impl PartitionedImpulseResponse {
+    /// Split the samples in partitions of `block_size` samples. A power of two block size
+    /// gives the fastest transforms.
+    pub fn new(samples: &[f32], block_size: usize) -> PartitionedImpulseResponse {
+        let block_size = block_size.max(1);
+        let fft = RealFft::new(2 * block_size);
+        let partitions = samples
+            .chunks(block_size)
+            .skip(1)
+            .map(|partition| {
+                let mut padded = partition.to_vec();
+                padded.resize(2 * block_size, 0.0);
+                fft.forward(&padded)
+            })
+            .collect();
+        PartitionedImpulseResponse {
+            block_size,
+            fft,
+            head: samples[..samples.len().min(block_size)].to_vec(),
+            partitions,
+            len: samples.len(),
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Number of samples of the impulse response.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Convolution of a stream of samples with a partitioned impulse response.
+#[derive(Debug, Clone)]
+pub struct PartitionedConvolution {
+    impulse_response: Arc<PartitionedImpulseResponse>,
+    /// The previous block of input followed by the current block.
+    history: Vec<f32>,
+    /// Position of the next sample in the current block.
+    position: usize,
+    /// Spectra of the previous input frames, one for each partition. Used as a ring
+    /// buffer, the spectrum of a new frame replaces the oldest one.
+    delay_line: Vec<Vec<ComplexNumber>>,
+    /// Index of the newest spectrum in the delay line.
+    newest: usize,
+    /// Sum of the delayed spectra multiplied with the partitions.
+    spectrum: Vec<ComplexNumber>,
+    /// Inverse transform of the spectrum.
+    output: Vec<f32>,
+    /// Scratch space of the transforms.
+    scratch: Vec<ComplexNumber>,
+    /// Output of all but the first partition for the current block.
+    tail: Vec<f32>,
+}
+
+impl PartitionedConvolution {
+    pub fn new(impulse_response: Arc<PartitionedImpulseResponse>) -> PartitionedConvolution {
+        let block_size = impulse_response.block_size;
+        let fft = &impulse_response.fft;
+        PartitionedConvolution {
+            history: vec![0.0; 2 * block_size],
+            position: 0,
+            delay_line: vec![
+                vec![(0.0, 0.0); fft.spectrum_len()];
+                impulse_response.partitions.len()
+            ],
+            newest: 0,
+            spectrum: vec![(0.0, 0.0); fft.spectrum_len()],
+            output: vec![0.0; fft.len()],
+            scratch: vec![(0.0, 0.0); fft.scratch_len()],
+            tail: vec![0.0; block_size],
+            impulse_response,
+        }
+    }
+
+    pub fn impulse_response(&self) -> &Arc<PartitionedImpulseResponse> {
+        &self.impulse_response
+    }
+
+    /// Clear the state, the convolution continues as if it only received zeros.
+    pub fn reset(&mut self) {
+        self.history.fill(0.0);
+        self.position = 0;
+        for spectrum in &mut self.delay_line {
+            spectrum.fill((0.0, 0.0));
+        }
+        self.newest = 0;
+        self.tail.fill(0.0);
+    }
+
+    /// Convolve the next sample.
+    pub fn process(&mut self, value_in: f32) -> f32 {
+        let block_size = self.impulse_response.block_size;
+        let current = block_size + self.position;
+        self.history[current] = value_in;
+
+        let head = self
+            .impulse_response
+            .head
+            .iter()
+            .enumerate()
+            .map(|(delay, factor)| factor * self.history[current - delay])
+            .sum::<f32>();
+        let result = head + self.tail[self.position];
+
+        self.position += 1;
+        if self.position == block_size {
+            self.next_block();
+        }
+        result
+    }
+
+    /// Convolve the samples in place.
+    pub fn process_block(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            *sample = self.process(*sample);
+        }
+    }
+
+    /// Transform the completed block and calculate the tail of the next block.
+    fn next_block(&mut self) {
+        let impulse_response = &self.impulse_response;
+        let block_size = impulse_response.block_size;
+        let num_partitions = impulse_response.partitions.len();
+        if num_partitions > 0 {
+            self.newest = (self.newest + num_partitions - 1) % num_partitions;
+            impulse_response.fft.forward_into(
+                &self.history,
+                &mut self.delay_line[self.newest],
+                &mut self.scratch,
+            );
+
+            self.spectrum.fill((0.0, 0.0));
+            for (delay, partition) in impulse_response.partitions.iter().enumerate() {
+                let input = &self.delay_line[(self.newest + delay) % num_partitions];
+                for ((sum, input), factor) in self.spectrum.iter_mut().zip(input).zip(partition) {
+                    *sum = sum.plus(input.multiply(*factor));
+                }
+            }
+            // Overlap-save: the first half is corrupted by the circular convolution.
+            impulse_response
+                .fft
+                .inverse_into(&self.spectrum, &mut self.output, &mut self.scratch);
+            self.tail.copy_from_slice(&self.output[block_size..]);
+        }
+        self.history.copy_within(block_size.., 0);
+        self.position = 0;
+    }
+}
+
+/// Full convolution of the signal with the impulse response, the result has
+/// `signal.len() + impulse_response.len() - 1` samples.
+pub fn convolve(signal: &[f32], impulse_response: &[f32], block_size: usize) -> Vec<f32> {
+    if signal.is_empty() || impulse_response.is_empty() {
+        return Vec::new();
+    }
+    let mut convolution = PartitionedConvolution::new(Arc::new(PartitionedImpulseResponse::new(
+        impulse_response,
+        block_size,
+    )));
+    let len = signal.len() + impulse_response.len() - 1;
+    (0..len)
+        .map(|index| convolution.process(signal.get(index).copied().unwrap_or_default()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{convolve, PartitionedConvolution, PartitionedImpulseResponse};
+
+    fn signal(len: usize, seed: f32) -> Vec<f32> {
+        (0..len)
+            .map(|index| ((index as f32 + seed) * 0.37).sin() * (-(index as f32) * 0.001).exp())
+            .collect()
+    }
+
+    /// Full convolution in the time domain.
+    fn direct(signal: &[f32], impulse_response: &[f32]) -> Vec<f32> {
+        let mut result = vec![0.0; signal.len() + impulse_response.len() - 1];
+        for (index, sample) in signal.iter().enumerate() {
+            for (delay, factor) in impulse_response.iter().enumerate() {
+                result[index + delay] += sample * factor;
+            }
+        }
+        result
+    }
+
+    fn assert_close(expected: &[f32], actual: &[f32]) {
+        assert_eq!(expected.len(), actual.len());
+        for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+            assert!(
+                (expected - actual).abs() < 1e-4 * expected.abs().max(1.0),
+                "{index}: {expected} != {actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_direct_convolution() {
+        let input = signal(1000, 0.0);
+        for (len, block_size) in [(1, 4), (4, 4), (5, 4), (64, 16), (257, 64), (1500, 32)] {
+            let impulse_response = signal(len, 3.0);
+            let expected = direct(&input, &impulse_response);
+            assert_close(&expected, &convolve(&input, &impulse_response, block_size));
+        }
+    }
+
+    #[test]
+    fn impulse_gives_impulse_response() {
+        let impulse_response = signal(300, 1.0);
+        let result = convolve(&[1.0], &impulse_response, 32);
+        assert_close(&impulse_response, &result);
+    }
+
+    #[test]
+    fn no_latency() {
+        let result = convolve(&[0.0, 0.0, 1.0], &[0.5, 0.25], 64);
+        assert_eq!(vec![0.0, 0.0, 0.5, 0.25], result);
+    }
+
+    #[test]
+    fn reset_restarts_convolution() {
+        let input = signal(500, 0.0);
+        let mut convolution = PartitionedConvolution::new(Arc::new(
+            PartitionedImpulseResponse::new(&signal(300, 2.0), 32),
+        ));
+        let mut first = input.clone();
+        convolution.process_block(&mut first);
+        convolution.reset();
+        let mut second = input.clone();
+        convolution.process_block(&mut second);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(convolve(&[], &[1.0], 64).is_empty());
+        assert!(convolve(&[1.0], &[], 64).is_empty());
+    }
+}
@@ -1,4 +1,11 @@
-use audio_engine_common::{buffer::ring_buffer::PushOperation, duration::Duration, level::Level};
+use audio_engine_common::{
+    buffer::ring_buffer::PushOperation,
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    duration::Duration,
+    level::Level,
+    lfo::{target::LfoTarget, Lfo},
+    modulation::destination::ModulationDestination,
+};
 use audio_engine_effect::effect::Effect;
 
 use crate::delay_state::DelayState;
@@ -10,6 +17,8 @@ pub struct Delay {
     pub is_enabled: bool,
     pub delay_time: Duration,
     pub level: Level,
+    /// Optional lfo modulating the feedback level. Depth is relative to the level.
+    pub lfo: Option<Lfo>,
 }
 
 impl Default for Delay {
@@ -18,6 +27,7 @@ impl Default for Delay {
             is_enabled: false,
             delay_time: 1.0,
             level: 0.2,
+            lfo: None,
         }
     }
 }
@@ -26,7 +36,13 @@ impl Effect for Delay {
     type EffectState = DelayState;
 
     fn effect_create_state(&self) -> Self::EffectState {
-        DelayState::default()
+        DelayState {
+            lfo: self
+                .lfo
+                .map(|lfo| lfo.init_sound_state())
+                .unwrap_or_default(),
+            ..DelayState::default()
+        }
     }
 
     fn effect_apply(
@@ -43,8 +59,25 @@ impl Effect for Delay {
         let max_offset = offset + audio_buffer.len() + 4;
         effect_state.buffer.ensure_size(max_offset);
 
+        let base_level = LfoTarget::Amplitude.apply(self.level, effect_state.level_modulation);
         audio_buffer.iter_mut().for_each(|out_sample| {
-            let new_sample = *out_sample + effect_state.buffer.pop_or_default() * self.level;
+            let level = if let Some(lfo) = &self.lfo {
+                let modulation = lfo.sample(
+                    &NoteParameters {
+                        note_time: effect_state.lfo_time,
+                        note_off: None,
+                        note_pitch: 0.0,
+                        gain: 1.0,
+                        sample_rate,
+                    },
+                    &mut effect_state.lfo,
+                );
+                effect_state.lfo_time += 1.0 / sample_rate;
+                LfoTarget::Amplitude.apply(base_level, modulation)
+            } else {
+                base_level
+            };
+            let new_sample = *out_sample + effect_state.buffer.pop_or_default() * level;
             effect_state
                 .buffer
                 .push(offset, new_sample, PushOperation::Add);
@@ -54,6 +87,28 @@ impl Effect for Delay {
     }
 }
 
+impl ModulationDestination for Delay {
+    type State = DelayState;
+
+    /// The level can be modulated relative to the level of the effect.
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("level")]
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        state.level_modulation = 0.0;
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        if parameter == "level" {
+            state.level_modulation += modulation;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use audio_engine_effect::effect::Effect;
@@ -66,6 +121,7 @@ mod test {
             is_enabled: true,
             delay_time: 1.0,
             level: 0.5,
+            lfo: None,
         };
 
         let in_samples = [1.0];
@@ -88,6 +144,7 @@ mod test {
             is_enabled: true,
             delay_time: 1.0,
             level: 0.5,
+            lfo: None,
         };
 
         let in_samples = [1.0; 16];
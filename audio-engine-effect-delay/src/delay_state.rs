@@ -1,8 +1,13 @@
-use audio_engine_common::buffer::ring_buffer::RingBuffer;
+use audio_engine_common::{buffer::ring_buffer::RingBuffer, lfo::LfoState, note_time::NoteTime};
 use audio_engine_effect::effect_state::EffectState;
 
 #[derive(Default, Debug, Clone)]
 pub struct DelayState {
     pub buffer: RingBuffer<f32>,
+    pub lfo: LfoState,
+    /// Time since the state was created, used to sample the lfo.
+    pub lfo_time: NoteTime,
+    /// Level modulation of a modulation matrix, relative to the level.
+    pub level_modulation: f32,
 }
 impl EffectState for DelayState {}
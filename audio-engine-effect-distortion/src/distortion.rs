@@ -1,4 +1,9 @@
-use audio_engine_common::level::Level;
+use audio_engine_common::{
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    level::Level,
+    lfo::{target::LfoTarget, Lfo},
+    modulation::destination::ModulationDestination,
+};
 use audio_engine_effect::effect::Effect;
 
 use crate::distortion_state::DistortionState;
@@ -9,6 +14,8 @@ use crate::distortion_state::DistortionState;
 pub struct Distortion {
     pub is_enabled: bool,
     pub level: Level,
+    /// Optional lfo modulating the level. Depth is relative to the level.
+    pub lfo: Option<Lfo>,
 }
 
 impl Default for Distortion {
@@ -16,6 +23,7 @@ impl Default for Distortion {
         Distortion {
             is_enabled: false,
             level: 0.2,
+            lfo: None,
         }
     }
 }
@@ -24,25 +32,196 @@ impl Effect for Distortion {
     type EffectState = DistortionState;
 
     fn effect_create_state(&self) -> Self::EffectState {
-        DistortionState::default()
+        DistortionState {
+            lfo: self
+                .lfo
+                .map(|lfo| lfo.init_sound_state())
+                .unwrap_or_default(),
+            ..DistortionState::default()
+        }
     }
 
     fn effect_apply(
         &self,
         audio_buffer: &mut [f32],
-        _sample_rate: f32,
-        _effect_state: &mut Self::EffectState,
+        sample_rate: f32,
+        effect_state: &mut Self::EffectState,
     ) {
         if !self.is_enabled || self.level == 0.0 {
             return;
         }
 
-        let level_inv = 1.0 - self.level;
-        let level_mult = 1.0 / level_inv;
+        let base_level = if effect_state.level_modulation == 0.0 {
+            self.level
+        } else {
+            LfoTarget::Amplitude
+                .apply(self.level, effect_state.level_modulation)
+                .min(MAX_LEVEL)
+        };
+        if let Some(lfo) = &self.lfo {
+            audio_buffer.iter_mut().for_each(|out_sample| {
+                let modulation = lfo.sample(
+                    &NoteParameters {
+                        note_time: effect_state.lfo_time,
+                        note_off: None,
+                        note_pitch: 0.0,
+                        gain: 1.0,
+                        sample_rate,
+                    },
+                    &mut effect_state.lfo,
+                );
+                effect_state.lfo_time += 1.0 / sample_rate;
+                let level = LfoTarget::Amplitude
+                    .apply(base_level, modulation)
+                    .min(MAX_LEVEL);
+                *out_sample = distort(*out_sample, level);
+            });
+        } else {
+            audio_buffer.iter_mut().for_each(|out_sample| {
+                *out_sample = distort(*out_sample, base_level);
+            })
+        }
+    }
+}
+
+/// Highest level modulation can reach, a level of 1.0 would divide by zero.
+const MAX_LEVEL: Level = 0.99;
+
+fn distort(sample: f32, level: Level) -> f32 {
+    let level_inv = 1.0 - level;
+    let level_mult = 1.0 / level_inv;
+    sample.clamp(-level_inv, level_inv) * level_mult
+}
+
+impl ModulationDestination for Distortion {
+    type State = DistortionState;
+
+    /// The level can be modulated relative to the level of the effect.
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("level")]
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        state.level_modulation = 0.0;
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        if parameter == "level" {
+            state.level_modulation += modulation;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::{
+        lfo::{rate::LfoRate, Lfo},
+        modulation::destination::ModulationDestination,
+    };
+    use audio_engine_effect::effect::Effect;
+
+    use super::Distortion;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn enabled() -> Distortion {
+        Distortion {
+            is_enabled: true,
+            ..Distortion::default()
+        }
+    }
+
+    /// Distort a constant signal of 0.5 for a second.
+    fn apply(distortion: &Distortion, state: &mut <Distortion as Effect>::EffectState) -> Vec<f32> {
+        let mut samples = vec![0.5; SAMPLE_RATE as usize];
+        distortion.effect_apply(&mut samples, SAMPLE_RATE, state);
+        samples
+    }
+
+    #[test]
+    fn disabled_distortion_passes_signal() {
+        let distortion = Distortion::default();
+        let mut state = distortion.effect_create_state();
+        assert!(apply(&distortion, &mut state)
+            .iter()
+            .all(|sample| *sample == 0.5));
+    }
+
+    #[test]
+    fn level() {
+        let distortion = enabled();
+        let mut state = distortion.effect_create_state();
+        // Clipped at 0.8 and scaled up to 1.0.
+        assert!(apply(&distortion, &mut state)
+            .iter()
+            .all(|sample| (sample - 0.625).abs() < 1e-6));
+    }
+
+    #[test]
+    fn modulated_level() {
+        let distortion = enabled();
+        let mut state = distortion.effect_create_state();
+        assert!(distortion.modulate("level", 1.0, &mut state));
+        assert!(!distortion.modulate("cutoff", 1.0, &mut state));
+
+        // Level is now 0.4, clipped at 0.6.
+        let samples = apply(&distortion, &mut state);
+        assert!(samples
+            .iter()
+            .all(|sample| (sample - 0.5 / 0.6).abs() < 1e-6));
+
+        // Modulation can't push the level to 1.0, which would divide by zero.
+        assert!(distortion.modulate("level", 10.0, &mut state));
+        let samples = apply(&distortion, &mut state);
+        assert!(samples
+            .iter()
+            .all(|sample| sample.is_finite() && *sample <= 1.0 + 1e-6));
+
+        distortion.clear_modulation(&mut state);
+        let samples = apply(&distortion, &mut state);
+        assert!(samples.iter().all(|sample| (sample - 0.625).abs() < 1e-6));
+    }
+
+    #[test]
+    fn lfo_modulates_level() {
+        let distortion = Distortion {
+            lfo: Some(Lfo {
+                rate: LfoRate::Hertz(2.0),
+                depth: 1.0,
+                ..Lfo::default()
+            }),
+            ..enabled()
+        };
+        let mut state = distortion.effect_create_state();
+        let samples = apply(&distortion, &mut state);
+        // Level swings between 0.0 and 0.4.
+        let min = samples.iter().copied().fold(f32::MAX, f32::min);
+        let max = samples.iter().copied().fold(f32::MIN, f32::max);
+        assert!((min - 0.5).abs() < 1e-3, "{min}");
+        assert!((max - 0.5 / 0.6).abs() < 1e-3, "{max}");
+    }
+
+    #[test]
+    fn lfo_continues_between_buffers() {
+        let distortion = Distortion {
+            lfo: Some(Lfo {
+                rate: LfoRate::Hertz(3.0),
+                depth: 0.5,
+                ..Lfo::default()
+            }),
+            ..enabled()
+        };
+        let mut state = distortion.effect_create_state();
+        let whole = apply(&distortion, &mut state);
 
-        audio_buffer.iter_mut().for_each(|out_sample| {
-            let new_sample = out_sample.clamp(-level_inv, level_inv) * level_mult;
-            *out_sample = new_sample;
-        })
+        let mut state = distortion.effect_create_state();
+        let mut parts = vec![0.5; SAMPLE_RATE as usize];
+        let (first, second) = parts.split_at_mut(1000);
+        distortion.effect_apply(first, SAMPLE_RATE, &mut state);
+        distortion.effect_apply(second, SAMPLE_RATE, &mut state);
+        assert_eq!(whole, parts);
     }
 }
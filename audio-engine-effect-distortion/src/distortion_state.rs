@@ -1,5 +1,12 @@
+use audio_engine_common::{lfo::LfoState, note_time::NoteTime};
 use audio_engine_effect::effect_state::EffectState;
 
 #[derive(Default, Debug, Clone)]
-pub struct DistortionState {}
+pub struct DistortionState {
+    pub lfo: LfoState,
+    /// Time since the state was created, used to sample the lfo.
+    pub lfo_time: NoteTime,
+    /// Level modulation of a modulation matrix, relative to the level.
+    pub level_modulation: f32,
+}
 impl EffectState for DistortionState {}
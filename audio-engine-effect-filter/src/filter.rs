@@ -0,0 +1,212 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use audio_engine_common::{
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    lfo::{target::LfoTarget, Lfo},
+    modulation::destination::ModulationDestination,
+};
+use audio_engine_discrete_time::biquad::{Biquad, BiquadType};
+use audio_engine_effect::effect::Effect;
+
+use crate::filter_state::FilterState;
+
+/// Filter effect is a second order filter designed with the formulas of the Audio EQ
+/// Cookbook.
+#[derive(Debug, Copy, Clone)]
+pub struct Filter {
+    pub is_enabled: bool,
+    pub filter_type: BiquadType,
+    /// Cutoff or center frequency in Hz.
+    pub cutoff: f32,
+    /// Quality factor, higher values give a narrower band or a resonance at the cutoff.
+    pub q: f32,
+    /// Gain in dB, only used by the peak and shelf filters.
+    pub gain: f32,
+    /// Optional lfo modulating the cutoff. Depth is in octaves.
+    pub lfo: Option<Lfo>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            is_enabled: false,
+            filter_type: BiquadType::LowPass,
+            cutoff: 1000.0,
+            q: FRAC_1_SQRT_2,
+            gain: 0.0,
+            lfo: None,
+        }
+    }
+}
+
+impl Filter {
+    fn biquad(&self, cutoff: f32) -> Biquad {
+        Biquad::with_gain(
+            self.filter_type,
+            cutoff as f64,
+            self.q as f64,
+            self.gain as f64,
+        )
+    }
+}
+
+impl Effect for Filter {
+    type EffectState = FilterState;
+
+    fn effect_create_state(&self) -> Self::EffectState {
+        FilterState {
+            lfo: self
+                .lfo
+                .map(|lfo| lfo.init_sound_state())
+                .unwrap_or_default(),
+            ..FilterState::default()
+        }
+    }
+
+    fn effect_apply(
+        &self,
+        audio_buffer: &mut [f32],
+        sample_rate: f32,
+        effect_state: &mut Self::EffectState,
+    ) {
+        if !self.is_enabled {
+            return;
+        }
+
+        let base_cutoff = LfoTarget::Cutoff.apply(self.cutoff, effect_state.cutoff_modulation);
+        audio_buffer.iter_mut().for_each(|out_sample| {
+            let cutoff = if let Some(lfo) = &self.lfo {
+                let modulation = lfo.sample(
+                    &NoteParameters {
+                        note_time: effect_state.lfo_time,
+                        note_off: None,
+                        note_pitch: 0.0,
+                        gain: 1.0,
+                        sample_rate,
+                    },
+                    &mut effect_state.lfo,
+                );
+                effect_state.lfo_time += 1.0 / sample_rate;
+                LfoTarget::Cutoff.apply(base_cutoff, modulation)
+            } else {
+                base_cutoff
+            };
+            update_filter(effect_state, self.biquad(cutoff), sample_rate);
+            if let Some(filter) = &mut effect_state.filter {
+                *out_sample = filter.filter(*out_sample);
+            }
+        });
+    }
+}
+
+/// Number of samples to move to new coefficients, prevents clicks when the cutoff jumps.
+const RAMP_LEN: usize = 64;
+
+/// Recalculate the coefficients when the design changed. The history of the filter is kept
+/// so a changing cutoff doesn't reset the filter.
+fn update_filter(effect_state: &mut FilterState, biquad: Biquad, sample_rate: f32) {
+    if effect_state.design == Some((biquad, sample_rate)) {
+        return;
+    }
+    effect_state.design = Some((biquad, sample_rate));
+    let coefficients = biquad.coefficients(sample_rate as f64);
+    match &mut effect_state.filter {
+        Some(filter) => filter.set_coefficients(&coefficients, RAMP_LEN),
+        None => effect_state.filter = Some(coefficients.into()),
+    }
+}
+
+impl ModulationDestination for Filter {
+    type State = FilterState;
+
+    /// The cutoff can be modulated in octaves.
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("cutoff")]
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        state.cutoff_modulation = 0.0;
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        if parameter == "cutoff" {
+            state.cutoff_modulation += modulation;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::TAU;
+
+    use audio_engine_common::modulation::destination::ModulationDestination;
+    use audio_engine_discrete_time::biquad::BiquadType;
+    use audio_engine_effect::effect::Effect;
+
+    use super::Filter;
+
+    fn sine(frequency: f32, sample_rate: f32) -> Vec<f32> {
+        (0..sample_rate as usize)
+            .map(|index| (TAU * frequency * index as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    /// Peak level of the second half of the buffer, after the filter settled.
+    fn peak(samples: &[f32]) -> f32 {
+        samples[samples.len() / 2..]
+            .iter()
+            .fold(0.0, |peak, sample| sample.abs().max(peak))
+    }
+
+    #[test]
+    fn disabled_filter_passes_signal() {
+        let filter = Filter::default();
+        let mut state = filter.effect_create_state();
+        let mut samples = sine(5000.0, 44100.0);
+        filter.effect_apply(&mut samples, 44100.0, &mut state);
+        assert_eq!(samples, sine(5000.0, 44100.0));
+    }
+
+    #[test]
+    fn low_pass() {
+        let sample_rate = 44100.0;
+        let filter = Filter {
+            is_enabled: true,
+            filter_type: BiquadType::LowPass,
+            cutoff: 500.0,
+            ..Filter::default()
+        };
+
+        let mut state = filter.effect_create_state();
+        let mut low = sine(50.0, sample_rate);
+        filter.effect_apply(&mut low, sample_rate, &mut state);
+        assert!((peak(&low) - 1.0).abs() < 0.01);
+
+        let mut state = filter.effect_create_state();
+        let mut high = sine(5000.0, sample_rate);
+        filter.effect_apply(&mut high, sample_rate, &mut state);
+        assert!(peak(&high) < 0.02);
+    }
+
+    #[test]
+    fn modulated_cutoff() {
+        let sample_rate = 44100.0;
+        let filter = Filter {
+            is_enabled: true,
+            filter_type: BiquadType::LowPass,
+            cutoff: 500.0,
+            ..Filter::default()
+        };
+        let mut state = filter.effect_create_state();
+        assert!(filter.modulate("cutoff", 4.0, &mut state));
+        assert!(!filter.modulate("level", 1.0, &mut state));
+
+        // Cutoff is now 8kHz, a 1kHz tone passes.
+        let mut samples = sine(1000.0, sample_rate);
+        filter.effect_apply(&mut samples, sample_rate, &mut state);
+        assert!((peak(&samples) - 1.0).abs() < 0.01);
+    }
+}
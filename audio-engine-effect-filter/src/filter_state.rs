@@ -0,0 +1,17 @@
+use audio_engine_common::{lfo::LfoState, note_time::NoteTime};
+use audio_engine_discrete_time::{biquad::Biquad, second_order_sections::SecondOrderSection};
+use audio_engine_effect::effect_state::EffectState;
+
+#[derive(Default, Debug, Clone)]
+pub struct FilterState {
+    /// Filter with the coefficients of `design`, created when the effect is first applied.
+    pub filter: Option<SecondOrderSection<f32>>,
+    /// Design and sample rate the coefficients of the filter are calculated for.
+    pub design: Option<(Biquad, f32)>,
+    pub lfo: LfoState,
+    /// Time since the state was created, used to sample the lfo.
+    pub lfo_time: NoteTime,
+    /// Cutoff modulation of a modulation matrix, in octaves.
+    pub cutoff_modulation: f32,
+}
+impl EffectState for FilterState {}
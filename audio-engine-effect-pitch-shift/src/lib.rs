@@ -0,0 +1,2 @@
+pub mod pitch_shift;
+pub mod pitch_shift_state;
@@ -0,0 +1,126 @@
+use audio_engine_effect::effect::Effect;
+use audio_engine_fourier::phase_vocoder::{PhaseVocoder, PhaseVocoderParameters};
+
+use crate::pitch_shift_state::PitchShiftState;
+
+/// Pitch shift effect changes the pitch of the signal without changing its duration.
+///
+/// Uses a phase vocoder that processes a frame each `hop_size` samples. The output is
+/// delayed by the window length of the vocoder.
+#[derive(Debug, Copy, Clone)]
+pub struct PitchShift {
+    pub is_enabled: bool,
+    /// Number of semitones to shift, negative values lower the pitch.
+    pub semitones: f32,
+    pub vocoder: PhaseVocoderParameters,
+}
+
+impl Default for PitchShift {
+    fn default() -> Self {
+        PitchShift {
+            is_enabled: false,
+            semitones: 0.0,
+            vocoder: PhaseVocoderParameters {
+                window_len: 1024,
+                hop_size: 256,
+                ..PhaseVocoderParameters::default()
+            },
+        }
+    }
+}
+
+impl PitchShift {
+    pub fn pitch_ratio(&self) -> f32 {
+        (self.semitones / 12.0).exp2()
+    }
+}
+
+impl Effect for PitchShift {
+    type EffectState = PitchShiftState;
+
+    fn effect_create_state(&self) -> Self::EffectState {
+        PitchShiftState {
+            vocoder: PhaseVocoder::new(self.vocoder),
+            input: vec![0.0; self.vocoder.window_len],
+            output: vec![0.0; self.vocoder.window_len],
+            position: 0,
+        }
+    }
+
+    fn effect_apply(
+        &self,
+        audio_buffer: &mut [f32],
+        _sample_rate: f32,
+        effect_state: &mut Self::EffectState,
+    ) {
+        if !self.is_enabled {
+            return;
+        }
+        let window_len = self.vocoder.window_len;
+        let hop_size = self.vocoder.hop_size;
+        let normalization = self.vocoder.normalization();
+        let pitch_ratio = self.pitch_ratio();
+
+        audio_buffer.iter_mut().for_each(|sample| {
+            let position = effect_state.position;
+            effect_state.input[window_len - hop_size + position] = *sample;
+            *sample = effect_state.output[position];
+            effect_state.position += 1;
+
+            if effect_state.position == hop_size {
+                let frame = effect_state.vocoder.process_frame(
+                    &effect_state.input,
+                    hop_size as f32,
+                    pitch_ratio,
+                );
+                effect_state.output.rotate_left(hop_size);
+                effect_state.output[window_len - hop_size..].fill(0.0);
+                for (output, sample) in effect_state.output.iter_mut().zip(frame) {
+                    *output += sample / normalization;
+                }
+                effect_state.input.rotate_left(hop_size);
+                effect_state.position = 0;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::TAU;
+
+    use audio_engine_effect::effect::Effect;
+    use audio_engine_fourier::to_spectrum::ToSpectrum;
+
+    use super::PitchShift;
+
+    #[test]
+    fn disabled_keeps_signal() {
+        let pitch_shift = PitchShift::default();
+        let mut state = pitch_shift.effect_create_state();
+        let mut samples = [0.5; 16];
+        pitch_shift.effect_apply(&mut samples, 44100.0, &mut state);
+        assert_eq!(samples, [0.5; 16]);
+    }
+
+    #[test]
+    fn shift_octave_up_in_blocks() {
+        let sample_rate = 44100.0;
+        let pitch_shift = PitchShift {
+            is_enabled: true,
+            semitones: 12.0,
+            ..PitchShift::default()
+        };
+        let mut state = pitch_shift.effect_create_state();
+        let mut samples = (0..16384)
+            .map(|index| (TAU * 441.0 * index as f32 / sample_rate).sin())
+            .collect::<Vec<f32>>();
+        for block in samples.chunks_mut(100) {
+            pitch_shift.effect_apply(block, sample_rate, &mut state);
+        }
+
+        let spectrum = (&samples[8192..]).to_spectrum();
+        let frequency = spectrum.frequency(spectrum.find_largest_amplitude(), sample_rate);
+        assert!((frequency - 882.0).abs() < 6.0, "{frequency}");
+    }
+}
@@ -0,0 +1,14 @@
+use audio_engine_effect::effect_state::EffectState;
+use audio_engine_fourier::phase_vocoder::PhaseVocoder;
+
+#[derive(Debug, Clone)]
+pub struct PitchShiftState {
+    pub vocoder: PhaseVocoder,
+    /// Last `window_len` input samples.
+    pub input: Vec<f32>,
+    /// Overlap-add buffer, the first `hop_size` samples are complete.
+    pub output: Vec<f32>,
+    /// Position inside the current hop.
+    pub position: usize,
+}
+impl EffectState for PitchShiftState {}
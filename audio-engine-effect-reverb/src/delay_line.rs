@@ -0,0 +1,48 @@
+/// Fixed length delay line; each processed sample comes out `len` samples later.
+#[derive(Default, Debug, Clone)]
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl DelayLine {
+    pub fn new(len: usize) -> DelayLine {
+        DelayLine {
+            buffer: vec![0.0; len],
+            index: 0,
+        }
+    }
+
+    /// Delay in samples.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Sample that leaves the delay line with the next call to [DelayLine::write].
+    pub fn read(&self) -> f32 {
+        self.buffer.get(self.index).copied().unwrap_or_default()
+    }
+
+    pub fn write(&mut self, value: f32) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.buffer[self.index] = value;
+        self.index = (self.index + 1) % self.buffer.len();
+    }
+
+    /// Write the value and return the value written `len` samples ago. An empty delay line
+    /// returns the value itself.
+    pub fn process(&mut self, value: f32) -> f32 {
+        if self.buffer.is_empty() {
+            return value;
+        }
+        let result = self.read();
+        self.write(value);
+        result
+    }
+}
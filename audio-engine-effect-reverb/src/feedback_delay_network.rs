@@ -0,0 +1,109 @@
+//! Feedback delay network reverb.
+//!
+//! Eight delay lines feed back into each other through a Hadamard matrix. The matrix is
+//! orthogonal, so the network itself doesn't lose energy; the decay is set by a gain per
+//! delay line that is proportional to its length, so all lines decay at the same rate.
+//! A one pole low pass per line makes high frequencies decay faster.
+use crate::delay_line::DelayLine;
+
+const LINES: usize = 8;
+/// Delays in samples at 44.1kHz, mutually prime to prevent echoes that line up.
+const LENGTHS: [usize; LINES] = [1433, 1601, 1867, 2053, 2251, 2399, 2617, 2797];
+const TUNING_SAMPLE_RATE: f32 = 44100.0;
+/// Output signs of the left and right channel. The patterns are orthogonal so the channels
+/// are decorrelated.
+const LEFT: [f32; LINES] = [1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0];
+const RIGHT: [f32; LINES] = [1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0];
+
+/// Input and output gain, chosen so the tail is about as loud as the Freeverb tail.
+const INPUT_GAIN: f32 = 0.5;
+const OUTPUT_GAIN: f32 = 0.6;
+/// Decay time (-60dB) in seconds for the smallest and largest room.
+const MIN_DECAY_TIME: f32 = 0.2;
+const MAX_DECAY_TIME: f32 = 8.0;
+const SCALE_DAMPING: f32 = 0.6;
+
+#[derive(Default, Debug, Clone)]
+pub struct FeedbackDelayNetwork {
+    sample_rate: f32,
+    delay_lines: Vec<DelayLine>,
+    filter_stores: [f32; LINES],
+    /// Room size the gains are calculated for.
+    room_size: Option<f32>,
+    gains: [f32; LINES],
+}
+
+impl FeedbackDelayNetwork {
+    pub fn new(sample_rate: f32) -> FeedbackDelayNetwork {
+        FeedbackDelayNetwork {
+            sample_rate,
+            delay_lines: LENGTHS
+                .iter()
+                .map(|len| {
+                    DelayLine::new((*len as f32 * sample_rate / TUNING_SAMPLE_RATE) as usize)
+                })
+                .collect(),
+            ..FeedbackDelayNetwork::default()
+        }
+    }
+
+    /// Decay time (-60dB) in seconds for the room size.
+    pub fn decay_time(room_size: f32) -> f32 {
+        MIN_DECAY_TIME * (MAX_DECAY_TIME / MIN_DECAY_TIME).powf(room_size)
+    }
+
+    fn update_gains(&mut self, room_size: f32) {
+        if self.room_size == Some(room_size) {
+            return;
+        }
+        self.room_size = Some(room_size);
+        let decay_samples = FeedbackDelayNetwork::decay_time(room_size) * self.sample_rate;
+        for (gain, delay_line) in self.gains.iter_mut().zip(&self.delay_lines) {
+            *gain = 10.0_f32.powf(-3.0 * delay_line.len() as f32 / decay_samples);
+        }
+    }
+
+    /// Wet left and right output for the next input sample. Room size and damping are in
+    /// the range 0..=1.
+    pub fn process(&mut self, input: f32, room_size: f32, damping: f32) -> (f32, f32) {
+        self.update_gains(room_size);
+        let damping = damping * SCALE_DAMPING;
+
+        let mut left = 0.0;
+        let mut right = 0.0;
+        let mut feedback = [0.0; LINES];
+        for index in 0..LINES {
+            let output = self.delay_lines[index].read();
+            left += output * LEFT[index];
+            right += output * RIGHT[index];
+            let store = &mut self.filter_stores[index];
+            *store = output * (1.0 - damping) + *store * damping;
+            feedback[index] = *store * self.gains[index];
+        }
+
+        hadamard(&mut feedback);
+        let input = input * INPUT_GAIN;
+        for (delay_line, feedback) in self.delay_lines.iter_mut().zip(feedback) {
+            delay_line.write(input + feedback);
+        }
+        (left * OUTPUT_GAIN, right * OUTPUT_GAIN)
+    }
+}
+
+/// Multiply with the normalized Hadamard matrix, using the fast Walsh-Hadamard transform.
+fn hadamard(values: &mut [f32; LINES]) {
+    let mut half = 1;
+    while half < LINES {
+        for start in (0..LINES).step_by(half * 2) {
+            for index in start..start + half {
+                let a = values[index];
+                let b = values[index + half];
+                values[index] = a + b;
+                values[index + half] = a - b;
+            }
+        }
+        half *= 2;
+    }
+    let scale = 1.0 / (LINES as f32).sqrt();
+    values.iter_mut().for_each(|value| *value *= scale);
+}
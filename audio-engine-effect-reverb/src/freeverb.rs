@@ -0,0 +1,116 @@
+//! Schroeder-Moorer reverb with the tuning of Freeverb.
+//!
+//! Eight parallel comb filters with a low pass in their feedback path build up the dense
+//! tail, four allpass filters in series diffuse it. The right channel uses slightly longer
+//! delays than the left channel, which decorrelates the two outputs.
+use crate::delay_line::DelayLine;
+
+/// Delays of the comb filters in samples at 44.1kHz.
+const COMB_LENGTHS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+/// Delays of the allpass filters in samples at 44.1kHz.
+const ALLPASS_LENGTHS: [usize; 4] = [556, 441, 341, 225];
+/// Extra delay of the right channel in samples at 44.1kHz.
+const STEREO_SPREAD: usize = 23;
+const TUNING_SAMPLE_RATE: f32 = 44100.0;
+
+const INPUT_GAIN: f32 = 0.015;
+const OUTPUT_GAIN: f32 = 3.0;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+const SCALE_ROOM: f32 = 0.28;
+const OFFSET_ROOM: f32 = 0.7;
+const SCALE_DAMPING: f32 = 0.4;
+
+/// Comb filter with a one pole low pass in the feedback path.
+#[derive(Default, Debug, Clone)]
+struct Comb {
+    delay_line: DelayLine,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.delay_line.read();
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.delay_line.write(input + self.filter_store * feedback);
+        output
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+struct Allpass {
+    delay_line: DelayLine,
+}
+
+impl Allpass {
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.delay_line.read();
+        self.delay_line.write(input + delayed * ALLPASS_FEEDBACK);
+        delayed - input
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+struct Channel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Channel {
+    fn new(sample_rate: f32, spread: usize) -> Channel {
+        let scale =
+            |len: usize| ((len + spread) as f32 * sample_rate / TUNING_SAMPLE_RATE) as usize;
+        Channel {
+            combs: COMB_LENGTHS
+                .iter()
+                .map(|len| Comb {
+                    delay_line: DelayLine::new(scale(*len)),
+                    filter_store: 0.0,
+                })
+                .collect(),
+            allpasses: ALLPASS_LENGTHS
+                .iter()
+                .map(|len| Allpass {
+                    delay_line: DelayLine::new(scale(*len)),
+                })
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let combs = self
+            .combs
+            .iter_mut()
+            .map(|comb| comb.process(input, feedback, damping))
+            .sum::<f32>();
+        self.allpasses
+            .iter_mut()
+            .fold(combs, |value, allpass| allpass.process(value))
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct Freeverb {
+    left: Channel,
+    right: Channel,
+}
+
+impl Freeverb {
+    pub fn new(sample_rate: f32) -> Freeverb {
+        Freeverb {
+            left: Channel::new(sample_rate, 0),
+            right: Channel::new(sample_rate, STEREO_SPREAD),
+        }
+    }
+
+    /// Wet left and right output for the next input sample. Room size and damping are in
+    /// the range 0..=1.
+    pub fn process(&mut self, input: f32, room_size: f32, damping: f32) -> (f32, f32) {
+        let input = input * INPUT_GAIN;
+        let feedback = room_size * SCALE_ROOM + OFFSET_ROOM;
+        let damping = damping * SCALE_DAMPING;
+        (
+            self.left.process(input, feedback, damping) * OUTPUT_GAIN,
+            self.right.process(input, feedback, damping) * OUTPUT_GAIN,
+        )
+    }
+}
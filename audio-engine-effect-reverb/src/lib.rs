@@ -0,0 +1,5 @@
+pub mod delay_line;
+pub mod feedback_delay_network;
+pub mod freeverb;
+pub mod reverb;
+pub mod reverb_state;
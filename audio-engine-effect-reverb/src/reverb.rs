@@ -0,0 +1,348 @@
+use audio_engine_common::{
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    duration::Duration,
+    lfo::{target::LfoTarget, Lfo},
+    modulation::destination::ModulationDestination,
+};
+use audio_engine_effect::effect::Effect;
+
+use crate::{
+    delay_line::DelayLine,
+    reverb_state::{ReverbState, Tank},
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum ReverbAlgorithm {
+    /// Schroeder-Moorer reverb with parallel comb filters and series allpass filters.
+    #[default]
+    Freeverb,
+    /// Delay lines feeding back into each other, a smoother and denser tail.
+    FeedbackDelayNetwork,
+}
+
+/// Reverb effect simulates the reflections of a room. The reverb is mixed with the dry
+/// signal after a pre-delay.
+#[derive(Debug, Copy, Clone)]
+pub struct Reverb {
+    pub is_enabled: bool,
+    pub algorithm: ReverbAlgorithm,
+    /// Size of the room (0..=1), a larger room has a longer tail.
+    pub room_size: f32,
+    /// Damping of high frequencies in the tail (0..=1).
+    pub damping: f32,
+    /// Time between the dry signal and the start of the reverb.
+    pub pre_delay: Duration,
+    /// Stereo width of the reverb (0..=1). Only audible in [Reverb::effect_apply_stereo],
+    /// the mono output is the sum of both channels.
+    pub width: f32,
+    /// Dry/wet mix (0..=1), 0 is only the dry signal and 1 only the reverb.
+    pub mix: f32,
+    /// Optional lfo modulating the mix. Depth is relative to the mix.
+    pub lfo: Option<Lfo>,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Reverb {
+            is_enabled: false,
+            algorithm: ReverbAlgorithm::Freeverb,
+            room_size: 0.5,
+            damping: 0.5,
+            pre_delay: 0.02,
+            width: 1.0,
+            mix: 0.3,
+            lfo: None,
+        }
+    }
+}
+
+impl Reverb {
+    /// Apply the reverb to a mono input and write a stereo output.
+    pub fn effect_apply_stereo(
+        &self,
+        input: &[f32],
+        left: &mut [f32],
+        right: &mut [f32],
+        sample_rate: f32,
+        effect_state: &mut ReverbState,
+    ) {
+        if !self.is_enabled {
+            left.copy_from_slice(input);
+            right.copy_from_slice(input);
+            return;
+        }
+        self.update_state(sample_rate, effect_state);
+        for ((input, left), right) in input.iter().zip(left).zip(right) {
+            (*left, *right) = self.sample(*input, sample_rate, effect_state);
+        }
+    }
+
+    /// Create the tank and pre-delay when the design changed.
+    fn update_state(&self, sample_rate: f32, effect_state: &mut ReverbState) {
+        let design = (self.algorithm, sample_rate);
+        if effect_state.design != Some(design) {
+            effect_state.design = Some(design);
+            effect_state.tank = Some(Tank::new(self.algorithm, sample_rate));
+        }
+        let pre_delay = (self.pre_delay.max(0.0) * sample_rate) as usize;
+        if effect_state.pre_delay.len() != pre_delay {
+            effect_state.pre_delay = DelayLine::new(pre_delay);
+        }
+    }
+
+    /// Left and right output for the next input sample.
+    fn sample(&self, input: f32, sample_rate: f32, effect_state: &mut ReverbState) -> (f32, f32) {
+        let mut mix = LfoTarget::Amplitude.apply(self.mix, effect_state.mix_modulation);
+        if let Some(lfo) = &self.lfo {
+            let modulation = lfo.sample(
+                &NoteParameters {
+                    note_time: effect_state.lfo_time,
+                    note_off: None,
+                    note_pitch: 0.0,
+                    gain: 1.0,
+                    sample_rate,
+                },
+                &mut effect_state.lfo,
+            );
+            effect_state.lfo_time += 1.0 / sample_rate;
+            mix = LfoTarget::Amplitude.apply(mix, modulation);
+        }
+        let mix = mix.clamp(0.0, 1.0);
+        let room_size = (self.room_size + effect_state.room_size_modulation).clamp(0.0, 1.0);
+        let damping = self.damping.clamp(0.0, 1.0);
+
+        let delayed = effect_state.pre_delay.process(input);
+        let (left, right) = match &mut effect_state.tank {
+            Some(tank) => tank.process(delayed, room_size, damping),
+            None => (0.0, 0.0),
+        };
+        let width = self.width.clamp(0.0, 1.0);
+        let direct = (1.0 + width) / 2.0;
+        let cross = (1.0 - width) / 2.0;
+        let dry = input * (1.0 - mix);
+        (
+            dry + mix * (left * direct + right * cross),
+            dry + mix * (right * direct + left * cross),
+        )
+    }
+}
+
+impl Effect for Reverb {
+    type EffectState = ReverbState;
+
+    fn effect_create_state(&self) -> Self::EffectState {
+        ReverbState {
+            lfo: self
+                .lfo
+                .map(|lfo| lfo.init_sound_state())
+                .unwrap_or_default(),
+            ..ReverbState::default()
+        }
+    }
+
+    fn effect_apply(
+        &self,
+        audio_buffer: &mut [f32],
+        sample_rate: f32,
+        effect_state: &mut Self::EffectState,
+    ) {
+        if !self.is_enabled {
+            return;
+        }
+        self.update_state(sample_rate, effect_state);
+        audio_buffer.iter_mut().for_each(|out_sample| {
+            let (left, right) = self.sample(*out_sample, sample_rate, effect_state);
+            *out_sample = (left + right) / 2.0;
+        });
+    }
+}
+
+impl ModulationDestination for Reverb {
+    type State = ReverbState;
+
+    /// The mix can be modulated relative to the mix of the effect, the room size is
+    /// modulated linearly.
+    fn parameter_names(&self) -> Vec<String> {
+        vec![String::from("mix"), String::from("room_size")]
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        state.mix_modulation = 0.0;
+        state.room_size_modulation = 0.0;
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        match parameter {
+            "mix" => state.mix_modulation += modulation,
+            "room_size" => state.room_size_modulation += modulation,
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::modulation::destination::ModulationDestination;
+    use audio_engine_effect::effect::Effect;
+
+    use super::{Reverb, ReverbAlgorithm};
+
+    const SAMPLE_RATE: f32 = 44100.0;
+    const ALGORITHMS: [ReverbAlgorithm; 2] = [
+        ReverbAlgorithm::Freeverb,
+        ReverbAlgorithm::FeedbackDelayNetwork,
+    ];
+
+    /// Output of the reverb for an impulse, two seconds long.
+    fn impulse_response(reverb: &Reverb) -> Vec<f32> {
+        let mut samples = vec![0.0; 2 * SAMPLE_RATE as usize];
+        samples[0] = 1.0;
+        let mut state = reverb.effect_create_state();
+        reverb.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        samples
+    }
+
+    fn energy(samples: &[f32], from: f32, to: f32) -> f32 {
+        samples[(from * SAMPLE_RATE) as usize..(to * SAMPLE_RATE) as usize]
+            .iter()
+            .map(|sample| sample * sample)
+            .sum()
+    }
+
+    fn wet_reverb(algorithm: ReverbAlgorithm) -> Reverb {
+        Reverb {
+            is_enabled: true,
+            algorithm,
+            mix: 1.0,
+            ..Reverb::default()
+        }
+    }
+
+    #[test]
+    fn disabled_reverb_passes_signal() {
+        let reverb = Reverb::default();
+        let mut samples = [1.0, 0.5, 0.0, -0.5];
+        let mut state = reverb.effect_create_state();
+        reverb.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([1.0, 0.5, 0.0, -0.5], samples);
+    }
+
+    #[test]
+    fn dry_mix_passes_signal() {
+        for algorithm in ALGORITHMS {
+            let reverb = Reverb {
+                mix: 0.0,
+                ..wet_reverb(algorithm)
+            };
+            let samples = impulse_response(&reverb);
+            assert_eq!(1.0, samples[0]);
+            assert!(samples[1..].iter().all(|sample| *sample == 0.0));
+        }
+    }
+
+    #[test]
+    fn decaying_tail() {
+        for algorithm in ALGORITHMS {
+            let samples = impulse_response(&wet_reverb(algorithm));
+            // Nothing before the pre-delay.
+            assert!(samples[..(0.02 * SAMPLE_RATE) as usize]
+                .iter()
+                .all(|sample| *sample == 0.0));
+            let early = energy(&samples, 0.0, 0.5);
+            let late = energy(&samples, 1.0, 1.5);
+            assert!(early > 0.001, "{algorithm:?}: {early}");
+            assert!(late > 0.0, "{algorithm:?}");
+            assert!(late < early * 0.1, "{algorithm:?}: {early} {late}");
+            assert!(samples.iter().all(|sample| sample.abs() < 1.0));
+        }
+    }
+
+    #[test]
+    fn room_size() {
+        for algorithm in ALGORITHMS {
+            let small = impulse_response(&Reverb {
+                room_size: 0.1,
+                ..wet_reverb(algorithm)
+            });
+            let large = impulse_response(&Reverb {
+                room_size: 0.9,
+                ..wet_reverb(algorithm)
+            });
+            assert!(energy(&large, 1.0, 2.0) > 10.0 * energy(&small, 1.0, 2.0));
+        }
+    }
+
+    #[test]
+    fn largest_room_is_stable() {
+        for algorithm in ALGORITHMS {
+            let samples = impulse_response(&Reverb {
+                room_size: 1.0,
+                damping: 0.0,
+                ..wet_reverb(algorithm)
+            });
+            assert!(energy(&samples, 1.5, 2.0) < energy(&samples, 0.0, 0.5));
+        }
+    }
+
+    #[test]
+    fn damping() {
+        for algorithm in ALGORITHMS {
+            let bright = impulse_response(&Reverb {
+                damping: 0.0,
+                ..wet_reverb(algorithm)
+            });
+            let dark = impulse_response(&Reverb {
+                damping: 1.0,
+                ..wet_reverb(algorithm)
+            });
+            assert!(energy(&dark, 0.5, 1.0) < energy(&bright, 0.5, 1.0));
+        }
+    }
+
+    #[test]
+    fn width() {
+        for algorithm in ALGORITHMS {
+            let mut input = vec![0.0; SAMPLE_RATE as usize];
+            input[0] = 1.0;
+            let mut left = vec![0.0; input.len()];
+            let mut right = vec![0.0; input.len()];
+
+            let mono = Reverb {
+                width: 0.0,
+                ..wet_reverb(algorithm)
+            };
+            let mut state = mono.effect_create_state();
+            mono.effect_apply_stereo(&input, &mut left, &mut right, SAMPLE_RATE, &mut state);
+            assert_eq!(left, right);
+
+            let wide = wet_reverb(algorithm);
+            let mut state = wide.effect_create_state();
+            wide.effect_apply_stereo(&input, &mut left, &mut right, SAMPLE_RATE, &mut state);
+            let difference = left
+                .iter()
+                .zip(&right)
+                .map(|(left, right)| (left - right).powi(2))
+                .sum::<f32>();
+            assert!(difference > 0.001, "{algorithm:?}: {difference}");
+        }
+    }
+
+    #[test]
+    fn modulation() {
+        let reverb = wet_reverb(ReverbAlgorithm::Freeverb);
+        let mut state = reverb.effect_create_state();
+        assert!(reverb.modulate("mix", -1.0, &mut state));
+        assert!(reverb.modulate("room_size", 0.1, &mut state));
+        assert!(!reverb.modulate("level", 1.0, &mut state));
+
+        // The mix is modulated to 0, only the dry signal remains.
+        let mut samples = [1.0, 0.0, 0.0, 0.0];
+        reverb.effect_apply(&mut samples, SAMPLE_RATE, &mut state);
+        assert_eq!([1.0, 0.0, 0.0, 0.0], samples);
+
+        reverb.clear_modulation(&mut state);
+        assert_eq!(0.0, state.mix_modulation);
+        assert_eq!(0.0, state.room_size_modulation);
+    }
+}
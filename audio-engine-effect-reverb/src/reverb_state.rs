@@ -0,0 +1,50 @@
+use audio_engine_common::{lfo::LfoState, note_time::NoteTime};
+use audio_engine_effect::effect_state::EffectState;
+
+use crate::{
+    delay_line::DelayLine, feedback_delay_network::FeedbackDelayNetwork, freeverb::Freeverb,
+    reverb::ReverbAlgorithm,
+};
+
+/// Reverb tank of the selected algorithm.
+#[derive(Debug, Clone)]
+pub enum Tank {
+    Freeverb(Freeverb),
+    FeedbackDelayNetwork(FeedbackDelayNetwork),
+}
+
+impl Tank {
+    pub fn new(algorithm: ReverbAlgorithm, sample_rate: f32) -> Tank {
+        match algorithm {
+            ReverbAlgorithm::Freeverb => Tank::Freeverb(Freeverb::new(sample_rate)),
+            ReverbAlgorithm::FeedbackDelayNetwork => {
+                Tank::FeedbackDelayNetwork(FeedbackDelayNetwork::new(sample_rate))
+            }
+        }
+    }
+
+    /// Wet left and right output for the next input sample.
+    pub fn process(&mut self, input: f32, room_size: f32, damping: f32) -> (f32, f32) {
+        match self {
+            Tank::Freeverb(freeverb) => freeverb.process(input, room_size, damping),
+            Tank::FeedbackDelayNetwork(network) => network.process(input, room_size, damping),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct ReverbState {
+    /// Tank of `design`, created when the effect is first applied.
+    pub tank: Option<Tank>,
+    /// Algorithm and sample rate the tank is created for.
+    pub design: Option<(ReverbAlgorithm, f32)>,
+    pub pre_delay: DelayLine,
+    pub lfo: LfoState,
+    /// Time since the state was created, used to sample the lfo.
+    pub lfo_time: NoteTime,
+    /// Mix modulation of a modulation matrix, relative to the mix.
+    pub mix_modulation: f32,
+    /// Room size modulation of a modulation matrix, added to the room size.
+    pub room_size_modulation: f32,
+}
+impl EffectState for ReverbState {}
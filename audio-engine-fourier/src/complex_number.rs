@@ -8,9 +8,44 @@
 pub type ComplexNumber = (f32, f32);
 pub trait ComplexNumberMethods {
     fn amplitude(&self) -> f32;
+    /// Angle of the complex number in radians.
+    fn phase(&self) -> f32;
+    fn plus(&self, other: ComplexNumber) -> ComplexNumber;
+    fn minus(&self, other: ComplexNumber) -> ComplexNumber;
+    fn multiply(&self, other: ComplexNumber) -> ComplexNumber;
+    fn scale(&self, factor: f32) -> ComplexNumber;
+    /// Complex number with the imaginary part negated.
+    fn conjugate(&self) -> ComplexNumber;
 }
 impl ComplexNumberMethods for ComplexNumber {
     fn amplitude(&self) -> f32 {
         (self.0 * self.0 + self.1 * self.1).sqrt()
     }
+
+    fn phase(&self) -> f32 {
+        self.1.atan2(self.0)
+    }
+
+    fn plus(&self, other: ComplexNumber) -> ComplexNumber {
+        (self.0 + other.0, self.1 + other.1)
+    }
+
+    fn minus(&self, other: ComplexNumber) -> ComplexNumber {
+        (self.0 - other.0, self.1 - other.1)
+    }
+
+    fn multiply(&self, other: ComplexNumber) -> ComplexNumber {
+        (
+            self.0 * other.0 - self.1 * other.1,
+            self.0 * other.1 + self.1 * other.0,
+        )
+    }
+
+    fn scale(&self, factor: f32) -> ComplexNumber {
+        (self.0 * factor, self.1 * factor)
+    }
+
+    fn conjugate(&self) -> ComplexNumber {
+        (self.0, -self.1)
+    }
 }
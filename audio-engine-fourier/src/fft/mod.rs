@@ -0,0 +1,231 @@
+//! Fast fourier transform.
+//!
+//! A direct fourier transform calculates each output bin with a loop over all input
+//! samples (`O(n^2)`). An FFT splits the transform into smaller transforms and reuses
+//! their results (`O(n log n)`).
+//!
+//! - Lengths that are a power of two use the radix-2 algorithm.
+//! - Other lengths use Bluestein's algorithm, which rewrites the transform as a
+//!   convolution that is calculated with a power of two FFT.
+//!
+//! The forward transform is `X[k] = sum(x[n] * e^(-i*TAU*k*n/N))` and isn't normalized.
+//! The inverse transform divides by `N`, so `inverse(forward(x)) == x`.
+use std::f64::consts::PI;
+
+use crate::complex_number::{ComplexNumber, ComplexNumberMethods};
+
+mod real;
+pub use real::RealFft;
+
+#[cfg(test)]
+mod test;
+
+/// Precalculated plan to perform fourier transforms of a single length.
+///
+/// Creating a plan calculates the twiddle factors, reuse the plan when transforming
+/// multiple buffers of the same length.
+///
+/// ```
+/// use audio_engine_fourier::fft::Fft;
+///
+/// let fft = Fft::new(8);
+/// let mut buffer = vec![(1.0, 0.0); 8];
+/// fft.forward(&mut buffer);
+/// assert_eq!(buffer[0], (8.0, 0.0));
+/// assert_eq!(buffer[1], (0.0, 0.0));
+/// fft.inverse(&mut buffer);
+/// assert_eq!(buffer, vec![(1.0, 0.0); 8]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fft {
+    len: usize,
+    plan: Plan,
+}
+
+#[derive(Debug, Clone)]
+enum Plan {
+    Radix2 {
+        /// `e^(-i*TAU*k/len)` for `k` in `0..len/2`.
+        twiddles: Vec<ComplexNumber>,
+        /// Index where each input element is moved to before the butterflies.
+        bit_reverse: Vec<usize>,
+    },
+    Bluestein {
+        /// Power of two FFT that performs the convolution.
+        inner: Box<Fft>,
+        /// `e^(-i*PI*k^2/len)` for `k` in `0..len`.
+        chirp: Vec<ComplexNumber>,
+        /// Spectrum of the conjugated chirp.
+        kernel: Vec<ComplexNumber>,
+    },
+}
+
+impl Fft {
+    pub fn new(len: usize) -> Fft {
+        let plan = if len.is_power_of_two() || len == 0 {
+            Plan::Radix2 {
+                twiddles: (0..len / 2).map(|k| twiddle(k, len)).collect(),
+                bit_reverse: bit_reverse(len),
+            }
+        } else {
+            let inner_len = (2 * len - 1).next_power_of_two();
+            let inner = Fft::new(inner_len);
+            // Use `k^2 mod 2*len` to keep the angle precise for long transforms.
+            let chirp = (0..len)
+                .map(|k| {
+                    let k = k as u64;
+                    let angle = -PI * ((k * k) % (2 * len as u64)) as f64 / len as f64;
+                    (angle.cos() as f32, angle.sin() as f32)
+                })
+                .collect::<Vec<ComplexNumber>>();
+            let mut kernel = vec![(0.0, 0.0); inner_len];
+            kernel[0] = chirp[0].conjugate();
+            for k in 1..len {
+                kernel[k] = chirp[k].conjugate();
+                kernel[inner_len - k] = chirp[k].conjugate();
+            }
+            inner.forward(&mut kernel);
+            Plan::Bluestein {
+                inner: Box::new(inner),
+                chirp,
+                kernel,
+            }
+        };
+        Fft { len, plan }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of values the `*_with_scratch` transforms need as scratch space.
+    pub fn scratch_len(&self) -> usize {
+        match &self.plan {
+            Plan::Radix2 { .. } => 0,
+            Plan::Bluestein { inner, .. } => inner.len(),
+        }
+    }
+
+    /// Transform the buffer from time domain to frequency domain in place.
+    ///
+    /// # Panics
+    ///
+    /// When the length of the buffer doesn't match the length of the plan.
+    pub fn forward(&self, buffer: &mut [ComplexNumber]) {
+        self.forward_with_scratch(buffer, &mut vec![(0.0, 0.0); self.scratch_len()]);
+    }
+
+    /// Like [Fft::forward], without allocating memory.
+    ///
+    /// # Panics
+    ///
+    /// When the length of the buffer doesn't match the length of the plan or the scratch
+    /// is shorter than [Fft::scratch_len].
+    pub fn forward_with_scratch(
+        &self,
+        buffer: &mut [ComplexNumber],
+        scratch: &mut [ComplexNumber],
+    ) {
+        assert_eq!(buffer.len(), self.len, "buffer length doesn't match plan");
+        assert!(scratch.len() >= self.scratch_len(), "scratch is too short");
+        match &self.plan {
+            Plan::Radix2 {
+                twiddles,
+                bit_reverse,
+            } => radix2(buffer, twiddles, bit_reverse),
+            Plan::Bluestein {
+                inner,
+                chirp,
+                kernel,
+            } => {
+                let convolution = &mut scratch[..inner.len()];
+                for (k, value) in buffer.iter().enumerate() {
+                    convolution[k] = value.multiply(chirp[k]);
+                }
+                convolution[buffer.len()..].fill((0.0, 0.0));
+                inner.forward(convolution);
+                for (value, kernel) in convolution.iter_mut().zip(kernel.iter()) {
+                    *value = value.multiply(*kernel);
+                }
+                inner.inverse(convolution);
+                for (k, value) in buffer.iter_mut().enumerate() {
+                    *value = convolution[k].multiply(chirp[k]);
+                }
+            }
+        }
+    }
+
+    /// Transform the buffer from frequency domain to time domain in place.
+    ///
+    /// # Panics
+    ///
+    /// When the length of the buffer doesn't match the length of the plan.
+    pub fn inverse(&self, buffer: &mut [ComplexNumber]) {
+        self.inverse_with_scratch(buffer, &mut vec![(0.0, 0.0); self.scratch_len()]);
+    }
+
+    /// Like [Fft::inverse], without allocating memory.
+    ///
+    /// # Panics
+    ///
+    /// When the length of the buffer doesn't match the length of the plan or the scratch
+    /// is shorter than [Fft::scratch_len].
+    pub fn inverse_with_scratch(
+        &self,
+        buffer: &mut [ComplexNumber],
+        scratch: &mut [ComplexNumber],
+    ) {
+        // The inverse is the forward transform of the conjugated input, conjugated.
+        for value in buffer.iter_mut() {
+            *value = value.conjugate();
+        }
+        self.forward_with_scratch(buffer, scratch);
+        let scale = 1.0 / self.len as f32;
+        for value in buffer.iter_mut() {
+            *value = value.conjugate().scale(scale);
+        }
+    }
+}
+
+fn twiddle(k: usize, len: usize) -> ComplexNumber {
+    let angle = -2.0 * PI * k as f64 / len as f64;
+    (angle.cos() as f32, angle.sin() as f32)
+}
+
+fn bit_reverse(len: usize) -> Vec<usize> {
+    if len < 2 {
+        return (0..len).collect();
+    }
+    let bits = len.trailing_zeros();
+    (0..len)
+        .map(|index| index.reverse_bits() >> (usize::BITS - bits))
+        .collect()
+}
+
+fn radix2(buffer: &mut [ComplexNumber], twiddles: &[ComplexNumber], bit_reverse: &[usize]) {
+    let len = buffer.len();
+    for (index, reversed) in bit_reverse.iter().enumerate() {
+        if index < *reversed {
+            buffer.swap(index, *reversed);
+        }
+    }
+
+    let mut size = 2;
+    while size <= len {
+        let half = size / 2;
+        let twiddle_step = len / size;
+        for start in (0..len).step_by(size) {
+            for j in 0..half {
+                let even = buffer[start + j];
+                let odd = buffer[start + j + half].multiply(twiddles[j * twiddle_step]);
+                buffer[start + j] = even.plus(odd);
+                buffer[start + j + half] = even.minus(odd);
+            }
+        }
+        size *= 2;
+    }
+}
@@ -0,0 +1,218 @@
+use std::f64::consts::PI;
+
+use crate::complex_number::{ComplexNumber, ComplexNumberMethods};
+
+use super::Fft;
+
+/// Fourier transform of real valued samples.
+///
+/// The spectrum of real samples is symmetric, so only the first `len/2 + 1` bins are
+/// returned. Bin `k` belongs to frequency `k * sample_rate / len`.
+///
+/// Even lengths pack the samples into a complex buffer of half the length, which makes
+/// the transform about twice as fast as a complex transform.
+///
+/// ```
+/// use audio_engine_fourier::fft::RealFft;
+///
+/// let fft = RealFft::new(4);
+/// let spectrum = fft.forward(&[1.0, 0.0, -1.0, 0.0]);
+/// assert_eq!(spectrum, vec![(0.0, 0.0), (2.0, 0.0), (0.0, 0.0)]);
+/// assert_eq!(fft.inverse(&spectrum), vec![1.0, 0.0, -1.0, 0.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RealFft {
+    len: usize,
+    plan: RealPlan,
+}
+
+#[derive(Debug, Clone)]
+enum RealPlan {
+    /// Even samples are stored in the real part and odd samples in the imaginary part.
+    Packed {
+        half: Fft,
+        /// `e^(-i*TAU*k/len)` for `k` in `0..=len/2`.
+        twiddles: Vec<ComplexNumber>,
+    },
+    Complex(Fft),
+}
+
+impl RealFft {
+    pub fn new(len: usize) -> RealFft {
+        let plan = if len.is_multiple_of(2) {
+            RealPlan::Packed {
+                half: Fft::new(len / 2),
+                twiddles: (0..=len / 2)
+                    .map(|k| {
+                        let angle = -2.0 * PI * k as f64 / len as f64;
+                        (angle.cos() as f32, angle.sin() as f32)
+                    })
+                    .collect(),
+            }
+        } else {
+            RealPlan::Complex(Fft::new(len))
+        };
+        RealFft { len, plan }
+    }
+
+    /// Number of samples in the time domain.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bins in the frequency domain.
+    pub fn spectrum_len(&self) -> usize {
+        if self.len == 0 {
+            0
+        } else {
+            self.len / 2 + 1
+        }
+    }
+
+    /// Number of values [RealFft::forward_into] and [RealFft::inverse_into] need as
+    /// scratch space.
+    pub fn scratch_len(&self) -> usize {
+        match &self.plan {
+            RealPlan::Packed { half, .. } => half.len() + half.scratch_len(),
+            RealPlan::Complex(fft) => fft.len() + fft.scratch_len(),
+        }
+    }
+
+    /// Transform samples to the first `len/2 + 1` bins of their spectrum.
+    ///
+    /// # Panics
+    ///
+    /// When the number of samples doesn't match the length of the plan.
+    pub fn forward(&self, samples: &[f32]) -> Vec<ComplexNumber> {
+        let mut spectrum = vec![(0.0, 0.0); self.spectrum_len()];
+        self.forward_into(samples, &mut spectrum, &mut self.scratch());
+        spectrum
+    }
+
+    /// Like [RealFft::forward], writes the spectrum without allocating memory.
+    ///
+    /// # Panics
+    ///
+    /// When the number of samples doesn't match the length of the plan, the number of
+    /// bins doesn't match [RealFft::spectrum_len] or the scratch is shorter than
+    /// [RealFft::scratch_len].
+    pub fn forward_into(
+        &self,
+        samples: &[f32],
+        spectrum: &mut [ComplexNumber],
+        scratch: &mut [ComplexNumber],
+    ) {
+        assert_eq!(samples.len(), self.len, "input length doesn't match plan");
+        assert_eq!(
+            spectrum.len(),
+            self.spectrum_len(),
+            "spectrum length doesn't match plan"
+        );
+        assert!(scratch.len() >= self.scratch_len(), "scratch is too short");
+        if self.len == 0 {
+            return;
+        }
+        match &self.plan {
+            RealPlan::Packed { half, twiddles } => {
+                let half_len = half.len();
+                let (packed, scratch) = scratch.split_at_mut(half_len);
+                for (packed, pair) in packed.iter_mut().zip(samples.chunks_exact(2)) {
+                    *packed = (pair[0], pair[1]);
+                }
+                half.forward_with_scratch(packed, scratch);
+                for (k, bin) in spectrum.iter_mut().enumerate() {
+                    let a = packed[k % half_len];
+                    let b = packed[(half_len - k) % half_len].conjugate();
+                    let even = a.plus(b).scale(0.5);
+                    let odd = a.minus(b).multiply((0.0, -0.5));
+                    *bin = even.plus(twiddles[k].multiply(odd));
+                }
+            }
+            RealPlan::Complex(fft) => {
+                let (buffer, scratch) = scratch.split_at_mut(self.len);
+                for (value, sample) in buffer.iter_mut().zip(samples) {
+                    *value = (*sample, 0.0);
+                }
+                fft.forward_with_scratch(buffer, scratch);
+                spectrum.copy_from_slice(&buffer[..spectrum.len()]);
+            }
+        }
+    }
+
+    /// Transform the first `len/2 + 1` bins of a spectrum back to samples.
+    ///
+    /// # Panics
+    ///
+    /// When the number of bins doesn't match [RealFft::spectrum_len].
+    pub fn inverse(&self, spectrum: &[ComplexNumber]) -> Vec<f32> {
+        let mut samples = vec![0.0; self.len];
+        self.inverse_into(spectrum, &mut samples, &mut self.scratch());
+        samples
+    }
+
+    /// Like [RealFft::inverse], writes the samples without allocating memory.
+    ///
+    /// # Panics
+    ///
+    /// When the number of bins doesn't match [RealFft::spectrum_len], the number of
+    /// samples doesn't match the length of the plan or the scratch is shorter than
+    /// [RealFft::scratch_len].
+    pub fn inverse_into(
+        &self,
+        spectrum: &[ComplexNumber],
+        samples: &mut [f32],
+        scratch: &mut [ComplexNumber],
+    ) {
+        assert_eq!(
+            spectrum.len(),
+            self.spectrum_len(),
+            "spectrum length doesn't match plan"
+        );
+        assert_eq!(samples.len(), self.len, "output length doesn't match plan");
+        assert!(scratch.len() >= self.scratch_len(), "scratch is too short");
+        if self.len == 0 {
+            return;
+        }
+        match &self.plan {
+            RealPlan::Packed { half, twiddles } => {
+                let half_len = half.len();
+                let (packed, scratch) = scratch.split_at_mut(half_len);
+                for (k, value) in packed.iter_mut().enumerate() {
+                    let a = spectrum[k];
+                    let b = spectrum[half_len - k].conjugate();
+                    let even = a.plus(b).scale(0.5);
+                    let odd = a.minus(b).scale(0.5).multiply(twiddles[k].conjugate());
+                    *value = even.plus(odd.multiply((0.0, 1.0)));
+                }
+                half.inverse_with_scratch(packed, scratch);
+                for (pair, value) in samples.chunks_exact_mut(2).zip(packed.iter()) {
+                    pair[0] = value.0;
+                    pair[1] = value.1;
+                }
+            }
+            RealPlan::Complex(fft) => {
+                let (buffer, scratch) = scratch.split_at_mut(self.len);
+                for (k, value) in buffer.iter_mut().enumerate() {
+                    *value = if k < spectrum.len() {
+                        spectrum[k]
+                    } else {
+                        spectrum[self.len - k].conjugate()
+                    };
+                }
+                fft.inverse_with_scratch(buffer, scratch);
+                for (sample, value) in samples.iter_mut().zip(buffer.iter()) {
+                    *sample = value.0;
+                }
+            }
+        }
+    }
+
+    /// Scratch space for a single transform.
+    fn scratch(&self) -> Vec<ComplexNumber> {
+        vec![(0.0, 0.0); self.scratch_len()]
+    }
+}
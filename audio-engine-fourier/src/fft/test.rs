@@ -0,0 +1,174 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    complex_number::{ComplexNumber, ComplexNumberMethods},
+    parameters::{FrequencyRange, Parameters, StepType},
+    to_frequency_domain::ToFrequencyDomain,
+    to_spectrum::ToSpectrum,
+    to_time_domain::ToTimeDomain,
+};
+
+use super::{Fft, RealFft};
+
+const RADIX2_LENGTHS: [usize; 6] = [1, 2, 4, 8, 64, 1024];
+const BLUESTEIN_LENGTHS: [usize; 6] = [3, 5, 6, 12, 100, 441];
+
+/// Deterministic test signal with all frequencies present.
+fn signal(len: usize) -> Vec<ComplexNumber> {
+    (0..len)
+        .map(|index| {
+            let x = index as f32;
+            ((x * 0.37).sin() + (x * 1.3).cos() * 0.5, (x * 0.11).cos())
+        })
+        .collect()
+}
+
+/// Reference discrete fourier transform, calculated in f64.
+fn dft(input: &[ComplexNumber]) -> Vec<ComplexNumber> {
+    let len = input.len();
+    (0..len)
+        .map(|k| {
+            let mut sum = (0.0_f64, 0.0_f64);
+            for (n, value) in input.iter().enumerate() {
+                let angle = -TAU * ((k * n) % len) as f64 / len as f64;
+                let (sin, cos) = angle.sin_cos();
+                sum.0 += value.0 as f64 * cos - value.1 as f64 * sin;
+                sum.1 += value.0 as f64 * sin + value.1 as f64 * cos;
+            }
+            (sum.0 as f32, sum.1 as f32)
+        })
+        .collect()
+}
+
+fn assert_close(actual: &[ComplexNumber], expected: &[ComplexNumber], tolerance: f32) {
+    assert_eq!(actual.len(), expected.len());
+    for (index, (a, e)) in actual.iter().zip(expected.iter()).enumerate() {
+        let error = a.minus(*e).amplitude();
+        assert!(error <= tolerance, "bin {index}: {a:?} != {e:?}");
+    }
+}
+
+#[test]
+fn forward_matches_dft() {
+    for len in RADIX2_LENGTHS.iter().chain(BLUESTEIN_LENGTHS.iter()) {
+        let input = signal(*len);
+        let mut output = input.clone();
+        Fft::new(*len).forward(&mut output);
+        assert_close(&output, &dft(&input), 1e-3 * *len as f32);
+    }
+}
+
+#[test]
+fn inverse_restores_input() {
+    for len in RADIX2_LENGTHS.iter().chain(BLUESTEIN_LENGTHS.iter()) {
+        let input = signal(*len);
+        let mut buffer = input.clone();
+        let fft = Fft::new(*len);
+        fft.forward(&mut buffer);
+        fft.inverse(&mut buffer);
+        assert_close(&buffer, &input, 1e-4);
+    }
+}
+
+#[test]
+fn real_forward_matches_dft() {
+    for len in RADIX2_LENGTHS.iter().chain(BLUESTEIN_LENGTHS.iter()) {
+        let samples = signal(*len)
+            .iter()
+            .map(|value| value.0)
+            .collect::<Vec<f32>>();
+        let complex = samples
+            .iter()
+            .map(|sample| (*sample, 0.0))
+            .collect::<Vec<ComplexNumber>>();
+        let expected = dft(&complex);
+
+        let fft = RealFft::new(*len);
+        let spectrum = fft.forward(&samples);
+        assert_eq!(spectrum.len(), fft.spectrum_len());
+        assert_close(&spectrum, &expected[..len / 2 + 1], 1e-3 * *len as f32);
+
+        let output = fft.inverse(&spectrum);
+        for (a, b) in output.iter().zip(samples.iter()) {
+            assert!((a - b).abs() < 1e-4, "{a} != {b}");
+        }
+    }
+}
+
+#[test]
+fn reused_buffers_match_allocating_transforms() {
+    for len in RADIX2_LENGTHS.iter().chain(BLUESTEIN_LENGTHS.iter()) {
+        let fft = RealFft::new(*len);
+        // Scratch and output are dirty from a previous transform.
+        let mut scratch = vec![(1.0, -1.0); fft.scratch_len()];
+        let mut spectrum = vec![(2.0, 2.0); fft.spectrum_len()];
+        let mut output = vec![3.0; *len];
+        for offset in [0, 1] {
+            let samples = signal(*len + offset)[offset..]
+                .iter()
+                .map(|value| value.0)
+                .collect::<Vec<f32>>();
+            fft.forward_into(&samples, &mut spectrum, &mut scratch);
+            assert_eq!(spectrum, fft.forward(&samples));
+            fft.inverse_into(&spectrum, &mut output, &mut scratch);
+            assert_eq!(output, fft.inverse(&spectrum));
+        }
+
+        let complex_fft = Fft::new(*len);
+        let mut scratch = vec![(1.0, -1.0); complex_fft.scratch_len()];
+        let mut buffer = signal(*len);
+        let mut expected = buffer.clone();
+        complex_fft.forward_with_scratch(&mut buffer, &mut scratch);
+        complex_fft.forward(&mut expected);
+        assert_eq!(buffer, expected);
+        complex_fft.inverse_with_scratch(&mut buffer, &mut scratch);
+        complex_fft.inverse(&mut expected);
+        assert_eq!(buffer, expected);
+    }
+}
+
+/// Spectrum is the conjugate of the direct transform, which is normalized by `data_len`.
+#[test]
+fn spectrum_matches_frequency_domain() {
+    let sample_rate = 44100.0;
+    let data_len = 256;
+    let samples = (0..data_len)
+        .map(|index| (index as f32 * TAU as f32 * 1000.0 / sample_rate).sin())
+        .collect::<Vec<f32>>();
+
+    let fourier_series = samples
+        .as_slice()
+        .to_frequency_domain_with_parameters(Parameters {
+            data_len,
+            steps: data_len,
+            step_type: StepType::FrequencyRange(FrequencyRange {
+                start_frequency: 0.0,
+                end_frequency: sample_rate,
+                sample_rate,
+            }),
+        });
+    let spectrum = samples.as_slice().to_spectrum();
+
+    for (bin, value) in spectrum.bins.iter().enumerate() {
+        assert_eq!(
+            spectrum.frequency(bin, sample_rate),
+            fourier_series.parameters.frequency(bin)
+        );
+        let expected = fourier_series.amplitudes[bin]
+            .conjugate()
+            .scale(data_len as f32);
+        assert!(value.minus(expected).amplitude() < 1e-2, "bin {bin}");
+    }
+
+    let output = spectrum.to_time_domain();
+    for (a, b) in output.iter().zip(samples.iter()) {
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+}
+
+#[test]
+fn empty() {
+    let mut buffer = Vec::new();
+    Fft::new(0).forward(&mut buffer);
+    assert_eq!(RealFft::new(0).forward(&[]), Vec::new());
+}
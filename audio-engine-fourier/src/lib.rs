@@ -13,13 +13,17 @@
 //! NOTE: This library is limited to what is needed. Many expected features for a
 //! full fourier transfer library are missing and aren't planned.
 //!
-//! The implementation of fourier transform in this library is known to be slow.
-//! It uses a `o^2` complexity. This can be solved by using a one of the many FFT
-//! algorithms. The idea would be to add a feature set to wrap the API into an
-//! existing library providing the FFT algorithms.
+//! There are two ways to transform.
 //!
-//! This makes the library not useful for real-time applications (yet). Currently
-//! would recommend it only for off-line audio rendering/processing.
+//! - [to_frequency_domain::ToFrequencyDomain] calculates any set of frequencies, for
+//!   example a frequency range around a note. Each frequency loops over all samples,
+//!   which has a `o^2` complexity. Only recommended for off-line processing.
+//! - [to_spectrum::ToSpectrum] uses a fast fourier transform ([fft]). The frequencies
+//!   are fixed by the number of samples, but the `o log(n)` complexity makes it usable
+//!   for real-time applications.
+//!
+//! To analyse how a sound changes over time, for example the decay of an instrument,
+//! [stft::Stft] splits the signal in windowed frames and transforms each frame.
 //!
 //! Thanks to 1 blue 3 brown for their excellent explanation videos which where a
 //! base of this implementation.
@@ -38,6 +42,21 @@
 //! println!("{:#?}", fourier_series);
 //! ```
 //!
+//! # Fast transform from time to frequency domain and back
+//!
+//! ```rust
+//! use std::f32::consts::TAU;
+//! use audio_engine_fourier::{to_spectrum::ToSpectrum, to_time_domain::ToTimeDomain};
+//!
+//! let time_domain = (0..16)
+//!     .map(|e| e as f32 / 16.0 * TAU)
+//!     .map(|radian| radian.sin())
+//!     .collect::<Vec<f32>>();
+//! let spectrum = time_domain.as_slice().to_spectrum();
+//! assert_eq!(spectrum.find_largest_amplitude(), 1);
+//! let output = spectrum.to_time_domain();
+//! ```
+//!
 //! # Transform from frequency to time domain
 //!
 //! This example will generate the values of a single sine wave.
@@ -61,10 +80,16 @@
 //! ```
 
 pub mod complex_number;
+pub mod fft;
 pub mod fourier_series;
 pub mod parameters;
+pub mod phase_vocoder;
+pub mod spectrum;
+pub mod stft;
 pub mod to_frequency_domain;
+pub mod to_spectrum;
 pub mod to_time_domain;
+pub mod window;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,377 @@
+//! Phase vocoder to change the duration or the pitch of a signal independently.
+//!
+//! Playing a sample faster shortens it, but also raises the pitch. A phase vocoder
+//! analyses overlapping frames ([crate::stft]) and places them at a different distance
+//! (hop) from each other when synthesizing. The phase of each bin is advanced with the
+//! frequency that was measured between the analysis frames, so partials continue
+//! smoothly over the new hop.
+//!
+//! - **Time stretch**: analysis hop differs from the synthesis hop.
+//! - **Pitch shift**: hops are equal, but the bins are moved to a scaled frequency.
+//!
+//! Two improvements reduce the typical "phasiness" of a phase vocoder:
+//!
+//! - **Phase locking**: bins around a spectral peak keep their phase relation to the
+//!   peak, so the peak stays a single partial.
+//! - **Transient detection**: when the spectrum changes suddenly (a note attack) the
+//!   phases are reset to the analysed phases so the attack stays sharp.
+//!
+//! ```
+//! use audio_engine_fourier::phase_vocoder::{time_stretch, PhaseVocoderParameters};
+//!
+//! let samples = (0..4410).map(|index| (index as f32 * 0.1).sin()).collect::<Vec<f32>>();
+//! let stretched = time_stretch(&samples, 2.0, PhaseVocoderParameters::default());
+//! assert_eq!(stretched.len(), 8820);
+//! ```
+use std::f32::consts::{PI, TAU};
+
+use crate::{
+    complex_number::{ComplexNumber, ComplexNumberMethods},
+    fft::RealFft,
+    window::WindowFunction,
+};
+
+#[cfg(test)]
+mod test;
+
+/// Relative increase of the spectrum that is seen as a transient.
+const TRANSIENT_THRESHOLD: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseVocoderParameters {
+    /// Number of samples in a frame.
+    pub window_len: usize,
+    /// Number of samples between two synthesized frames.
+    pub hop_size: usize,
+    pub window: WindowFunction,
+    /// Keep the phase relation of bins around spectral peaks.
+    pub phase_locking: bool,
+    /// Reset phases when a transient is detected.
+    pub transient_detection: bool,
+}
+
+impl Default for PhaseVocoderParameters {
+    fn default() -> Self {
+        PhaseVocoderParameters {
+            window_len: 2048,
+            hop_size: 512,
+            window: WindowFunction::Hann,
+            phase_locking: true,
+            transient_detection: true,
+        }
+    }
+}
+
+impl PhaseVocoderParameters {
+    /// Sum of the squared windows that overlap at each sample.
+    ///
+    /// Overlap-add of the synthesized frames should be divided by this value.
+    pub fn normalization(&self) -> f32 {
+        self.window
+            .create(self.window_len)
+            .iter()
+            .map(|factor| factor * factor)
+            .sum::<f32>()
+            / self.hop_size as f32
+    }
+}
+
+/// State of a phase vocoder between frames.
+///
+/// Frames are passed one at a time, so the same vocoder can be used offline and in a
+/// streaming effect.
+#[derive(Debug, Clone)]
+pub struct PhaseVocoder {
+    parameters: PhaseVocoderParameters,
+    fft: RealFft,
+    window: Vec<f32>,
+    previous_phase: Vec<f32>,
+    previous_magnitude: Vec<f32>,
+    synthesis_phase: Vec<f32>,
+    is_first_frame: bool,
+}
+
+impl PhaseVocoder {
+    pub fn new(parameters: PhaseVocoderParameters) -> PhaseVocoder {
+        let fft = RealFft::new(parameters.window_len);
+        let num_bins = fft.spectrum_len();
+        PhaseVocoder {
+            parameters,
+            window: parameters.window.create(parameters.window_len),
+            fft,
+            previous_phase: vec![0.0; num_bins],
+            previous_magnitude: vec![0.0; num_bins],
+            synthesis_phase: vec![0.0; num_bins],
+            is_first_frame: true,
+        }
+    }
+
+    pub fn parameters(&self) -> &PhaseVocoderParameters {
+        &self.parameters
+    }
+
+    /// Forget the previous frames.
+    pub fn reset(&mut self) {
+        self.is_first_frame = true;
+    }
+
+    /// Analyse a frame and synthesize the frame to overlap-add at the synthesis hop.
+    ///
+    /// `analysis_hop` is the distance in samples to the previous analysed frame. Each bin
+    /// is moved to `pitch_ratio` times its frequency. The returned frame is windowed, but
+    /// not normalized (see [PhaseVocoderParameters::normalization]).
+    pub fn process_frame(
+        &mut self,
+        frame: &[f32],
+        analysis_hop: f32,
+        pitch_ratio: f32,
+    ) -> Vec<f32> {
+        let window_len = self.parameters.window_len as f32;
+        let synthesis_hop = self.parameters.hop_size as f32;
+        let windowed = frame
+            .iter()
+            .zip(self.window.iter())
+            .map(|(sample, factor)| sample * factor)
+            .collect::<Vec<f32>>();
+        let spectrum = self.fft.forward(&windowed);
+        let magnitude = spectrum
+            .iter()
+            .map(ComplexNumber::amplitude)
+            .collect::<Vec<f32>>();
+        let phase = spectrum
+            .iter()
+            .map(ComplexNumber::phase)
+            .collect::<Vec<f32>>();
+        let num_bins = spectrum.len();
+
+        let reset_phase = self.is_first_frame
+            || (self.parameters.transient_detection
+                && is_transient(&self.previous_magnitude, &magnitude));
+
+        // Frequency in radians per sample measured between the analysis frames.
+        let frequency = (0..num_bins)
+            .map(|bin| {
+                let bin_frequency = TAU * bin as f32 / window_len;
+                if self.is_first_frame {
+                    return bin_frequency;
+                }
+                let expected = self.previous_phase[bin] + bin_frequency * analysis_hop;
+                bin_frequency + wrap_phase(phase[bin] - expected) / analysis_hop
+            })
+            .collect::<Vec<f32>>();
+
+        // Move each bin to the shifted frequency, the loudest source bin decides the
+        // frequency of the target bin.
+        let mut out_magnitude = vec![0.0; num_bins];
+        let mut out_frequency = vec![0.0; num_bins];
+        let mut source = vec![None; num_bins];
+        for bin in 0..num_bins {
+            let target = (bin as f32 * pitch_ratio).round() as usize;
+            if target >= num_bins {
+                continue;
+            }
+            out_magnitude[target] += magnitude[bin];
+            if source[target].is_none_or(|source: usize| magnitude[source] < magnitude[bin]) {
+                source[target] = Some(bin);
+                out_frequency[target] = frequency[bin] * pitch_ratio;
+            }
+        }
+
+        let peaks = if self.parameters.phase_locking {
+            find_peaks(&out_magnitude)
+        } else {
+            (0..num_bins).collect()
+        };
+        let mut synthesis_phase = vec![0.0; num_bins];
+        for peak in &peaks {
+            synthesis_phase[*peak] = match source[*peak] {
+                Some(bin) if reset_phase => phase[bin],
+                Some(_) => self.synthesis_phase[*peak] + out_frequency[*peak] * synthesis_hop,
+                None => 0.0,
+            };
+        }
+        if self.parameters.phase_locking {
+            for (bin, peak) in nearest_peaks(&peaks, &out_magnitude)
+                .into_iter()
+                .enumerate()
+            {
+                let (Some(peak), Some(source_bin)) = (peak, source[bin]) else {
+                    continue;
+                };
+                let Some(peak_source) = source[peak] else {
+                    continue;
+                };
+                if bin != peak {
+                    synthesis_phase[bin] =
+                        synthesis_phase[peak] + phase[source_bin] - phase[peak_source];
+                }
+            }
+        }
+
+        let out_spectrum = out_magnitude
+            .iter()
+            .zip(synthesis_phase.iter())
+            .map(|(magnitude, phase)| (magnitude * phase.cos(), magnitude * phase.sin()))
+            .collect::<Vec<ComplexNumber>>();
+
+        self.previous_phase = phase;
+        self.previous_magnitude = magnitude;
+        self.synthesis_phase = synthesis_phase
+            .iter()
+            .map(|phase| wrap_phase(*phase))
+            .collect();
+        self.is_first_frame = false;
+
+        self.fft
+            .inverse(&out_spectrum)
+            .iter()
+            .zip(self.window.iter())
+            .map(|(sample, factor)| sample * factor)
+            .collect()
+    }
+}
+
+/// Change the duration of the samples without changing the pitch.
+///
+/// The result has `stretch` times the number of samples. Panics when `stretch` isn't
+/// positive.
+pub fn time_stretch(samples: &[f32], stretch: f32, parameters: PhaseVocoderParameters) -> Vec<f32> {
+    assert!(stretch > 0.0, "stretch should be positive");
+    let output_len = (samples.len() as f32 * stretch).round() as usize;
+    let analysis_hop = parameters.hop_size as f32 / stretch;
+    process(samples, output_len, analysis_hop, 1.0, parameters)
+}
+
+/// Change the pitch of the samples without changing the duration.
+///
+/// Frequencies are multiplied by `pitch_ratio`, use `2^(semitones/12)` to shift by
+/// semitones. Panics when `pitch_ratio` isn't positive.
+pub fn pitch_shift(
+    samples: &[f32],
+    pitch_ratio: f32,
+    parameters: PhaseVocoderParameters,
+) -> Vec<f32> {
+    assert!(pitch_ratio > 0.0, "pitch ratio should be positive");
+    process(
+        samples,
+        samples.len(),
+        parameters.hop_size as f32,
+        pitch_ratio,
+        parameters,
+    )
+}
+
+fn process(
+    samples: &[f32],
+    output_len: usize,
+    analysis_hop: f32,
+    pitch_ratio: f32,
+    parameters: PhaseVocoderParameters,
+) -> Vec<f32> {
+    let mut vocoder = PhaseVocoder::new(parameters);
+    let window_len = parameters.window_len;
+    let hop_size = parameters.hop_size;
+    // Frames are centered on their position, both signals start half a window early.
+    let padding = window_len / 2;
+    let num_frames = (output_len + padding).div_ceil(hop_size);
+
+    let window_squared = parameters
+        .window
+        .create(window_len)
+        .iter()
+        .map(|factor| factor * factor)
+        .collect::<Vec<f32>>();
+    let mut output = vec![0.0; num_frames * hop_size + window_len];
+    let mut normalization = vec![0.0; output.len()];
+    let mut previous_start = 0;
+    for frame_index in 0..num_frames {
+        let start = (frame_index as f32 * analysis_hop).round() as usize;
+        let frame = (start..start + window_len)
+            .map(|index| {
+                index
+                    .checked_sub(padding)
+                    .and_then(|index| samples.get(index))
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<f32>>();
+        let hop = if frame_index == 0 {
+            analysis_hop
+        } else {
+            (start - previous_start) as f32
+        };
+        previous_start = start;
+
+        let synthesized = vocoder.process_frame(&frame, hop.max(1.0), pitch_ratio);
+        let out_start = frame_index * hop_size;
+        for (index, sample) in synthesized.iter().enumerate() {
+            output[out_start + index] += sample;
+            normalization[out_start + index] += window_squared[index];
+        }
+    }
+
+    output
+        .iter()
+        .zip(normalization.iter())
+        .skip(padding)
+        .take(output_len)
+        .map(|(sample, normalization)| {
+            if *normalization > 1e-3 {
+                sample / normalization
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Wrap the phase to `-PI..=PI`.
+fn wrap_phase(phase: f32) -> f32 {
+    phase - TAU * ((phase + PI) / TAU).floor()
+}
+
+/// Spectral flux: the relative increase of the magnitudes compared to the previous frame.
+fn is_transient(previous: &[f32], current: &[f32]) -> bool {
+    let total = current.iter().sum::<f32>();
+    if total <= f32::EPSILON {
+        return false;
+    }
+    let increase = previous
+        .iter()
+        .zip(current.iter())
+        .map(|(previous, current)| (current - previous).max(0.0))
+        .sum::<f32>();
+    increase / total > TRANSIENT_THRESHOLD
+}
+
+/// Bins that are louder than their two neighbours on each side.
+fn find_peaks(magnitude: &[f32]) -> Vec<usize> {
+    (0..magnitude.len())
+        .filter(|bin| {
+            let start = bin.saturating_sub(2);
+            let end = (bin + 3).min(magnitude.len());
+            magnitude[*bin] > 0.0 && (start..end).all(|other| magnitude[other] <= magnitude[*bin])
+        })
+        .collect()
+}
+
+/// Peak that each bin belongs to. The boundary between two peaks is the quietest bin
+/// between them.
+fn nearest_peaks(peaks: &[usize], magnitude: &[f32]) -> Vec<Option<usize>> {
+    let mut result = vec![None; magnitude.len()];
+    if peaks.is_empty() {
+        return result;
+    }
+    let mut region_start = 0;
+    for (index, peak) in peaks.iter().enumerate() {
+        let region_end = match peaks.get(index + 1) {
+            Some(next) => (*peak..*next)
+                .min_by(|a, b| magnitude[*a].total_cmp(&magnitude[*b]))
+                .map_or(*next, |bin| bin + 1),
+            None => magnitude.len(),
+        };
+        result[region_start..region_end].fill(Some(*peak));
+        region_start = region_end;
+    }
+    result
+}
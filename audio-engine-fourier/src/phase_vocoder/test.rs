@@ -0,0 +1,83 @@
+use std::f32::consts::TAU;
+
+use crate::{spectrum::Spectrum, to_spectrum::ToSpectrum};
+
+use super::{pitch_shift, time_stretch, PhaseVocoderParameters};
+
+const SAMPLE_RATE: f32 = 44100.0;
+
+fn sine(frequency: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|index| (TAU * frequency * index as f32 / SAMPLE_RATE).sin())
+        .collect()
+}
+
+/// Frequency of the loudest bin in the middle of the signal.
+fn peak_frequency(samples: &[f32]) -> f32 {
+    let center = samples.len() / 2;
+    let spectrum: Spectrum = (&samples[center - 4096..center + 4096]).to_spectrum();
+    spectrum.frequency(spectrum.find_largest_amplitude(), SAMPLE_RATE)
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[test]
+fn identity() {
+    let samples = sine(440.0, 22050);
+    for phase_locking in [false, true] {
+        let output = time_stretch(
+            &samples,
+            1.0,
+            PhaseVocoderParameters {
+                phase_locking,
+                ..PhaseVocoderParameters::default()
+            },
+        );
+        assert_eq!(output.len(), samples.len());
+        for (a, b) in output.iter().zip(samples.iter()).skip(2048).take(18000) {
+            assert!((a - b).abs() < 0.02, "{a} != {b}");
+        }
+    }
+}
+
+#[test]
+fn time_stretch_keeps_pitch() {
+    let samples = sine(440.0, 44100);
+    for stretch in [0.5, 1.5, 2.0] {
+        let output = time_stretch(&samples, stretch, PhaseVocoderParameters::default());
+        assert_eq!(output.len(), (44100.0 * stretch) as usize);
+        assert!((peak_frequency(&output) - 440.0).abs() < 6.0);
+        let center = output.len() / 2;
+        let level = rms(&output[center - 4096..center + 4096]);
+        assert!((level - 0.5_f32.sqrt()).abs() < 0.05, "{stretch}: {level}");
+    }
+}
+
+#[test]
+fn pitch_shift_keeps_duration() {
+    let samples = sine(440.0, 44100);
+    let fifth = (7.0 / 12.0_f32).exp2();
+    for ratio in [0.5, fifth, 2.0] {
+        let output = pitch_shift(&samples, ratio, PhaseVocoderParameters::default());
+        assert_eq!(output.len(), samples.len());
+        assert!(
+            (peak_frequency(&output) - 440.0 * ratio).abs() < 6.0,
+            "{ratio}: {}",
+            peak_frequency(&output)
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn time_stretch_to_nothing() {
+    time_stretch(&sine(440.0, 4410), 0.0, PhaseVocoderParameters::default());
+}
+
+#[test]
+#[should_panic]
+fn pitch_shift_negative() {
+    pitch_shift(&sine(440.0, 4410), -1.0, PhaseVocoderParameters::default());
+}
@@ -0,0 +1,35 @@
+use crate::complex_number::{ComplexNumber, ComplexNumberMethods};
+
+/// Spectrum of real valued samples calculated with an FFT.
+///
+/// In contrast to [crate::fourier_series::FourierSeries] the bins are fixed by the
+/// number of samples: bin `k` belongs to frequency `k * sample_rate / data_len`. Bins
+/// aren't normalized, divide the amplitude by `data_len / 2` to get the amplitude of
+/// a sine wave.
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    /// Number of samples in the time domain.
+    pub data_len: usize,
+    /// First `data_len/2 + 1` bins of the spectrum.
+    pub bins: Vec<ComplexNumber>,
+}
+
+impl Spectrum {
+    pub fn frequency(&self, bin: usize, sample_rate: f32) -> f32 {
+        bin as f32 * sample_rate / self.data_len as f32
+    }
+
+    pub fn amplitude(&self, bin: usize) -> f32 {
+        self.bins[bin].amplitude()
+    }
+
+    pub fn find_largest_amplitude(&self) -> usize {
+        self.bins
+            .iter()
+            .map(|bin| bin.amplitude())
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(bin, _)| bin)
+            .unwrap()
+    }
+}
@@ -0,0 +1,199 @@
+//! Short-time fourier transform.
+//!
+//! A fourier transform describes a whole block of audio. To see how the spectrum changes
+//! over time the signal is split in overlapping frames, each frame is windowed and
+//! transformed. The result is a time-frequency matrix ([Spectrogram]).
+//!
+//! The inverse transform (overlap-add) transforms each frame back, applies the window
+//! again and adds the frames together. Dividing by the sum of the squared windows
+//! reconstructs the original signal.
+//!
+//! ```
+//! use audio_engine_fourier::{
+//!     stft::{Stft, StftParameters},
+//!     window::WindowFunction,
+//! };
+//!
+//! let stft = Stft::new(StftParameters {
+//!     window_len: 64,
+//!     hop_size: 16,
+//!     window: WindowFunction::Hann,
+//! });
+//! let samples = (0..300).map(|index| (index as f32 * 0.1).sin()).collect::<Vec<f32>>();
+//! let spectrogram = stft.analyze(&samples);
+//! let output = stft.synthesize(&spectrogram);
+//! assert_eq!(output.len(), samples.len());
+//! ```
+use crate::{
+    complex_number::{ComplexNumber, ComplexNumberMethods},
+    fft::RealFft,
+    window::WindowFunction,
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StftParameters {
+    /// Number of samples in a frame.
+    pub window_len: usize,
+    /// Number of samples between the start of two frames.
+    pub hop_size: usize,
+    pub window: WindowFunction,
+}
+
+impl StftParameters {
+    /// Frames start before the signal so every sample is covered by the same number of
+    /// frames.
+    fn padding(&self) -> usize {
+        self.window_len - self.hop_size
+    }
+
+    fn num_frames(&self, data_len: usize) -> usize {
+        if data_len == 0 {
+            0
+        } else {
+            (data_len + self.padding()).div_ceil(self.hop_size)
+        }
+    }
+}
+
+/// Time-frequency matrix of a signal.
+#[derive(Debug, Clone)]
+pub struct Spectrogram {
+    pub parameters: StftParameters,
+    /// Number of samples in the time domain.
+    pub data_len: usize,
+    /// Spectrum of each frame, with `window_len/2 + 1` bins per frame.
+    pub frames: Vec<Vec<ComplexNumber>>,
+}
+
+impl Spectrogram {
+    pub fn num_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn num_bins(&self) -> usize {
+        self.frames.first().map(Vec::len).unwrap_or_default()
+    }
+
+    /// Frequency of the bin in Hz.
+    pub fn frequency(&self, bin: usize, sample_rate: f32) -> f32 {
+        bin as f32 * sample_rate / self.parameters.window_len as f32
+    }
+
+    /// Time in seconds of the center of the frame.
+    pub fn time(&self, frame: usize, sample_rate: f32) -> f32 {
+        let center = (frame * self.parameters.hop_size + self.parameters.window_len / 2) as f32
+            - self.parameters.padding() as f32;
+        center / sample_rate
+    }
+
+    pub fn amplitude(&self, frame: usize, bin: usize) -> f32 {
+        self.frames[frame][bin].amplitude()
+    }
+
+    /// Amplitude of each frame at the bin closest to the frequency.
+    pub fn amplitudes_at(&self, frequency: f32, sample_rate: f32) -> Vec<f32> {
+        let bin = (frequency * self.parameters.window_len as f32 / sample_rate).round() as usize;
+        let bin = bin.min(self.num_bins().saturating_sub(1));
+        self.frames
+            .iter()
+            .map(|frame| frame[bin].amplitude())
+            .collect()
+    }
+}
+
+/// Precalculated plan to perform short-time fourier transforms.
+#[derive(Debug, Clone)]
+pub struct Stft {
+    parameters: StftParameters,
+    fft: RealFft,
+    window: Vec<f32>,
+}
+
+impl Stft {
+    /// # Panics
+    ///
+    /// When the hop size is zero or larger than the window.
+    pub fn new(parameters: StftParameters) -> Stft {
+        assert!(
+            parameters.hop_size > 0 && parameters.hop_size <= parameters.window_len,
+            "hop size should be between 1 and the window length"
+        );
+        Stft {
+            parameters,
+            fft: RealFft::new(parameters.window_len),
+            window: parameters.window.create(parameters.window_len),
+        }
+    }
+
+    pub fn parameters(&self) -> &StftParameters {
+        &self.parameters
+    }
+
+    /// Transform the signal to a time-frequency matrix.
+    pub fn analyze(&self, samples: &[f32]) -> Spectrogram {
+        let padding = self.parameters.padding();
+        let frames = (0..self.parameters.num_frames(samples.len()))
+            .map(|frame| {
+                let start = frame * self.parameters.hop_size;
+                let windowed = self
+                    .window
+                    .iter()
+                    .enumerate()
+                    .map(|(index, factor)| {
+                        (start + index)
+                            .checked_sub(padding)
+                            .and_then(|index| samples.get(index))
+                            .map_or(0.0, |sample| sample * factor)
+                    })
+                    .collect::<Vec<f32>>();
+                self.fft.forward(&windowed)
+            })
+            .collect();
+        Spectrogram {
+            parameters: self.parameters,
+            data_len: samples.len(),
+            frames,
+        }
+    }
+
+    /// Reconstruct the signal with weighted overlap-add.
+    ///
+    /// # Panics
+    ///
+    /// When the spectrogram was created with different parameters.
+    pub fn synthesize(&self, spectrogram: &Spectrogram) -> Vec<f32> {
+        assert_eq!(
+            spectrogram.parameters, self.parameters,
+            "spectrogram parameters don't match"
+        );
+        let padding = self.parameters.padding();
+        let padded_len = spectrogram.num_frames() * self.parameters.hop_size + padding;
+        let mut output = vec![0.0; padded_len];
+        let mut normalization = vec![0.0; padded_len];
+        for (frame, spectrum) in spectrogram.frames.iter().enumerate() {
+            let start = frame * self.parameters.hop_size;
+            let samples = self.fft.inverse(spectrum);
+            for (index, (sample, factor)) in samples.iter().zip(self.window.iter()).enumerate() {
+                output[start + index] += sample * factor;
+                normalization[start + index] += factor * factor;
+            }
+        }
+
+        output
+            .iter()
+            .zip(normalization.iter())
+            .skip(padding)
+            .take(spectrogram.data_len)
+            .map(|(sample, normalization)| {
+                if *normalization > 1e-8 {
+                    sample / normalization
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,99 @@
+use std::f32::consts::TAU;
+
+use crate::window::WindowFunction;
+
+use super::{Stft, StftParameters};
+
+const WINDOWS: [WindowFunction; 5] = [
+    WindowFunction::Rectangular,
+    WindowFunction::Hann,
+    WindowFunction::Hamming,
+    WindowFunction::Blackman,
+    WindowFunction::Kaiser(8.0),
+];
+
+fn signal(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|index| {
+            let x = index as f32;
+            (x * 0.05).sin() + 0.3 * (x * 0.71).cos() + 0.1 * (x * 2.3).sin()
+        })
+        .collect()
+}
+
+#[test]
+fn windows() {
+    let len = 16;
+    for window in WINDOWS {
+        let factors = window.create(len);
+        assert!((factors[len / 2] - 1.0).abs() < 1e-6, "{window:?}");
+        // Periodic windows are symmetric around the center.
+        for index in 1..len / 2 {
+            assert!((factors[index] - factors[len - index]).abs() < 1e-6);
+        }
+    }
+    assert!((WindowFunction::Hamming.factor(0, len) - 0.08).abs() < 1e-6);
+    assert!(WindowFunction::Blackman.factor(0, len).abs() < 1e-6);
+    assert_eq!(
+        WindowFunction::Kaiser(0.0).create(len),
+        WindowFunction::Rectangular.create(len)
+    );
+}
+
+#[test]
+fn overlap_add_reconstructs_signal() {
+    for window in WINDOWS {
+        for (window_len, hop_size) in [(64, 16), (64, 32), (256, 64), (100, 25)] {
+            let stft = Stft::new(StftParameters {
+                window_len,
+                hop_size,
+                window,
+            });
+            let samples = signal(1001);
+            let spectrogram = stft.analyze(&samples);
+            assert_eq!(spectrogram.num_bins(), window_len / 2 + 1);
+
+            let output = stft.synthesize(&spectrogram);
+            assert_eq!(output.len(), samples.len());
+            for (index, (a, b)) in output.iter().zip(samples.iter()).enumerate() {
+                assert!(
+                    (a - b).abs() < 1e-4,
+                    "{window:?} {window_len}/{hop_size} sample {index}: {a} != {b}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn decaying_sine() {
+    let sample_rate = 44100.0;
+    let frequency = 1000.0;
+    let decay_per_second = 4.0;
+    let samples = (0..sample_rate as usize)
+        .map(|index| index as f32 / sample_rate)
+        .map(|time| (TAU * frequency * time).sin() * (-decay_per_second * time).exp())
+        .collect::<Vec<f32>>();
+
+    let stft = Stft::new(StftParameters {
+        window_len: 2048,
+        hop_size: 512,
+        window: WindowFunction::Hann,
+    });
+    let spectrogram = stft.analyze(&samples);
+    let peak_bin = (0..spectrogram.num_bins())
+        .max_by(|a, b| {
+            spectrogram
+                .amplitude(10, *a)
+                .total_cmp(&spectrogram.amplitude(10, *b))
+        })
+        .unwrap();
+    assert!((spectrogram.frequency(peak_bin, sample_rate) - frequency).abs() < 22.0);
+
+    // Frames fully inside the signal decay with the signal.
+    let amplitudes = spectrogram.amplitudes_at(frequency, sample_rate);
+    let (first, last) = (10, 60);
+    let measured = (amplitudes[first] / amplitudes[last]).ln()
+        / (spectrogram.time(last, sample_rate) - spectrogram.time(first, sample_rate));
+    assert!((measured - decay_per_second).abs() < 0.05, "{measured}");
+}
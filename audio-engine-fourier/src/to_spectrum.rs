@@ -0,0 +1,18 @@
+use crate::{fft::RealFft, spectrum::Spectrum};
+
+pub trait ToSpectrum {
+    /// Perform a fast transformation from time domain to frequency domain.
+    ///
+    /// Creates a new plan for each call, use [RealFft] directly when transforming many
+    /// buffers of the same length.
+    fn to_spectrum(&self) -> Spectrum;
+}
+
+impl ToSpectrum for &[f32] {
+    fn to_spectrum(&self) -> Spectrum {
+        Spectrum {
+            data_len: self.len(),
+            bins: RealFft::new(self.len()).forward(self),
+        }
+    }
+}
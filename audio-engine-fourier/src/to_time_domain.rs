@@ -1,4 +1,4 @@
-use crate::fourier_series::FourierSeries;
+use crate::{fft::RealFft, fourier_series::FourierSeries, spectrum::Spectrum};
 
 pub trait ToTimeDomain {
     /// Perform a transformation from frequency domain to time domain.
@@ -23,3 +23,9 @@ impl ToTimeDomain for FourierSeries {
             .collect::<Vec<f32>>()
     }
 }
+
+impl ToTimeDomain for Spectrum {
+    fn to_time_domain(&self) -> Vec<f32> {
+        RealFft::new(self.data_len).inverse(&self.bins)
+    }
+}
@@ -0,0 +1,67 @@
+use std::f32::consts::TAU;
+
+/// Window function to fade the edges of a block before transforming it.
+///
+/// Transforming a block that isn't a whole number of periods spreads the energy of a
+/// frequency over many bins (spectral leakage). Fading in and out reduces the leakage,
+/// each window makes a different trade off between the width of the peak and the
+/// level of the leakage.
+///
+/// Windows are periodic, so overlapping windows add up to a constant, which is needed
+/// to reconstruct a signal with overlap-add.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum WindowFunction {
+    /// No fading.
+    Rectangular,
+    #[default]
+    Hann,
+    Hamming,
+    Blackman,
+    /// Kaiser window with the given beta. Higher betas give lower leakage and wider
+    /// peaks, `0.0` is a rectangular window.
+    Kaiser(f32),
+}
+
+impl WindowFunction {
+    /// Factor of the window at `index`.
+    pub fn factor(&self, index: usize, len: usize) -> f32 {
+        let x = index as f32 / len as f32;
+        match self {
+            WindowFunction::Rectangular => 1.0,
+            WindowFunction::Hann => 0.5 - 0.5 * (TAU * x).cos(),
+            WindowFunction::Hamming => 0.54 - 0.46 * (TAU * x).cos(),
+            WindowFunction::Blackman => 0.42 - 0.5 * (TAU * x).cos() + 0.08 * (2.0 * TAU * x).cos(),
+            WindowFunction::Kaiser(beta) => {
+                let r = 2.0 * x - 1.0;
+                bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(*beta)
+            }
+        }
+    }
+
+    /// Factors of the window with the given length.
+    ///
+    /// ```
+    /// use audio_engine_fourier::window::WindowFunction;
+    ///
+    /// assert_eq!(WindowFunction::Hann.create(4), vec![0.0, 0.5, 1.0, 0.5]);
+    /// ```
+    pub fn create(&self, len: usize) -> Vec<f32> {
+        (0..len).map(|index| self.factor(index, len)).collect()
+    }
+}
+
+/// Modified Bessel function of the first kind, order 0.
+fn bessel_i0(x: f32) -> f32 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..50 {
+        term *= half_x / k as f32;
+        let addition = term * term;
+        sum += addition;
+        if addition < sum * 1e-9 {
+            break;
+        }
+    }
+    sum
+}
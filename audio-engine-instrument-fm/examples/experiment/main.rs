@@ -28,8 +28,8 @@ fn play_tone(device: &cpal::Device, config: &cpal::StreamConfig) -> Result<(), (
 
     let mut sample_num = 0_u64;
     let mut instrument = FMInstrument::<DelayAttackHoldDecaySustainRelease> {
-        operators: Operators::<DelayAttackHoldDecaySustainRelease> {
-            a: Operator {
+        operators: Operators::from(vec![
+            Operator {
                 waveform: Waveform::Sine,
                 frequency: RATED_1,
                 level: 1.0,
@@ -43,18 +43,17 @@ fn play_tone(device: &cpal::Device, config: &cpal::StreamConfig) -> Result<(), (
                 },
                 ..Operator::default()
             },
-            b: Operator {
+            Operator {
                 waveform: Waveform::Triangle,
                 frequency: RATED_2,
                 level: 64.0,
                 ..Operator::default()
             },
-            ..Operators::default()
-        },
+        ]),
         algorithm_preset: FM_ALGORITHM_BASIC_B_MOD_A,
         ..FMInstrument::<DelayAttackHoldDecaySustainRelease>::default()
     };
-    instrument.compile();
+    instrument.compile().unwrap();
     let mut instrument_state = FMInstrumentNoteState::default();
     let note_pitch = 437.0;
 
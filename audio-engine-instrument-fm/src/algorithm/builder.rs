@@ -1,19 +1,19 @@
 use crate::{
-    algorithm::compiled::{CompiledAlgorithm, StackID},
+    algorithm::{
+        compiled::{CompiledAlgorithm, StackID},
+        execution_step::{ExecutionStepID, Feedback, MAX_FEEDBACK_LEVEL},
+    },
     operator::OperatorID,
 };
 
 fn next_step_id(compiled_algorithm: &CompiledAlgorithm) -> StackID {
-    let mut result = None;
+    let mut result = 0;
     for step in &compiled_algorithm.execution_steps {
         if let StackID::Index(stack_index) = step.stack_out {
-            match result {
-                None => result = Some(stack_index),
-                Some(prev) => result = Some(prev.max(stack_index + 1)),
-            }
+            result = result.max(stack_index + 1);
         }
     }
-    StackID::from(result.unwrap_or_default())
+    StackID::from(result)
 }
 
 pub fn build_step(
@@ -38,23 +38,26 @@ pub fn build_steps(
     stack_out[0]
 }
 
-pub fn build_repeat(
+/// Build a chain of operators where the output of the last operator is fed back into the
+/// first operator.
+///
+/// A feedback level of 0 disables the feedback.
+pub fn build_feedback(
     result: &mut CompiledAlgorithm,
     operators: &[OperatorID],
-    repeat: u8,
+    feedback: u8,
 ) -> StackID {
     assert!(!operators.is_empty());
-    assert!(result.execution_steps.is_empty());
-    let mut stack_in = vec![];
-    let mut stack_index = 0;
-    for _ in 0..=repeat {
-        for operator_id in operators {
-            result.add_step(stack_in, *operator_id, StackID::from(stack_index));
-            stack_in = vec![StackID::from(stack_index)];
-            stack_index += 1;
-        }
+    let first_step = result.execution_steps.len();
+    let stack_out = build_steps(result, vec![], operators);
+    if feedback > 0 {
+        let last_step = result.execution_steps.len() - 1;
+        result.execution_steps[first_step].feedback = Some(Feedback {
+            level: feedback.min(MAX_FEEDBACK_LEVEL),
+            step: ExecutionStepID::from(last_step as u8),
+        });
     }
-    stack_in[0]
+    stack_out
 }
 
 fn build_stack_size(result: &mut CompiledAlgorithm) {
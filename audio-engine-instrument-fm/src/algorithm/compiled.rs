@@ -1,8 +1,8 @@
 use audio_engine_common::{digital_sound::parameters::NoteParameters, envelope::Envelope, id::ID};
 
-use crate::operator::{OperatorID, OperatorNoteState, Operators};
+use crate::operator::{Operator, OperatorID, OperatorNoteState, Operators};
 
-use super::execution_step::ExecutionStep;
+use super::execution_step::{ExecutionStep, ExecutionStepID, Feedback, StepParameters};
 
 pub type StackID = ID;
 
@@ -21,23 +21,15 @@ pub type StackID = ID;
 /// Operators write there result to the stack. [carrier_output] points to location
 /// on the stack where the carrier has written its result to.
 ///
-/// # Unrolling repeat
+/// # Feedback
 ///
-/// During compilation the operator repeat is unrolled resulting in a vector of
-/// [ExecutionStep]. They are stored in [CompiledAlgorithm::execution_steps].
+/// Feedback loops are not unrolled. The first step of the loop reads the output
+/// of the last step of the loop from the previous sample. See [ExecutionStep::feedback].
 ///
-/// Unrolling ensures that each step can have its own execution state and is
-/// independent from the number of times a specific operator is used.
+/// # Optimization
 ///
-/// # TODO
-///
-/// - Optimize execution by removing execution steps won't change the final
-///   output. This happens when certain operators are disabled or when their
-///   gain is zero.
-/// - Currently all execution steps write to their own reserved space on the
-///   stack. Stack space can be saved when operators are sequential and don't
-///   branch. Or in other way when not read from anymore, other operators
-///   may reuse already reserved stack space.
+/// [CompiledAlgorithm::optimize] removes execution steps that won't change the final
+/// output and reuses stack space that isn't read from anymore.
 #[derive(Debug, Default, Clone)]
 pub struct CompiledAlgorithm {
     /// A compiled algorithm is a vec of execution steps that are executed in order.
@@ -65,6 +57,7 @@ impl CompiledAlgorithm {
             stack_in,
             operator_index: operator,
             stack_out,
+            feedback: None,
         };
         self.execution_steps.push(step);
     }
@@ -74,20 +67,90 @@ impl CompiledAlgorithm {
 pub struct CompiledAlgorithmState {
     pub stack: Vec<f32>,
     pub execution_step_state: Vec<OperatorNoteState>,
+    /// Multiplier of the level of each operator, indexed by operator index.
+    ///
+    /// Operators without an entry are not modulated.
+    pub level_modulation: Vec<f32>,
 }
 
 impl CompiledAlgorithm {
+    /// Note state at the start of a note.
+    pub fn init_note_state<E: Envelope>(&self, operators: &Operators<E>) -> CompiledAlgorithmState {
+        CompiledAlgorithmState {
+            stack: vec![0.0; self.stack_size],
+            execution_step_state: self
+                .execution_steps
+                .iter()
+                .map(|step| {
+                    operators
+                        .get_operator(step.operator_index)
+                        .map_or(OperatorNoteState::default(), Operator::init_note_state)
+                })
+                .collect(),
+            level_modulation: Vec::default(),
+        }
+    }
+
+    /// Reset the note state of the previous note for a new note. The phase of operators
+    /// without key sync is kept.
+    pub fn restart_note_state<E: Envelope>(
+        &self,
+        operators: &Operators<E>,
+        note_state: &mut CompiledAlgorithmState,
+    ) {
+        self.init_state(note_state);
+        for (step, step_state) in self
+            .execution_steps
+            .iter()
+            .zip(note_state.execution_step_state.iter_mut())
+        {
+            match operators.get_operator(step.operator_index) {
+                Some(operator) => operator.restart_note_state(step_state),
+                None => step_state.reset(),
+            }
+        }
+        note_state.stack.fill(0.0);
+        note_state.level_modulation.clear();
+    }
+
     pub fn sample<E: Envelope>(
         &self,
         parameters: &NoteParameters,
         operators: &Operators<E>,
         note_state: &mut CompiledAlgorithmState,
+    ) -> f32 {
+        self.sample_modulated(parameters, parameters, operators, note_state)
+    }
+
+    /// Sample a note of which the pitch or gain is modulated.
+    ///
+    /// The frequencies of the operators follow the pitch of the modulated parameters and
+    /// the output is multiplied by their gain. Velocity sensitivity and keyboard scaling
+    /// use the pitch and gain of the note as it was played.
+    pub fn sample_modulated<E: Envelope>(
+        &self,
+        note: &NoteParameters,
+        parameters: &NoteParameters,
+        operators: &Operators<E>,
+        note_state: &mut CompiledAlgorithmState,
     ) -> f32 {
         self.init_state(note_state);
-        self.execute_steps(parameters, operators, note_state);
+        self.execute_steps(note, parameters, operators, note_state);
         self.sum_carrier_result(note_state) * parameters.gain
     }
 
+    /// Is the output of the execution step read as carrier output.
+    ///
+    /// Stack space can be reused, only the last step writing to a carrier position is a
+    /// carrier.
+    fn is_carrier(&self, step_index: usize) -> bool {
+        let stack_out = self.execution_steps[step_index].stack_out;
+        self.carrier_output.contains(&stack_out)
+            && !self.execution_steps[step_index + 1..]
+                .iter()
+                .any(|step| step.stack_out == stack_out)
+    }
+
     fn init_state(&self, note_state: &mut CompiledAlgorithmState) {
         note_state.stack.reserve_exact(self.stack_size);
         note_state.stack.resize(self.stack_size, f32::default());
@@ -101,16 +164,52 @@ impl CompiledAlgorithm {
 
     fn execute_steps<E: Envelope>(
         &self,
+        note: &NoteParameters,
         parameters: &NoteParameters,
         operators: &Operators<E>,
         note_state: &mut CompiledAlgorithmState,
     ) {
-        for (step, step_state) in self
-            .execution_steps
-            .iter()
-            .zip(note_state.execution_step_state.iter_mut())
-        {
-            step.execute(parameters, operators, step_state, &mut note_state.stack);
+        let step_parameters = StepParameters {
+            parameters,
+            key_pitch: note.note_pitch,
+            velocity: note.gain,
+            level_modulation: &note_state.level_modulation,
+        };
+        for (index, step) in self.execution_steps.iter().enumerate() {
+            let feedback = step.feedback.map_or(0.0, |feedback| {
+                self.feedback_value(feedback, parameters, operators, note_state)
+            });
+            step.execute(
+                &step_parameters,
+                operators,
+                self.is_carrier(index),
+                feedback,
+                &mut note_state.execution_step_state[index],
+                &mut note_state.stack,
+            );
+        }
+    }
+
+    /// Value of the feedback, in the frequency units of modulator outputs.
+    fn feedback_value<E: Envelope>(
+        &self,
+        feedback: Feedback,
+        parameters: &NoteParameters,
+        operators: &Operators<E>,
+        note_state: &CompiledAlgorithmState,
+    ) -> f32 {
+        let carrier_operator = match feedback.step {
+            ExecutionStepID::Index(index) if self.is_carrier(index as usize) => {
+                operators.get_operator(self.execution_steps[index as usize].operator_index)
+            }
+            _ => None,
+        };
+        match carrier_operator {
+            Some(operator) => feedback.carrier_value(
+                &note_state.execution_step_state,
+                operator.frequency.apply(parameters.note_pitch),
+            ),
+            None => feedback.value(&note_state.execution_step_state),
         }
     }
 
@@ -1,3 +1,5 @@
+use std::f32::consts::PI;
+
 use audio_engine_common::{digital_sound::parameters::NoteParameters, envelope::Envelope, id::ID};
 
 use crate::operator::{OperatorID, OperatorNoteState, Operators};
@@ -12,29 +14,109 @@ pub struct ExecutionStep {
     pub operator_index: OperatorID,
     /// Where to write the result of the operator.
     pub stack_out: StackID,
+    /// Feed back the previous output of an execution step into the input.
+    pub feedback: Option<Feedback>,
+}
+
+/// Highest feedback level, equal to the DX7.
+pub const MAX_FEEDBACK_LEVEL: u8 = 7;
+
+/// Modulation index of a modulator at the maximum output level.
+///
+/// Modulators add their output to the frequency of the operators they modulate, so a
+/// modulator with this index has a level of `MAX_MODULATION_INDEX` times its frequency.
+pub const MAX_MODULATION_INDEX: f32 = 4.0 * PI;
+
+/// One sample feedback of the output of an execution step.
+///
+/// The average of the last two outputs of the step is used, which dampens the oscillation
+/// that occurs at high feedback levels. The level is in the range of 0 to 7 where each
+/// level doubles the amount of feedback. Level 7 feeds back the full output.
+///
+/// The feedback is added to the frequency of the operator, like the output of modulators.
+/// Carriers output a level instead of a frequency, their output is converted to a
+/// modulation index of up to [MAX_MODULATION_INDEX], see [Feedback::carrier_value].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Feedback {
+    pub level: u8,
+    /// Execution step to take the output from. Can be the step itself.
+    pub step: ExecutionStepID,
+}
+
+impl Feedback {
+    /// Value to add to the input of the step.
+    pub fn value(&self, step_states: &[OperatorNoteState]) -> f32 {
+        if self.level == 0 {
+            return 0.0;
+        }
+        if let ExecutionStepID::Index(index) = self.step {
+            let previous_output = step_states[index as usize].previous_output;
+            let scale =
+                (self.level.min(MAX_FEEDBACK_LEVEL) as f32 - MAX_FEEDBACK_LEVEL as f32).exp2();
+            (previous_output[0] + previous_output[1]) * 0.5 * scale
+        } else {
+            0.0
+        }
+    }
+
+    /// Value to add to the input of the step when the feedback is taken from a carrier.
+    ///
+    /// `frequency` is the frequency of the operator of the carrier, at full output it
+    /// modulates with [MAX_MODULATION_INDEX].
+    pub fn carrier_value(&self, step_states: &[OperatorNoteState], frequency: f32) -> f32 {
+        self.value(step_states) * MAX_MODULATION_INDEX * frequency
+    }
+}
+
+/// Parameters shared by all execution steps of a sample.
+pub struct StepParameters<'a> {
+    /// Parameters of the note, the pitch and gain can be modulated by the instrument.
+    pub parameters: &'a NoteParameters,
+    /// Pitch of the key that is played, before modulation. Used for keyboard scaling.
+    pub key_pitch: f32,
+    /// Gain of the note as it was played, before modulation. Used for velocity
+    /// sensitivity of modulators.
+    pub velocity: f32,
+    /// Multiplier of the level of each operator, see
+    /// [super::compiled::CompiledAlgorithmState::level_modulation].
+    pub level_modulation: &'a [f32],
 }
 
 impl ExecutionStep {
+    /// Execute the operator of the step. Carriers ignore the velocity sensitivity of their
+    /// operator, see [crate::operator::Operator::velocity_sensitivity].
     pub fn execute<E: Envelope>(
         &self,
-        parameters: &NoteParameters,
+        parameters: &StepParameters,
         operators: &Operators<E>,
+        is_carrier: bool,
+        feedback: f32,
         step_state: &mut OperatorNoteState,
         stack: &mut [f32],
     ) {
-        let note_pitch_base = parameters.note_pitch;
-        let note_pitch_modulator = self.sum_inputs(stack);
+        let mut note_pitch_modulator = self.sum_inputs(stack);
+        if self.feedback.is_some() {
+            note_pitch_modulator += feedback;
+        }
         if let (Some(operator), StackID::Index(index)) =
             (operators.get_operator(self.operator_index), self.stack_out)
         {
+            let velocity = if is_carrier { 1.0 } else { parameters.velocity };
             let result = operator.sample(
-                parameters.note_time,
-                parameters.note_off,
-                note_pitch_base,
+                parameters.parameters,
+                parameters.key_pitch,
+                velocity,
                 note_pitch_modulator,
-                parameters.sample_rate,
                 step_state,
             );
+            let result = match self.operator_index {
+                OperatorID::Index(operator_index) => parameters
+                    .level_modulation
+                    .get(operator_index as usize)
+                    .map_or(result, |modulation| result * modulation),
+                OperatorID::NotSet => result,
+            };
+            step_state.previous_output = [result, step_state.previous_output[0]];
             stack[index as usize] = result;
         }
     }
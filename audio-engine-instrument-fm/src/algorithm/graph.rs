@@ -0,0 +1,508 @@
+//! User defined algorithms.
+//!
+//! An [AlgorithmGraph] wires operators together. Modulation connections must form a
+//! directed acyclic graph, loops are only possible via feedback connections which read
+//! the output of the previous sample.
+//!
+//! ```
+//! use audio_engine_instrument_fm::{
+//!     algorithm::graph::AlgorithmGraph,
+//!     operator::{OPERATOR_1, OPERATOR_2, OPERATOR_3},
+//! };
+//!
+//! let graph = AlgorithmGraph::default()
+//!     .feedback(OPERATOR_3, OPERATOR_3)
+//!     .modulate(OPERATOR_3, OPERATOR_1)
+//!     .modulate(OPERATOR_3, OPERATOR_2)
+//!     .carrier(OPERATOR_1)
+//!     .carrier(OPERATOR_2);
+//! assert!(graph.validate().is_ok());
+//!
+//! let text = graph.to_string();
+//! assert_eq!(text, "3 ~> 3\n3 -> 1\n3 -> 2\n1 -> out\n2 -> out");
+//! assert_eq!(text.parse::<AlgorithmGraph>().unwrap(), graph);
+//!
+//! let compiled = graph.compile(7).unwrap();
+//! assert_eq!(compiled.execution_steps.len(), 3);
+//! ```
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    algorithm::{
+        builder::{build_carrier_out, build_step},
+        compiled::{CompiledAlgorithm, StackID},
+        execution_step::{ExecutionStepID, Feedback, MAX_FEEDBACK_LEVEL},
+    },
+    operator::OperatorID,
+};
+
+const CARRIER_OUTPUT: &str = "out";
+const MODULATE_ARROW: &str = "->";
+const FEEDBACK_ARROW: &str = "~>";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Connection {
+    /// Output of an operator modulates another operator.
+    Modulate {
+        modulator: OperatorID,
+        carrier: OperatorID,
+    },
+    /// Previous output of an operator modulates another operator (or itself).
+    Feedback { from: OperatorID, to: OperatorID },
+    /// Operator is part of the output.
+    Carrier(OperatorID),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    /// Graph doesn't have any carrier.
+    NoCarriers,
+    /// Operator modulates itself (directly or indirectly) without feedback.
+    Cycle(OperatorID),
+    /// Operator isn't connected (directly or indirectly) to the output.
+    Disconnected(OperatorID),
+    /// Operator has more than one feedback connection as input.
+    MultipleFeedback(OperatorID),
+    /// Operator is connected to the output more than once.
+    DuplicateCarrier(OperatorID),
+    /// Modulation connection is listed more than once.
+    DuplicateConnection(Connection),
+    /// Text isn't a valid connection.
+    InvalidConnection(String),
+}
+
+impl Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::NoCarriers => write!(f, "algorithm has no carriers"),
+            GraphError::Cycle(operator) => {
+                write!(f, "operator {} is part of a cycle", OperatorName(*operator))
+            }
+            GraphError::Disconnected(operator) => write!(
+                f,
+                "operator {} isn't connected to the output",
+                OperatorName(*operator)
+            ),
+            GraphError::MultipleFeedback(operator) => write!(
+                f,
+                "operator {} has more than one feedback input",
+                OperatorName(*operator)
+            ),
+            GraphError::DuplicateCarrier(operator) => write!(
+                f,
+                "operator {} is connected to the output more than once",
+                OperatorName(*operator)
+            ),
+            GraphError::DuplicateConnection(connection) => {
+                write!(f, "connection '{connection}' is listed more than once")
+            }
+            GraphError::InvalidConnection(text) => write!(f, "invalid connection '{text}'"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// Algorithm defined by connections between operators.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AlgorithmGraph {
+    pub connections: Vec<Connection>,
+}
+
+impl AlgorithmGraph {
+    pub fn modulate(mut self, modulator: OperatorID, carrier: OperatorID) -> Self {
+        self.connections
+            .push(Connection::Modulate { modulator, carrier });
+        self
+    }
+
+    pub fn feedback(mut self, from: OperatorID, to: OperatorID) -> Self {
+        self.connections.push(Connection::Feedback { from, to });
+        self
+    }
+
+    pub fn carrier(mut self, operator: OperatorID) -> Self {
+        self.connections.push(Connection::Carrier(operator));
+        self
+    }
+
+    /// All operators in the graph in order of first appearance.
+    pub fn operators(&self) -> Vec<OperatorID> {
+        let mut result = Vec::default();
+        let mut add = |operator: OperatorID| {
+            if !result.contains(&operator) {
+                result.push(operator);
+            }
+        };
+        for connection in &self.connections {
+            match *connection {
+                Connection::Modulate { modulator, carrier } => {
+                    add(modulator);
+                    add(carrier);
+                }
+                Connection::Feedback { from, to } => {
+                    add(from);
+                    add(to);
+                }
+                Connection::Carrier(operator) => add(operator),
+            }
+        }
+        result
+    }
+
+    fn carriers(&self) -> Vec<OperatorID> {
+        self.connections
+            .iter()
+            .filter_map(|connection| match connection {
+                Connection::Carrier(operator) => Some(*operator),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn modulators(&self, carrier: OperatorID) -> Vec<OperatorID> {
+        self.connections
+            .iter()
+            .filter_map(|connection| match *connection {
+                Connection::Modulate {
+                    modulator,
+                    carrier: modulated,
+                } if modulated == carrier => Some(modulator),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Operators ordered so every operator comes after its modulators.
+    fn execution_order(&self) -> Result<Vec<OperatorID>, GraphError> {
+        let mut pending = self.operators();
+        let mut result = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let next = pending.iter().position(|operator| {
+                self.modulators(*operator)
+                    .iter()
+                    .all(|modulator| result.contains(modulator))
+            });
+            match next {
+                Some(index) => result.push(pending.remove(index)),
+                None => return Err(GraphError::Cycle(pending[0])),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Check that the graph can be compiled.
+    ///
+    /// Validation rules that are checked:
+    ///
+    /// - There is at least one carrier.
+    /// - Modulation connections don't form a cycle.
+    /// - All operators should be connected (direct or indirect) to the output.
+    /// - An operator has at most one feedback connection as input.
+    /// - Carriers and modulation connections are listed once.
+    pub fn validate(&self) -> Result<(), GraphError> {
+        let carriers = self.carriers();
+        if carriers.is_empty() {
+            return Err(GraphError::NoCarriers);
+        }
+        for (index, connection) in self.connections.iter().enumerate() {
+            if self.connections[..index].contains(connection) {
+                return Err(match *connection {
+                    Connection::Carrier(operator) => GraphError::DuplicateCarrier(operator),
+                    Connection::Feedback { to, .. } => GraphError::MultipleFeedback(to),
+                    connection => GraphError::DuplicateConnection(connection),
+                });
+            }
+        }
+        self.execution_order()?;
+
+        let mut connected = carriers;
+        let mut index = 0;
+        while index < connected.len() {
+            for modulator in self.modulators(connected[index]) {
+                if !connected.contains(&modulator) {
+                    connected.push(modulator);
+                }
+            }
+            index += 1;
+        }
+        if let Some(operator) = self
+            .operators()
+            .into_iter()
+            .find(|operator| !connected.contains(operator))
+        {
+            return Err(GraphError::Disconnected(operator));
+        }
+
+        let mut feedback_inputs = Vec::default();
+        for connection in &self.connections {
+            if let Connection::Feedback { to, .. } = connection {
+                if feedback_inputs.contains(to) {
+                    return Err(GraphError::MultipleFeedback(*to));
+                }
+                feedback_inputs.push(*to);
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile the graph. Each operator becomes a single execution step.
+    ///
+    /// A feedback level of 0 disables all feedback connections.
+    pub fn compile(&self, feedback: u8) -> Result<CompiledAlgorithm, GraphError> {
+        self.validate()?;
+        let mut result = CompiledAlgorithm::default();
+        let order = self.execution_order()?;
+        let mut stack_out = Vec::<(OperatorID, StackID)>::default();
+        let output_of = |stack_out: &[(OperatorID, StackID)], operator: OperatorID| {
+            stack_out
+                .iter()
+                .find(|(output_operator, _)| *output_operator == operator)
+                .map(|(_, stack_id)| *stack_id)
+                .unwrap()
+        };
+        for operator in &order {
+            let stack_in = self
+                .modulators(*operator)
+                .iter()
+                .map(|modulator| output_of(&stack_out, *modulator))
+                .collect();
+            let out = build_step(&mut result, stack_in, *operator);
+            stack_out.push((*operator, out));
+        }
+
+        if feedback > 0 {
+            for connection in &self.connections {
+                if let Connection::Feedback { from, to } = connection {
+                    let step_of = |operator| order.iter().position(|o| *o == operator).unwrap();
+                    result.execution_steps[step_of(*to)].feedback = Some(Feedback {
+                        level: feedback.min(MAX_FEEDBACK_LEVEL),
+                        step: ExecutionStepID::from(step_of(*from) as u8),
+                    });
+                }
+            }
+        }
+
+        let carriers = self
+            .carriers()
+            .iter()
+            .map(|carrier| output_of(&stack_out, *carrier))
+            .collect();
+        build_carrier_out(&mut result, carriers);
+        Ok(result)
+    }
+}
+
+/// Operator as written in text: `1` for the first operator.
+struct OperatorName(OperatorID);
+
+impl Display for OperatorName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            OperatorID::Index(index) => write!(f, "{}", index as u16 + 1),
+            OperatorID::NotSet => write!(f, "-"),
+        }
+    }
+}
+
+fn parse_operator(text: &str) -> Option<OperatorID> {
+    match text.parse::<u8>() {
+        Ok(number) if number > 0 => Some(OperatorID::from(number - 1)),
+        _ => None,
+    }
+}
+
+impl Display for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Connection::Modulate { modulator, carrier } => write!(
+                f,
+                "{} {MODULATE_ARROW} {}",
+                OperatorName(modulator),
+                OperatorName(carrier)
+            ),
+            Connection::Feedback { from, to } => write!(
+                f,
+                "{} {FEEDBACK_ARROW} {}",
+                OperatorName(from),
+                OperatorName(to)
+            ),
+            Connection::Carrier(operator) => write!(
+                f,
+                "{} {MODULATE_ARROW} {CARRIER_OUTPUT}",
+                OperatorName(operator)
+            ),
+        }
+    }
+}
+
+impl FromStr for Connection {
+    type Err = GraphError;
+
+    /// Parse `2 -> 1` (modulation), `2 ~> 2` (feedback) or `1 -> out` (carrier).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || GraphError::InvalidConnection(s.to_string());
+        let parts = s.split_whitespace().collect::<Vec<&str>>();
+        let [from, arrow, to] = parts[..] else {
+            return Err(invalid());
+        };
+        let from = parse_operator(from).ok_or_else(invalid)?;
+        match (arrow, to) {
+            (MODULATE_ARROW, CARRIER_OUTPUT) => Ok(Connection::Carrier(from)),
+            (MODULATE_ARROW, to) => Ok(Connection::Modulate {
+                modulator: from,
+                carrier: parse_operator(to).ok_or_else(invalid)?,
+            }),
+            (FEEDBACK_ARROW, to) => Ok(Connection::Feedback {
+                from,
+                to: parse_operator(to).ok_or_else(invalid)?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// One connection per line.
+impl Display for AlgorithmGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, connection) in self.connections.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{connection}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse connections separated by new lines or `;`. The parsed graph is validated.
+impl FromStr for AlgorithmGraph {
+    type Err = GraphError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let connections = s
+            .split(['\n', ';'])
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::parse::<Connection>)
+            .collect::<Result<Vec<Connection>, GraphError>>()?;
+        let result = AlgorithmGraph { connections };
+        result.validate()?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        algorithm::{execution_step::ExecutionStepID, preset::Algorithm},
+        operator::{OPERATOR_1, OPERATOR_2, OPERATOR_3},
+    };
+
+    use super::{AlgorithmGraph, Connection, GraphError};
+
+    #[test]
+    fn dx7_algorithm_1() {
+        let graph = "6 ~> 6; 6 -> 5; 5 -> 4; 4 -> 3; 3 -> out; 2 -> 1; 1 -> out"
+            .parse::<AlgorithmGraph>()
+            .unwrap();
+        let compiled = graph.compile(5).unwrap();
+        let expected = Algorithm::DX7(1).compile(5).unwrap();
+        assert_eq!(compiled.stack_size, expected.stack_size);
+        assert_eq!(compiled.carrier_output.len(), 2);
+        for (step, expected_step) in compiled
+            .execution_steps
+            .iter()
+            .zip(expected.execution_steps.iter())
+        {
+            assert_eq!(step.operator_index, expected_step.operator_index);
+            assert_eq!(step.stack_in, expected_step.stack_in);
+            assert_eq!(step.stack_out, expected_step.stack_out);
+            assert_eq!(step.feedback, expected_step.feedback);
+        }
+    }
+
+    #[test]
+    fn feedback_between_operators() {
+        let graph = AlgorithmGraph::default()
+            .modulate(OPERATOR_3, OPERATOR_2)
+            .modulate(OPERATOR_2, OPERATOR_1)
+            .feedback(OPERATOR_2, OPERATOR_3)
+            .carrier(OPERATOR_1);
+        let compiled = graph.compile(3).unwrap();
+        let feedback = compiled.execution_steps[0].feedback.unwrap();
+        assert_eq!(feedback.level, 3);
+        assert_eq!(feedback.step, ExecutionStepID::from(1));
+        assert!(graph.compile(0).unwrap().execution_steps[0]
+            .feedback
+            .is_none());
+    }
+
+    #[test]
+    fn invalid_custom_algorithm() {
+        let graph = AlgorithmGraph::default()
+            .modulate(OPERATOR_2, OPERATOR_1)
+            .carrier(OPERATOR_1)
+            .carrier(OPERATOR_1);
+        assert_eq!(
+            Algorithm::Custom(graph).compile(0).err(),
+            Some(GraphError::DuplicateCarrier(OPERATOR_1))
+        );
+    }
+
+    #[test]
+    fn more_than_six_operators() {
+        let graph = "8 -> 7; 7 -> out; 1 -> out"
+            .parse::<AlgorithmGraph>()
+            .unwrap();
+        assert_eq!(graph.compile(0).unwrap().execution_steps.len(), 3);
+    }
+
+    #[test]
+    fn invalid_graphs() {
+        assert_eq!(
+            AlgorithmGraph::default().validate(),
+            Err(GraphError::NoCarriers)
+        );
+        assert_eq!(
+            "1 -> 2; 2 -> 1; 1 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::Cycle(OPERATOR_1))
+        );
+        assert_eq!(
+            "2 -> 3; 1 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::Disconnected(OPERATOR_2))
+        );
+        assert_eq!(
+            "2 ~> 1; 3 ~> 1; 2 -> 1; 3 -> 1; 1 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::MultipleFeedback(OPERATOR_1))
+        );
+        assert_eq!(
+            "2 -> 1; 1 -> out; 1 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::DuplicateCarrier(OPERATOR_1))
+        );
+        assert_eq!(
+            "2 -> 1; 2 -> 1; 1 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::DuplicateConnection(Connection::Modulate {
+                modulator: OPERATOR_2,
+                carrier: OPERATOR_1
+            }))
+        );
+        assert_eq!(
+            "2 ~> 2; 2 ~> 2; 2 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::MultipleFeedback(OPERATOR_2))
+        );
+        assert_eq!(
+            "2 => 1".parse::<AlgorithmGraph>(),
+            Err(GraphError::InvalidConnection("2 => 1".to_string()))
+        );
+        assert_eq!(
+            "0 -> out".parse::<AlgorithmGraph>(),
+            Err(GraphError::InvalidConnection("0 -> out".to_string()))
+        );
+        assert!(AlgorithmGraph::default()
+            .modulate(OPERATOR_2, OPERATOR_3)
+            .carrier(OPERATOR_3)
+            .validate()
+            .is_ok());
+    }
+}
@@ -1,4 +1,6 @@
 mod builder;
 pub mod compiled;
 pub mod execution_step;
+pub mod graph;
+mod optimizer;
 pub mod preset;
@@ -0,0 +1,485 @@
+use audio_engine_common::{
+    digital_sound::{parameters::NoteParameters, sound::Sound},
+    envelope::Envelope,
+    waveform::state::WaveformState,
+};
+
+use crate::{
+    algorithm::{
+        compiled::{CompiledAlgorithm, StackID},
+        execution_step::{ExecutionStep, ExecutionStepID, Feedback},
+    },
+    operator::{Operator, OperatorID, Operators},
+    operator_frequency::OperatorFrequency,
+};
+
+/// Value read by an execution step or carrier.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Value {
+    /// Output of an execution step that is always zero.
+    Zero,
+    /// Output of the execution step with the given index.
+    Step(usize),
+}
+
+impl CompiledAlgorithm {
+    /// Optimize the compiled algorithm for the given operators.
+    ///
+    /// - Execution steps that always output zero are removed. This happens when their
+    ///   operator is disabled or has a level of zero.
+    /// - Operators with a fixed frequency of zero that aren't modulated never advance their
+    ///   phase, their waveform is folded to the constant value at [Operator::phase]. When
+    ///   that value is zero, for example a sine without a phase offset, the step is removed.
+    /// - Execution steps whose output isn't used are removed.
+    /// - Stack space is reused when a value isn't read from anymore.
+    ///
+    /// Folding is limited to the waveform of these operators. Folded operators with a
+    /// non-zero value are kept: their output still follows the envelope and level of the
+    /// operator, so it changes every sample and can't be folded into the steps that read
+    /// it. For the same reason operators with any other fixed frequency, or with inputs
+    /// that are all folded, aren't folded: only an output that is always zero is constant.
+    ///
+    /// The optimized algorithm produces the same output as the original algorithm, but
+    /// only as long as the operators aren't changed. Carriers that are removed read from
+    /// a stack position that is never written to, so they are still part of the average
+    /// of the carrier outputs.
+    ///
+    /// Algorithms that read from the stack before it is written to in the same sample are
+    /// returned unchanged.
+    pub fn optimize<E: Envelope>(&self, operators: &Operators<E>) -> CompiledAlgorithm {
+        let Some(inputs) = self.resolve_inputs() else {
+            return self.clone();
+        };
+        let Some(carriers) = self.resolve_carriers() else {
+            return self.clone();
+        };
+
+        let is_zero = self.find_zero_steps(operators, &inputs);
+        let carriers = carriers
+            .iter()
+            .map(|step| {
+                if is_zero[*step] {
+                    Value::Zero
+                } else {
+                    Value::Step(*step)
+                }
+            })
+            .collect::<Vec<Value>>();
+        let is_live = self.find_live_steps(&inputs, &carriers, &is_zero);
+        self.build_optimized(&inputs, &carriers, &is_live)
+    }
+
+    /// Index of the step that writes the value of each input of each step.
+    ///
+    /// Returns None when an input is read before it is written.
+    fn resolve_inputs(&self) -> Option<Vec<Vec<usize>>> {
+        self.execution_steps
+            .iter()
+            .enumerate()
+            .map(|(step_index, step)| {
+                step.stack_in
+                    .iter()
+                    .map(|stack_id| self.last_writer(*stack_id, step_index))
+                    .collect::<Option<Vec<usize>>>()
+            })
+            .collect()
+    }
+
+    /// Index of the step that writes the value of each carrier.
+    fn resolve_carriers(&self) -> Option<Vec<usize>> {
+        self.carrier_output
+            .iter()
+            .map(|stack_id| self.last_writer(*stack_id, self.execution_steps.len()))
+            .collect()
+    }
+
+    /// Last step before `before` that writes to the given stack position.
+    fn last_writer(&self, stack_id: StackID, before: usize) -> Option<usize> {
+        self.execution_steps[..before]
+            .iter()
+            .rposition(|step| writes_output(step) && step.stack_out == stack_id)
+    }
+
+    fn find_zero_steps<E: Envelope>(
+        &self,
+        operators: &Operators<E>,
+        inputs: &[Vec<usize>],
+    ) -> Vec<bool> {
+        let mut is_zero = vec![false; self.execution_steps.len()];
+        for (step_index, step) in self.execution_steps.iter().enumerate() {
+            // Steps of missing operators don't write to the stack, which stays zero.
+            let Some(operator) = operators.get_operator(step.operator_index) else {
+                is_zero[step_index] = true;
+                continue;
+            };
+            let is_modulated = inputs[step_index].iter().any(|input| !is_zero[*input])
+                || step.feedback.is_some_and(|feedback| feedback.level > 0);
+            is_zero[step_index] = writes_output(step) && is_silent(operator, is_modulated);
+        }
+        is_zero
+    }
+
+    /// Steps that contribute to the carrier output.
+    fn find_live_steps(
+        &self,
+        inputs: &[Vec<usize>],
+        carriers: &[Value],
+        is_zero: &[bool],
+    ) -> Vec<bool> {
+        let mut is_live = vec![false; self.execution_steps.len()];
+        for carrier in carriers {
+            if let Value::Step(step_index) = carrier {
+                is_live[*step_index] = true;
+            }
+        }
+        // Feedback can read from later steps, repeat until nothing changes.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (step_index, step) in self.execution_steps.iter().enumerate().rev() {
+                if !is_live[step_index] {
+                    continue;
+                }
+                let feedback_source = step.feedback.and_then(|feedback| match feedback.step {
+                    ExecutionStepID::Index(index) => Some(index as usize),
+                    ExecutionStepID::NotSet => None,
+                });
+                for source in inputs[step_index].iter().chain(feedback_source.iter()) {
+                    if !is_zero[*source] && !is_live[*source] {
+                        is_live[*source] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        is_live
+    }
+
+    fn build_optimized(
+        &self,
+        inputs: &[Vec<usize>],
+        carriers: &[Value],
+        is_live: &[bool],
+    ) -> CompiledAlgorithm {
+        let mut new_index = vec![None; self.execution_steps.len()];
+        for (index, step_index) in (0..self.execution_steps.len())
+            .filter(|step_index| is_live[*step_index])
+            .enumerate()
+        {
+            new_index[step_index] = Some(index);
+        }
+
+        // Last step reading the output of each step. Carriers are read after all steps.
+        let mut last_read = vec![None; self.execution_steps.len()];
+        for (step_index, step_inputs) in inputs.iter().enumerate() {
+            if is_live[step_index] {
+                for input in step_inputs {
+                    last_read[*input] = Some(step_index);
+                }
+            }
+        }
+        for carrier in carriers {
+            if let Value::Step(step_index) = carrier {
+                last_read[*step_index] = Some(usize::MAX);
+            }
+        }
+
+        let mut result = CompiledAlgorithm::default();
+        let mut stack_of_step = vec![StackID::NotSet; self.execution_steps.len()];
+        let mut in_use = Vec::<bool>::default();
+        for (step_index, step) in self.execution_steps.iter().enumerate() {
+            if !is_live[step_index] {
+                continue;
+            }
+            let stack_in = inputs[step_index]
+                .iter()
+                .filter(|input| is_live[**input])
+                .map(|input| stack_of_step[*input])
+                .collect::<Vec<StackID>>();
+            // Inputs are summed before the output is written, so the output can reuse
+            // the stack space of inputs that aren't read anymore.
+            for input in &inputs[step_index] {
+                if last_read[*input] == Some(step_index) {
+                    if let StackID::Index(index) = stack_of_step[*input] {
+                        in_use[index as usize] = false;
+                    }
+                }
+            }
+            let stack_out = allocate(&mut in_use);
+            stack_of_step[step_index] = stack_out;
+
+            let feedback = step.feedback.and_then(|feedback| match feedback.step {
+                ExecutionStepID::Index(index) => new_index[index as usize].map(|index| Feedback {
+                    step: ExecutionStepID::from(index as u8),
+                    ..feedback
+                }),
+                ExecutionStepID::NotSet => None,
+            });
+            result.execution_steps.push(ExecutionStep {
+                stack_in,
+                operator_index: step.operator_index,
+                stack_out,
+                feedback,
+            });
+        }
+
+        let zero_stack = StackID::from(in_use.len() as u8);
+        result.carrier_output = carriers
+            .iter()
+            .map(|carrier| match carrier {
+                Value::Zero => zero_stack,
+                Value::Step(step_index) => stack_of_step[*step_index],
+            })
+            .collect();
+        result.stack_size = if carriers.contains(&Value::Zero) {
+            in_use.len() + 1
+        } else {
+            in_use.len()
+        };
+        result
+    }
+}
+
+/// Does executing the step write its result to the stack.
+fn writes_output(step: &ExecutionStep) -> bool {
+    matches!(
+        (step.operator_index, step.stack_out),
+        (OperatorID::Index(_), StackID::Index(_))
+    )
+}
+
+/// Does the operator always output zero.
+fn is_silent<E: Envelope>(operator: &Operator<E>, is_modulated: bool) -> bool {
+    if !operator.enable || operator.level == 0.0 {
+        return true;
+    }
+    !is_modulated && constant_waveform(operator) == Some(0.0)
+}
+
+/// Value of the waveform of an operator that doesn't advance its phase.
+///
+/// Returns None when the operator has a pitch. The waveform is sampled twice, as adding a
+/// zero phase can round the start phase once.
+fn constant_waveform<E: Envelope>(operator: &Operator<E>) -> Option<f32> {
+    if !matches!(operator.frequency, OperatorFrequency::Fixed(frequency) if frequency == 0.0) {
+        return None;
+    }
+    let parameters = NoteParameters {
+        note_time: 0.0,
+        note_off: None,
+        note_pitch: 0.0,
+        gain: 1.0,
+        sample_rate: 1.0,
+    };
+    let mut state = WaveformState {
+        phase_time: operator.phase,
+    };
+    let first = operator.waveform.sample(&parameters, &mut state);
+    let second = operator.waveform.sample(&parameters, &mut state);
+    (first == second).then_some(first)
+}
+
+/// Reserve the first free stack position.
+fn allocate(in_use: &mut Vec<bool>) -> StackID {
+    let index = in_use.iter().position(|in_use| !in_use).unwrap_or_else(|| {
+        in_use.push(false);
+        in_use.len() - 1
+    });
+    in_use[index] = true;
+    StackID::from(index as u8)
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::{
+        digital_sound::parameters::NoteParameters,
+        envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease,
+        phase_time::PhaseTime, waveform::Waveform,
+    };
+
+    use crate::{
+        algorithm::{compiled::CompiledAlgorithm, preset::Algorithm},
+        operator::{Operator, Operators},
+        operator_frequency::OperatorFrequency,
+    };
+
+    type TestOperator = Operator<DelayAttackHoldDecaySustainRelease>;
+
+    fn operator(frequency: OperatorFrequency, level: f32) -> TestOperator {
+        Operator {
+            frequency,
+            level,
+            envelope: DelayAttackHoldDecaySustainRelease {
+                delay: 0.0,
+                attack: 0.01,
+                hold: 0.0,
+                decay: 0.05,
+                sustain: 0.6,
+                release: 0.05,
+            },
+            ..Operator::default()
+        }
+    }
+
+    fn render(
+        algorithm: &CompiledAlgorithm,
+        operators: &Operators<DelayAttackHoldDecaySustainRelease>,
+    ) -> Vec<u32> {
+        let mut state = algorithm.init_note_state(operators);
+        let sample_rate = 44100.0;
+        (0..4410)
+            .map(|index| {
+                let note_time = index as f32 / sample_rate;
+                let parameters = NoteParameters {
+                    note_time,
+                    note_off: Some(0.08),
+                    note_pitch: 261.63,
+                    gain: 0.8,
+                    sample_rate,
+                };
+                algorithm
+                    .sample(&parameters, operators, &mut state)
+                    .to_bits()
+            })
+            .collect()
+    }
+
+    fn test_operator_sets() -> Vec<Operators<DelayAttackHoldDecaySustainRelease>> {
+        let all_enabled = Operators::from(vec![
+            operator(OperatorFrequency::Rated(1.0), 1.0),
+            operator(OperatorFrequency::Rated(2.0), 300.0),
+            operator(OperatorFrequency::Rated(0.5), 120.0),
+            operator(OperatorFrequency::Fixed(110.0), 0.7),
+            operator(OperatorFrequency::Rated(3.0), 80.0),
+            operator(OperatorFrequency::Rated(1.01), 200.0),
+        ]);
+        let mut silenced = all_enabled.clone();
+        silenced.operators[1].enable = false;
+        silenced.operators[3].level = 0.0;
+        silenced.operators[5].frequency = OperatorFrequency::Fixed(0.0);
+        let mut carriers_silenced = all_enabled.clone();
+        carriers_silenced.operators[0].level = 0.0;
+        carriers_silenced.operators[2].enable = false;
+        carriers_silenced.operators[4].level = 0.0;
+        let four_operators = Operators::from(all_enabled.operators[..4].to_vec());
+        // Zero frequency operators with a phase offset output a constant.
+        let mut constant_phase = all_enabled.clone();
+        for index in [0, 3, 5] {
+            constant_phase.operators[index].frequency = OperatorFrequency::Fixed(0.0);
+        }
+        constant_phase.operators[0].phase = PhaseTime { time: 0.25 };
+        constant_phase.operators[3].phase = PhaseTime { time: 0.1 };
+        constant_phase.operators[5].waveform = Waveform::Saw(false);
+        constant_phase.operators[5].phase = PhaseTime { time: 0.5 };
+        vec![
+            all_enabled,
+            silenced,
+            carriers_silenced,
+            four_operators,
+            constant_phase,
+        ]
+    }
+
+    #[test]
+    fn optimized_dx7_algorithms_are_bit_identical() {
+        for operators in test_operator_sets() {
+            for algorithm in 1..=32 {
+                for feedback in [0, 7] {
+                    let compiled = Algorithm::DX7(algorithm).compile(feedback).unwrap();
+                    let optimized = compiled.optimize(&operators);
+                    assert!(optimized.execution_steps.len() <= compiled.execution_steps.len());
+                    assert!(optimized.stack_size <= compiled.stack_size + 1);
+                    assert_eq!(
+                        render(&compiled, &operators),
+                        render(&optimized, &operators),
+                        "algorithm {algorithm} feedback {feedback}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn remove_silent_steps() {
+        let mut operators = test_operator_sets().remove(0);
+        operators.operators[1].enable = false;
+        operators.operators[3].level = 0.0;
+        // Operator 2 modulates 1, operator 4 modulates 3.
+        let optimized = Algorithm::DX7(5).compile(7).unwrap().optimize(&operators);
+        assert_eq!(optimized.execution_steps.len(), 4);
+        assert_eq!(optimized.carrier_output.len(), 3);
+    }
+
+    #[test]
+    fn reuse_stack() {
+        let operators = test_operator_sets().remove(0);
+        let compiled = Algorithm::DX7(1).compile(7).unwrap();
+        let optimized = compiled.optimize(&operators);
+        assert_eq!(compiled.stack_size, 6);
+        assert_eq!(optimized.stack_size, 2);
+    }
+
+    #[test]
+    fn remove_missing_operators() {
+        let operators = Operators::from(test_operator_sets().remove(0).operators[..2].to_vec());
+        let optimized = Algorithm::DX7(32).compile(7).unwrap().optimize(&operators);
+        assert_eq!(optimized.execution_steps.len(), 2);
+        assert_eq!(optimized.carrier_output.len(), 6);
+    }
+
+    #[test]
+    fn keep_carrier_count() {
+        let mut operators = test_operator_sets().remove(0);
+        operators.operators[0].enable = false;
+        operators.operators[5].enable = false;
+        let optimized = Algorithm::DX7(32).compile(7).unwrap().optimize(&operators);
+        assert_eq!(optimized.execution_steps.len(), 4);
+        assert_eq!(optimized.carrier_output.len(), 6);
+        assert_eq!(optimized.stack_size, 5);
+    }
+
+    #[test]
+    fn unmodulated_zero_frequency_sine() {
+        let mut operators = test_operator_sets().remove(0);
+        operators.operators[5].frequency = OperatorFrequency::Fixed(0.0);
+        // Operator 6 has feedback.
+        let with_feedback = Algorithm::DX7(1).compile(7).unwrap().optimize(&operators);
+        assert_eq!(with_feedback.execution_steps.len(), 6);
+        let without_feedback = Algorithm::DX7(1).compile(0).unwrap().optimize(&operators);
+        assert_eq!(without_feedback.execution_steps.len(), 5);
+
+        operators.operators[5].waveform = Waveform::Saw(false);
+        let saw = Algorithm::DX7(1).compile(0).unwrap().optimize(&operators);
+        assert_eq!(saw.execution_steps.len(), 6);
+    }
+
+    #[test]
+    fn zero_frequency_with_phase() {
+        let mut operators = test_operator_sets().remove(0);
+        operators.operators[5].frequency = OperatorFrequency::Fixed(0.0);
+        // A sine a quarter cycle in outputs its level.
+        operators.operators[5].phase = PhaseTime { time: 0.25 };
+        let sine = Algorithm::DX7(1).compile(0).unwrap().optimize(&operators);
+        assert_eq!(sine.execution_steps.len(), 6);
+
+        // A saw halfway its cycle outputs zero.
+        operators.operators[5].waveform = Waveform::Saw(false);
+        operators.operators[5].phase = PhaseTime { time: 0.5 };
+        let saw = Algorithm::DX7(1).compile(0).unwrap().optimize(&operators);
+        assert_eq!(saw.execution_steps.len(), 5);
+    }
+
+    #[test]
+    fn zero_frequency_carrier_with_phase() {
+        let mut operators = test_operator_sets().remove(0);
+        operators.operators[0].frequency = OperatorFrequency::Fixed(0.0);
+        operators.operators[0].phase = PhaseTime { time: 0.25 };
+        // Carrier without modulators.
+        let compiled = Algorithm::DX7(32).compile(0).unwrap();
+        let optimized = compiled.optimize(&operators);
+        assert_eq!(optimized.execution_steps.len(), 6);
+        let output = render(&optimized, &operators);
+        assert!(output.iter().any(|sample| f32::from_bits(*sample) != 0.0));
+        assert_eq!(render(&compiled, &operators), output);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     algorithm::{
-        builder::{build_carrier_out, build_repeat, build_step, build_steps},
+        builder::{build_carrier_out, build_feedback, build_step, build_steps},
         compiled::CompiledAlgorithm,
     },
     operator::{
@@ -8,40 +8,40 @@ use crate::{
     },
 };
 
-pub fn compile_dx7(dx7: u8, repeat: u8) -> CompiledAlgorithm {
+pub fn compile_dx7(dx7: u8, feedback: u8) -> CompiledAlgorithm {
     let compiled_algorithm = match dx7 {
-        1 => compile_dx7_1(repeat),
-        2 => compile_dx7_2(repeat),
-        3 => compile_dx7_3(repeat),
-        4 => compile_dx7_4(repeat),
-        5 => compile_dx7_5(repeat),
-        6 => compile_dx7_6(repeat),
-        7 => compile_dx7_7(repeat),
-        8 => compile_dx7_8(repeat),
-        9 => compile_dx7_9(repeat),
-        10 => compile_dx7_10(repeat),
-        11 => compile_dx7_11(repeat),
-        12 => compile_dx7_12(repeat),
-        13 => compile_dx7_13(repeat),
-        14 => compile_dx7_14(repeat),
-        15 => compile_dx7_15(repeat),
-        16 => compile_dx7_16(repeat),
-        17 => compile_dx7_17(repeat),
-        18 => compile_dx7_18(repeat),
-        19 => compile_dx7_19(repeat),
-        20 => compile_dx7_20(repeat),
-        21 => compile_dx7_21(repeat),
-        22 => compile_dx7_22(repeat),
-        23 => compile_dx7_23(repeat),
-        24 => compile_dx7_24(repeat),
-        25 => compile_dx7_25(repeat),
-        26 => compile_dx7_26(repeat),
-        27 => compile_dx7_27(repeat),
-        28 => compile_dx7_28(repeat),
-        29 => compile_dx7_29(repeat),
-        30 => compile_dx7_30(repeat),
-        31 => compile_dx7_31(repeat),
-        32 => compile_dx7_32(repeat),
+        1 => compile_dx7_1(feedback),
+        2 => compile_dx7_2(feedback),
+        3 => compile_dx7_3(feedback),
+        4 => compile_dx7_4(feedback),
+        5 => compile_dx7_5(feedback),
+        6 => compile_dx7_6(feedback),
+        7 => compile_dx7_7(feedback),
+        8 => compile_dx7_8(feedback),
+        9 => compile_dx7_9(feedback),
+        10 => compile_dx7_10(feedback),
+        11 => compile_dx7_11(feedback),
+        12 => compile_dx7_12(feedback),
+        13 => compile_dx7_13(feedback),
+        14 => compile_dx7_14(feedback),
+        15 => compile_dx7_15(feedback),
+        16 => compile_dx7_16(feedback),
+        17 => compile_dx7_17(feedback),
+        18 => compile_dx7_18(feedback),
+        19 => compile_dx7_19(feedback),
+        20 => compile_dx7_20(feedback),
+        21 => compile_dx7_21(feedback),
+        22 => compile_dx7_22(feedback),
+        23 => compile_dx7_23(feedback),
+        24 => compile_dx7_24(feedback),
+        25 => compile_dx7_25(feedback),
+        26 => compile_dx7_26(feedback),
+        27 => compile_dx7_27(feedback),
+        28 => compile_dx7_28(feedback),
+        29 => compile_dx7_29(feedback),
+        30 => compile_dx7_30(feedback),
+        31 => compile_dx7_31(feedback),
+        32 => compile_dx7_32(feedback),
         _ => CompiledAlgorithm::default(),
     };
     debug_assert!(is_valid_dx7_algorithm(&compiled_algorithm));
@@ -85,10 +85,10 @@ fn is_valid_dx7_algorithm(compiled_algorithm: &CompiledAlgorithm) -> bool {
 }
 
 /// Compile #FM_ALGORITHM_DX7_1
-pub fn compile_dx7_1(repeat: u8) -> CompiledAlgorithm {
+pub fn compile_dx7_1(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
 
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_3 = build_steps(
         &mut result,
         vec![out_6],
@@ -101,10 +101,10 @@ pub fn compile_dx7_1(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_2
-fn compile_dx7_2(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_2(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
 
-    let out_2 = build_repeat(&mut result, &[OPERATOR_2], repeat);
+    let out_2 = build_feedback(&mut result, &[OPERATOR_2], feedback);
     let out_1 = build_step(&mut result, vec![out_2], OPERATOR_1);
     let out_3 = build_steps(
         &mut result,
@@ -117,9 +117,9 @@ fn compile_dx7_2(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_3
-fn compile_dx7_3(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_3(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_4 = build_steps(&mut result, vec![out_6], &[OPERATOR_5, OPERATOR_4]);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_3, OPERATOR_2, OPERATOR_1]);
     build_carrier_out(&mut result, vec![out_1, out_4]);
@@ -127,18 +127,18 @@ fn compile_dx7_3(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_4
-fn compile_dx7_4(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_4(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_4 = build_repeat(&mut result, &[OPERATOR_6, OPERATOR_5, OPERATOR_4], repeat);
+    let out_4 = build_feedback(&mut result, &[OPERATOR_6, OPERATOR_5, OPERATOR_4], feedback);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_3, OPERATOR_2, OPERATOR_1]);
     build_carrier_out(&mut result, vec![out_1, out_4]);
     result
 }
 
 /// Compile #FM_ALGORITHM_DX7_5
-fn compile_dx7_5(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_5(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_5 = build_step(&mut result, vec![out_6], OPERATOR_5);
     let out_3 = build_steps(&mut result, vec![], &[OPERATOR_4, OPERATOR_3]);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
@@ -147,9 +147,9 @@ fn compile_dx7_5(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_6
-fn compile_dx7_6(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_6(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_5 = build_repeat(&mut result, &[OPERATOR_6, OPERATOR_5], repeat);
+    let out_5 = build_feedback(&mut result, &[OPERATOR_6, OPERATOR_5], feedback);
     let out_3 = build_steps(&mut result, vec![], &[OPERATOR_4, OPERATOR_3]);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
     build_carrier_out(&mut result, vec![out_1, out_3, out_5]);
@@ -157,9 +157,9 @@ fn compile_dx7_6(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_7
-fn compile_dx7_7(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_7(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_5 = build_repeat(&mut result, &[OPERATOR_6, OPERATOR_5], repeat);
+    let out_5 = build_feedback(&mut result, &[OPERATOR_6, OPERATOR_5], feedback);
     let out_4 = build_step(&mut result, vec![], OPERATOR_4);
     let out_3 = build_step(&mut result, vec![out_5, out_4], OPERATOR_3);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
@@ -168,9 +168,9 @@ fn compile_dx7_7(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_8
-fn compile_dx7_8(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_8(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_4 = build_repeat(&mut result, &[OPERATOR_4], repeat);
+    let out_4 = build_feedback(&mut result, &[OPERATOR_4], feedback);
     let out_5 = build_steps(&mut result, vec![], &[OPERATOR_6, OPERATOR_5]);
     let out_3 = build_step(&mut result, vec![out_5, out_4], OPERATOR_3);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
@@ -179,9 +179,9 @@ fn compile_dx7_8(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_9
-fn compile_dx7_9(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_9(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_2 = build_repeat(&mut result, &[OPERATOR_2], repeat);
+    let out_2 = build_feedback(&mut result, &[OPERATOR_2], feedback);
     let out_1 = build_step(&mut result, vec![out_2], OPERATOR_1);
     let out_4 = build_step(&mut result, vec![], OPERATOR_4);
     let out_5 = build_steps(&mut result, vec![], &[OPERATOR_6, OPERATOR_5]);
@@ -191,9 +191,9 @@ fn compile_dx7_9(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_10
-fn compile_dx7_10(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_10(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_3 = build_repeat(&mut result, &[OPERATOR_3], repeat);
+    let out_3 = build_feedback(&mut result, &[OPERATOR_3], feedback);
     let out_1 = build_steps(&mut result, vec![out_3], &[OPERATOR_2, OPERATOR_1]);
     let out_6 = build_step(&mut result, vec![], OPERATOR_6);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
@@ -203,9 +203,9 @@ fn compile_dx7_10(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_11
-fn compile_dx7_11(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_11(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_3, OPERATOR_2, OPERATOR_1]);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
     let out_4 = build_step(&mut result, vec![out_6, out_5], OPERATOR_4);
@@ -214,9 +214,9 @@ fn compile_dx7_11(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_12
-fn compile_dx7_12(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_12(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_2 = build_repeat(&mut result, &[OPERATOR_2], repeat);
+    let out_2 = build_feedback(&mut result, &[OPERATOR_2], feedback);
     let out_1 = build_step(&mut result, vec![out_2], OPERATOR_1);
     let out_4 = build_step(&mut result, vec![], OPERATOR_4);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
@@ -227,9 +227,9 @@ fn compile_dx7_12(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_13
-fn compile_dx7_13(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_13(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
     let out_4 = build_step(&mut result, vec![], OPERATOR_4);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
@@ -239,9 +239,9 @@ fn compile_dx7_13(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_14
-fn compile_dx7_14(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_14(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
     let out_3 = build_steps(&mut result, vec![out_6, out_5], &[OPERATOR_4, OPERATOR_3]);
@@ -250,9 +250,9 @@ fn compile_dx7_14(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_15
-fn compile_dx7_15(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_15(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_2 = build_repeat(&mut result, &[OPERATOR_2], repeat);
+    let out_2 = build_feedback(&mut result, &[OPERATOR_2], feedback);
     let out_1 = build_step(&mut result, vec![out_2], OPERATOR_1);
     let out_6 = build_step(&mut result, vec![], OPERATOR_6);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
@@ -262,9 +262,9 @@ fn compile_dx7_15(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_16
-fn compile_dx7_16(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_16(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_5 = build_step(&mut result, vec![out_6], OPERATOR_5);
     let out_3 = build_steps(&mut result, vec![], &[OPERATOR_4, OPERATOR_3]);
     let out_2 = build_step(&mut result, vec![], OPERATOR_2);
@@ -274,9 +274,9 @@ fn compile_dx7_16(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_17
-fn compile_dx7_17(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_17(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_2 = build_repeat(&mut result, &[OPERATOR_2], repeat);
+    let out_2 = build_feedback(&mut result, &[OPERATOR_2], feedback);
     let out_5 = build_steps(&mut result, vec![], &[OPERATOR_6, OPERATOR_5]);
     let out_3 = build_steps(&mut result, vec![], &[OPERATOR_4, OPERATOR_3]);
     let out_1 = build_step(&mut result, vec![out_5, out_3, out_2], OPERATOR_1);
@@ -285,9 +285,9 @@ fn compile_dx7_17(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_18
-fn compile_dx7_18(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_18(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_3 = build_repeat(&mut result, &[OPERATOR_3], repeat);
+    let out_3 = build_feedback(&mut result, &[OPERATOR_3], feedback);
     let out_4 = build_steps(&mut result, vec![], &[OPERATOR_6, OPERATOR_5, OPERATOR_4]);
     let out_2 = build_step(&mut result, vec![], OPERATOR_2);
     let out_1 = build_step(&mut result, vec![out_2, out_3, out_4], OPERATOR_1);
@@ -296,9 +296,9 @@ fn compile_dx7_18(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_19
-fn compile_dx7_19(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_19(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_4 = build_step(&mut result, vec![out_6], OPERATOR_4);
     let out_5 = build_step(&mut result, vec![out_6], OPERATOR_5);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_3, OPERATOR_2, OPERATOR_1]);
@@ -307,9 +307,9 @@ fn compile_dx7_19(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_20
-fn compile_dx7_20(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_20(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_3 = build_repeat(&mut result, &[OPERATOR_3], repeat);
+    let out_3 = build_feedback(&mut result, &[OPERATOR_3], feedback);
     let out_1 = build_step(&mut result, vec![out_3], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![out_3], OPERATOR_2);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
@@ -320,9 +320,9 @@ fn compile_dx7_20(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_21
-fn compile_dx7_21(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_21(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_3 = build_repeat(&mut result, &[OPERATOR_3], repeat);
+    let out_3 = build_feedback(&mut result, &[OPERATOR_3], feedback);
     let out_1 = build_step(&mut result, vec![out_3], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![out_3], OPERATOR_2);
     let out_6 = build_step(&mut result, vec![], OPERATOR_6);
@@ -333,9 +333,9 @@ fn compile_dx7_21(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_22
-fn compile_dx7_22(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_22(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
     let out_3 = build_step(&mut result, vec![out_6], OPERATOR_3);
     let out_4 = build_step(&mut result, vec![out_6], OPERATOR_4);
@@ -345,9 +345,9 @@ fn compile_dx7_22(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_23
-fn compile_dx7_23(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_23(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_steps(&mut result, vec![], &[OPERATOR_3, OPERATOR_2]);
     let out_4 = build_step(&mut result, vec![out_6], OPERATOR_4);
@@ -357,9 +357,9 @@ fn compile_dx7_23(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_24
-fn compile_dx7_24(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_24(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![], OPERATOR_2);
     let out_3 = build_step(&mut result, vec![out_6], OPERATOR_3);
@@ -370,9 +370,9 @@ fn compile_dx7_24(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_25
-fn compile_dx7_25(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_25(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![], OPERATOR_2);
     let out_3 = build_step(&mut result, vec![], OPERATOR_3);
@@ -383,9 +383,9 @@ fn compile_dx7_25(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_26
-fn compile_dx7_26(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_26(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_steps(&mut result, vec![], &[OPERATOR_3, OPERATOR_2]);
     let out_5 = build_step(&mut result, vec![out_6], OPERATOR_5);
@@ -395,9 +395,9 @@ fn compile_dx7_26(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_27
-fn compile_dx7_27(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_27(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_3 = build_repeat(&mut result, &[OPERATOR_3], repeat);
+    let out_3 = build_feedback(&mut result, &[OPERATOR_3], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![out_3], OPERATOR_2);
     let out_6 = build_step(&mut result, vec![], OPERATOR_6);
@@ -408,9 +408,9 @@ fn compile_dx7_27(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_28
-fn compile_dx7_28(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_28(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_5 = build_repeat(&mut result, &[OPERATOR_5], repeat);
+    let out_5 = build_feedback(&mut result, &[OPERATOR_5], feedback);
     let out_1 = build_steps(&mut result, vec![], &[OPERATOR_2, OPERATOR_1]);
     let out_3 = build_steps(&mut result, vec![out_5], &[OPERATOR_4, OPERATOR_3]);
     let out_6 = build_step(&mut result, vec![], OPERATOR_6);
@@ -419,9 +419,9 @@ fn compile_dx7_28(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_29
-fn compile_dx7_29(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_29(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![], OPERATOR_2);
     let out_3 = build_steps(&mut result, vec![], &[OPERATOR_4, OPERATOR_3]);
@@ -431,9 +431,9 @@ fn compile_dx7_29(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_30
-fn compile_dx7_30(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_30(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_5 = build_repeat(&mut result, &[OPERATOR_5], repeat);
+    let out_5 = build_feedback(&mut result, &[OPERATOR_5], feedback);
     let out_1 = build_step(&mut result, vec![], OPERATOR_1);
     let out_2 = build_step(&mut result, vec![], OPERATOR_2);
     let out_3 = build_steps(&mut result, vec![out_5], &[OPERATOR_4, OPERATOR_3]);
@@ -443,9 +443,9 @@ fn compile_dx7_30(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_31
-fn compile_dx7_31(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_31(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_5 = build_step(&mut result, vec![out_6], OPERATOR_5);
     let out_4 = build_step(&mut result, vec![], OPERATOR_4);
     let out_3 = build_step(&mut result, vec![], OPERATOR_3);
@@ -456,9 +456,9 @@ fn compile_dx7_31(repeat: u8) -> CompiledAlgorithm {
 }
 
 /// Compile #FM_ALGORITHM_DX7_32
-fn compile_dx7_32(repeat: u8) -> CompiledAlgorithm {
+fn compile_dx7_32(feedback: u8) -> CompiledAlgorithm {
     let mut result = CompiledAlgorithm::default();
-    let out_6 = build_repeat(&mut result, &[OPERATOR_6], repeat);
+    let out_6 = build_feedback(&mut result, &[OPERATOR_6], feedback);
     let out_5 = build_step(&mut result, vec![], OPERATOR_5);
     let out_4 = build_step(&mut result, vec![], OPERATOR_4);
     let out_3 = build_step(&mut result, vec![], OPERATOR_3);
@@ -470,13 +470,53 @@ fn compile_dx7_32(repeat: u8) -> CompiledAlgorithm {
 
 #[cfg(test)]
 mod test {
+    use crate::{
+        algorithm::execution_step::{ExecutionStepID, Feedback},
+        operator::{OPERATOR_4, OPERATOR_6},
+    };
+
+    use super::compile_dx7;
+
     #[test]
     fn compile_dx7_algorithms() {
-        use super::compile_dx7;
         for algorithm in 1..=32 {
-            for repeat in 0..=7 {
-                compile_dx7(algorithm, repeat);
+            for feedback in 0..=7 {
+                compile_dx7(algorithm, feedback);
             }
         }
     }
+
+    #[test]
+    fn feedback_is_not_unrolled() {
+        for algorithm in 1..=32 {
+            assert_eq!(compile_dx7(algorithm, 0).execution_steps.len(), 6);
+            assert_eq!(compile_dx7(algorithm, 7).execution_steps.len(), 6);
+        }
+    }
+
+    #[test]
+    fn feedback_loop() {
+        let self_feedback = compile_dx7(1, 5);
+        assert_eq!(self_feedback.execution_steps[0].operator_index, OPERATOR_6);
+        assert_eq!(
+            self_feedback.execution_steps[0].feedback,
+            Some(Feedback {
+                level: 5,
+                step: ExecutionStepID::from(0)
+            })
+        );
+
+        let loop_feedback = compile_dx7(4, 7);
+        assert_eq!(loop_feedback.execution_steps[0].operator_index, OPERATOR_6);
+        assert_eq!(loop_feedback.execution_steps[2].operator_index, OPERATOR_4);
+        assert_eq!(
+            loop_feedback.execution_steps[0].feedback,
+            Some(Feedback {
+                level: 7,
+                step: ExecutionStepID::from(2)
+            })
+        );
+
+        assert_eq!(compile_dx7(4, 0).execution_steps[0].feedback, None);
+    }
 }
@@ -1,6 +1,9 @@
 use self::{basic::Basic, dx7::compile_dx7};
 
-use super::compiled::CompiledAlgorithm;
+use super::{
+    compiled::CompiledAlgorithm,
+    graph::{AlgorithmGraph, GraphError},
+};
 pub mod basic;
 mod dx7;
 
@@ -13,7 +16,7 @@ pub const FM_ALGORITHM_BASIC_D_MOD_ABC: Algorithm = Algorithm::Basic(Basic::DMod
 /// DX7 algorithm 1
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 5 --> 4 --> 3 --> out
 ///     2 --> 1 --> out
 /// ```
@@ -24,7 +27,7 @@ pub const FM_ALGORITHM_DX7_1: Algorithm = Algorithm::DX7(1);
 ///
 /// ```mermaid
 /// flowchart
-///     2 -->|repeat| 2
+///     2 -->|feedback| 2
 ///     2 --> 1 --> out
 ///     6 --> 5 --> 4 --> 3 --> out
 /// ```
@@ -35,7 +38,7 @@ pub const FM_ALGORITHM_DX7_2: Algorithm = Algorithm::DX7(2);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 5 --> 4 --> out
 ///     3 --> 2 --> 1 --> out
 /// ```
@@ -46,7 +49,7 @@ pub const FM_ALGORITHM_DX7_3: Algorithm = Algorithm::DX7(3);
 ///
 /// ```mermaid
 /// flowchart
-///     6 --> 5 --> 4 -->|repeat| 6
+///     6 --> 5 --> 4 -->|feedback| 6
 ///     4 --> out
 ///     3 --> 2 --> 1 --> out
 /// ```
@@ -57,7 +60,7 @@ pub const FM_ALGORITHM_DX7_4: Algorithm = Algorithm::DX7(4);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 5 --> out
 ///     4 --> 3 --> out
 ///     2 --> 1 --> out
@@ -69,7 +72,7 @@ pub const FM_ALGORITHM_DX7_5: Algorithm = Algorithm::DX7(5);
 ///
 /// ```mermaid
 /// flowchart
-///     6 --> 5 -->|repeat| 6
+///     6 --> 5 -->|feedback| 6
 ///     5 --> out
 ///     4 --> 3 --> out
 ///     2 --> 1 --> out
@@ -81,7 +84,7 @@ pub const FM_ALGORITHM_DX7_6: Algorithm = Algorithm::DX7(6);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     5 --> 3
 ///     4 --> 3 --> out
 ///     2 --> 1 --> out
@@ -93,7 +96,7 @@ pub const FM_ALGORITHM_DX7_7: Algorithm = Algorithm::DX7(7);
 ///
 /// ```mermaid
 /// flowchart
-///     4 -->|repeat| 4
+///     4 -->|feedback| 4
 ///     6 --> 5 --> 3
 ///     4 --> 3 --> out
 ///     2 --> 1 --> out
@@ -105,7 +108,7 @@ pub const FM_ALGORITHM_DX7_8: Algorithm = Algorithm::DX7(8);
 ///
 /// ```mermaid
 /// flowchart
-///     2 -->|repeat| 2
+///     2 -->|feedback| 2
 ///     2 --> 1 --> out
 ///     6 --> 5 --> 3
 ///     4 --> 3 --> out
@@ -117,7 +120,7 @@ pub const FM_ALGORITHM_DX7_9: Algorithm = Algorithm::DX7(9);
 ///
 /// ```mermaid
 /// flowchart
-///     3 -->|repeat| 3
+///     3 -->|feedback| 3
 ///     3 --> 2 --> 1 --> out
 ///     6 --> 4
 ///     5 --> 4 --> out
@@ -130,7 +133,7 @@ pub const FM_ALGORITHM_DX7_10: Algorithm = Algorithm::DX7(10);
 /// ```mermaid
 /// flowchart
 ///     3 --> 2 --> 1
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 4 --> out
 ///     5 --> 4
 /// ```
@@ -141,7 +144,7 @@ pub const FM_ALGORITHM_DX7_11: Algorithm = Algorithm::DX7(11);
 ///
 /// ```mermaid
 /// flowchart
-///     2 -->|repeat| 2
+///     2 -->|feedback| 2
 ///     2 --> 1 --> out
 ///     4 --> 3 --> out
 ///     5 --> 3
@@ -157,7 +160,7 @@ pub const FM_ALGORITHM_DX7_12: Algorithm = Algorithm::DX7(12);
 ///     2 --> 1 --> out
 ///     4 --> 3 --> out
 ///     5 --> 3
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 3
 /// ```
 pub const FM_ALGORITHM_DX7_13: Algorithm = Algorithm::DX7(13);
@@ -167,7 +170,7 @@ pub const FM_ALGORITHM_DX7_13: Algorithm = Algorithm::DX7(13);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     2 --> 1 --> out
 ///     6 --> 4 --> 3 --> out
 ///     5 --> 4
@@ -179,7 +182,7 @@ pub const FM_ALGORITHM_DX7_14: Algorithm = Algorithm::DX7(14);
 ///
 /// ```mermaid
 /// flowchart
-///     2 -->|repeat| 2
+///     2 -->|feedback| 2
 ///     2 --> 1 --> out
 ///     6 --> 4 --> 3 --> out
 ///     5 --> 4
@@ -191,7 +194,7 @@ pub const FM_ALGORITHM_DX7_15: Algorithm = Algorithm::DX7(15);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 5 --> 1
 ///     4 --> 3 --> 1
 ///     2 --> 1 --> out
@@ -203,7 +206,7 @@ pub const FM_ALGORITHM_DX7_16: Algorithm = Algorithm::DX7(16);
 ///
 /// ```mermaid
 /// flowchart
-///     2 -->|repeat| 2
+///     2 -->|feedback| 2
 ///     6 --> 5 --> 1
 ///     4 --> 3 --> 1
 ///     2 --> 1 --> out
@@ -215,7 +218,7 @@ pub const FM_ALGORITHM_DX7_17: Algorithm = Algorithm::DX7(17);
 ///
 /// ```mermaid
 /// flowchart
-///     3 -->|repeat| 3
+///     3 -->|feedback| 3
 ///     6 --> 5 --> 4 --> 1 --> out
 ///     3 --> 1
 ///     2 --> 1
@@ -227,7 +230,7 @@ pub const FM_ALGORITHM_DX7_18: Algorithm = Algorithm::DX7(18);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 4 --> out
 ///     6 --> 5 --> out
 ///     3 --> 2 --> 1 --> out
@@ -239,7 +242,7 @@ pub const FM_ALGORITHM_DX7_19: Algorithm = Algorithm::DX7(19);
 ///
 /// ```mermaid
 /// flowchart
-///     3 -->|repeat| 3
+///     3 -->|feedback| 3
 ///     3 --> 1 --> out
 ///     3 --> 2 --> out
 ///     5 --> 4 --> out
@@ -252,7 +255,7 @@ pub const FM_ALGORITHM_DX7_20: Algorithm = Algorithm::DX7(20);
 ///
 /// ```mermaid
 /// flowchart
-///     3 -->|repeat| 3
+///     3 -->|feedback| 3
 ///     3 --> 1 --> out
 ///     3 --> 2 --> out
 ///     6 --> 4 --> out
@@ -265,7 +268,7 @@ pub const FM_ALGORITHM_DX7_21: Algorithm = Algorithm::DX7(21);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     2 --> 1 --> out
 ///     6 --> 3 --> out
 ///     6 --> 4 --> out
@@ -278,7 +281,7 @@ pub const FM_ALGORITHM_DX7_22: Algorithm = Algorithm::DX7(22);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     1 --> out
 ///     3 --> 2 --> out
 ///     6 --> 4 --> out
@@ -291,7 +294,7 @@ pub const FM_ALGORITHM_DX7_23: Algorithm = Algorithm::DX7(23);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     1 --> out
 ///     2 --> out
 ///     6 --> 3 --> out
@@ -305,7 +308,7 @@ pub const FM_ALGORITHM_DX7_24: Algorithm = Algorithm::DX7(24);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     1 --> out
 ///     2 --> out
 ///     3 --> out
@@ -319,7 +322,7 @@ pub const FM_ALGORITHM_DX7_25: Algorithm = Algorithm::DX7(25);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     1 --> out
 ///     3 --> 2 --> out
 ///     5 --> 4 --> out
@@ -332,7 +335,7 @@ pub const FM_ALGORITHM_DX7_26: Algorithm = Algorithm::DX7(26);
 ///
 /// ```mermaid
 /// flowchart
-///     3 -->|repeat| 3
+///     3 -->|feedback| 3
 ///     1 --> out
 ///     3 --> 2 --> out
 ///     5 --> 4 --> out
@@ -345,7 +348,7 @@ pub const FM_ALGORITHM_DX7_27: Algorithm = Algorithm::DX7(27);
 ///
 /// ```mermaid
 /// flowchart
-///     5 -->|repeat| 5
+///     5 -->|feedback| 5
 ///     2 --> 1 --> out
 ///     5 --> 4 --> 3 --> out
 ///     6 --> out
@@ -357,7 +360,7 @@ pub const FM_ALGORITHM_DX7_28: Algorithm = Algorithm::DX7(28);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     1 --> out
 ///     2 --> out
 ///     4 --> 3 --> out
@@ -370,7 +373,7 @@ pub const FM_ALGORITHM_DX7_29: Algorithm = Algorithm::DX7(29);
 ///
 /// ```mermaid
 /// flowchart
-///     5 -->|repeat| 5
+///     5 -->|feedback| 5
 ///     1 --> out
 ///     2 --> out
 ///     5 --> 4 --> 3 --> out
@@ -383,7 +386,7 @@ pub const FM_ALGORITHM_DX7_30: Algorithm = Algorithm::DX7(30);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> 5 --> out
 ///     4 --> out
 ///     3 --> out
@@ -397,7 +400,7 @@ pub const FM_ALGORITHM_DX7_31: Algorithm = Algorithm::DX7(31);
 ///
 /// ```mermaid
 /// flowchart
-///     6 -->|repeat| 6
+///     6 -->|feedback| 6
 ///     6 --> out
 ///     5 --> out
 ///     4 --> out
@@ -407,10 +410,13 @@ pub const FM_ALGORITHM_DX7_31: Algorithm = Algorithm::DX7(31);
 /// ```
 pub const FM_ALGORITHM_DX7_32: Algorithm = Algorithm::DX7(32);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum Algorithm {
     Basic(Basic),
     DX7(u8),
+    /// User defined algorithm. Compiling fails when the graph isn't valid, see
+    /// [AlgorithmGraph::validate].
+    Custom(AlgorithmGraph),
 }
 impl Default for Algorithm {
     fn default() -> Self {
@@ -419,10 +425,11 @@ impl Default for Algorithm {
 }
 
 impl Algorithm {
-    pub fn compile(&self, repeat: u8) -> CompiledAlgorithm {
+    pub fn compile(&self, feedback: u8) -> Result<CompiledAlgorithm, GraphError> {
         match self {
-            Algorithm::Basic(basic) => basic.compile(repeat),
-            Algorithm::DX7(dx7) => compile_dx7(*dx7, repeat),
+            Algorithm::Basic(basic) => Ok(basic.compile(feedback)),
+            Algorithm::DX7(dx7) => Ok(compile_dx7(*dx7, feedback)),
+            Algorithm::Custom(graph) => graph.compile(feedback),
         }
     }
 }
@@ -1,74 +1,478 @@
 use audio_engine_common::{
     digital_sound::{parameters::NoteParameters, sound::Sound, sound_state::SoundState},
-    envelope::Envelope,
+    envelope::{multi_segment::MultiSegment, Envelope},
+    lfo::{target::LfoTarget, LfoState},
+    modulation::destination::ModulationDestination,
 };
 
 use crate::{
     algorithm::{
         compiled::{CompiledAlgorithm, CompiledAlgorithmState},
+        graph::GraphError,
         preset::Algorithm,
     },
-    operator::Operators,
+    lfo::{amplitude_modulation, FMLfo, FMLfoTarget},
+    operator::{OperatorID, Operators},
 };
 
 #[derive(Debug, Clone)]
 pub struct FMInstrument<E>
 where
-    E: Envelope + Copy + Clone,
+    E: Envelope + Clone,
 {
-    pub repeat: u8,
+    /// Feedback level (0-7) of the feedback loop of the algorithm.
+    pub feedback: u8,
     pub algorithm_preset: Algorithm,
     pub operators: Operators<E>,
     pub algorithm: Option<CompiledAlgorithm>,
+    /// Low frequency oscillators modulating the instrument.
+    pub lfos: Vec<FMLfo>,
+    /// Pitch change during the note. Levels of the envelope are in semitones.
+    pub pitch_envelope: Option<MultiSegment>,
 }
 
 impl<E> Default for FMInstrument<E>
 where
-    E: Envelope + Copy + Default,
+    E: Envelope + Clone + Default,
 {
     fn default() -> Self {
         FMInstrument::<E> {
-            repeat: 0,
+            feedback: 0,
             algorithm_preset: Algorithm::default(),
             operators: Operators::<E>::default(),
             algorithm: None,
+            lfos: Vec::default(),
+            pitch_envelope: None,
         }
     }
 }
 
 impl<E> FMInstrument<E>
 where
-    E: Envelope + Copy + Clone,
+    E: Envelope + Clone,
 {
-    pub fn compile(&mut self) {
-        self.algorithm = Some(self.algorithm_preset.compile(self.repeat));
+    /// Compile and optimize the algorithm for the current operators.
+    ///
+    /// Needs to be called again after enabling/disabling operators or changing their
+    /// level or frequency. Fails when the algorithm is a custom graph that isn't valid.
+    pub fn compile(&mut self) -> Result<(), GraphError> {
+        let algorithm = self.algorithm_preset.compile(self.feedback)?;
+        self.algorithm = Some(algorithm.optimize(&self.operators));
+        Ok(())
+    }
+
+    /// Reuse the note state of the previous note for a new note.
+    ///
+    /// Operators and lfos without key sync continue from the phase where the previous
+    /// note ended, everything else starts as with [Sound::init_sound_state].
+    pub fn restart_sound_state(&self, state: &mut FMInstrumentNoteState) {
+        let previous_lfo_states = std::mem::take(&mut state.lfo_states);
+        let mut algorithm_state = std::mem::take(&mut state.state);
+        if let Some(algorithm) = &self.algorithm {
+            algorithm.restart_note_state(&self.operators, &mut algorithm_state);
+        }
+        *state = self.init_sound_state();
+        state.state = algorithm_state;
+        for ((lfo, lfo_state), previous_lfo_state) in self
+            .lfos
+            .iter()
+            .zip(state.lfo_states.iter_mut())
+            .zip(previous_lfo_states)
+        {
+            if !lfo.key_sync {
+                *lfo_state = previous_lfo_state;
+            }
+        }
+    }
+
+    /// Reduce the level of the operators by the output of an lfo with
+    /// [FMLfoTarget::Voice], depending on their amplitude modulation sensitivity.
+    fn modulate_operator_levels(
+        &self,
+        lfo_output: f32,
+        amplitude_modulation_depth: f32,
+        level_modulation: &mut Vec<f32>,
+    ) {
+        if amplitude_modulation_depth <= 0.0 {
+            return;
+        }
+        for (index, operator) in self.operators.operators.iter().enumerate() {
+            if operator.amplitude_modulation_sensitivity > 0.0 {
+                let level = amplitude_modulation(
+                    lfo_output,
+                    amplitude_modulation_depth,
+                    operator.amplitude_modulation_sensitivity,
+                );
+                multiply_level(level_modulation, index, level);
+            }
+        }
     }
 }
 
 impl<E> Sound for FMInstrument<E>
 where
-    E: Envelope + Copy,
+    E: Envelope + Clone,
 {
     type SoundState = FMInstrumentNoteState;
     type Parameters = NoteParameters;
 
     fn init_sound_state(&self) -> Self::SoundState {
         assert!(self.algorithm.is_some(), "Algorithm should have been compiled when creating the instrument by calling #FMInstrument::compile.");
-        Self::SoundState::default()
+        let algorithm = self.algorithm.as_ref().unwrap();
+        Self::SoundState {
+            state: algorithm.init_note_state(&self.operators),
+            lfo_states: self
+                .lfos
+                .iter()
+                .map(|lfo| lfo.lfo.init_sound_state())
+                .collect(),
+            level_modulation: Vec::default(),
+        }
     }
 
     fn sample(&self, parameters: &Self::Parameters, state: &mut Self::SoundState) -> f32 {
         if let Some(program) = &self.algorithm {
-            program.sample(parameters, &self.operators, &mut state.state)
+            if self.lfos.is_empty()
+                && self.pitch_envelope.is_none()
+                && state.level_modulation.is_empty()
+            {
+                if !state.state.level_modulation.is_empty() {
+                    state.state.level_modulation.clear();
+                }
+                return program.sample(parameters, &self.operators, &mut state.state);
+            }
+
+            let mut gain = parameters.gain;
+            let level_modulation = &mut state.state.level_modulation;
+            level_modulation.clone_from(&state.level_modulation);
+            let mut note_pitch = parameters.note_pitch;
+            if let Some(pitch_envelope) = &self.pitch_envelope {
+                let semitones = pitch_envelope.level(parameters.note_time, parameters.note_off);
+                note_pitch = LfoTarget::Pitch.apply(note_pitch, semitones);
+            }
+            for (lfo, lfo_state) in self.lfos.iter().zip(state.lfo_states.iter_mut()) {
+                let modulation = lfo.lfo.sample(parameters, lfo_state);
+                match lfo.target {
+                    FMLfoTarget::Pitch => {
+                        note_pitch = LfoTarget::Pitch.apply(note_pitch, modulation);
+                    }
+                    FMLfoTarget::Gain => {
+                        gain = LfoTarget::Amplitude.apply(gain, modulation);
+                    }
+                    FMLfoTarget::OperatorLevel(OperatorID::Index(index)) => {
+                        modulate_level(level_modulation, index as usize, modulation);
+                    }
+                    FMLfoTarget::OperatorLevel(OperatorID::NotSet) => {}
+                    FMLfoTarget::Voice {
+                        pitch_modulation_depth,
+                        amplitude_modulation_depth,
+                    } => {
+                        note_pitch =
+                            LfoTarget::Pitch.apply(note_pitch, modulation * pitch_modulation_depth);
+                        self.modulate_operator_levels(
+                            modulation,
+                            amplitude_modulation_depth,
+                            level_modulation,
+                        );
+                    }
+                }
+            }
+
+            program.sample_modulated(
+                parameters,
+                &NoteParameters {
+                    note_time: parameters.note_time,
+                    note_off: parameters.note_off,
+                    note_pitch,
+                    gain,
+                    sample_rate: parameters.sample_rate,
+                },
+                &self.operators,
+                &mut state.state,
+            )
         } else {
             0.0
         }
     }
 }
 
+/// Multiply the level of the operator with the given index by the modulation.
+fn modulate_level(level_modulation: &mut Vec<f32>, index: usize, modulation: f32) {
+    let level = LfoTarget::Amplitude.apply(1.0, modulation);
+    multiply_level(level_modulation, index, level);
+}
+
+/// Multiply the level of the operator with the given index.
+fn multiply_level(level_modulation: &mut Vec<f32>, index: usize, level: f32) {
+    if level_modulation.len() <= index {
+        level_modulation.resize(index + 1, 1.0);
+    }
+    level_modulation[index] *= level;
+}
+
+impl<E> ModulationDestination for FMInstrument<E>
+where
+    E: Envelope + Clone,
+{
+    type State = FMInstrumentNoteState;
+
+    /// Operators can be modulated by their level, `operator.1.level` to `operator.N.level`.
+    /// Modulation is relative to the level of the operator.
+    fn parameter_names(&self) -> Vec<String> {
+        (1..=self.operators.len())
+            .map(|operator| format!("operator.{operator}.level"))
+            .collect()
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        state.level_modulation.clear();
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        let operator = parameter
+            .strip_prefix("operator.")
+            .and_then(|parameter| parameter.strip_suffix(".level"))
+            .and_then(|operator| operator.parse::<usize>().ok());
+        match operator {
+            Some(operator) if (1..=self.operators.len()).contains(&operator) => {
+                modulate_level(&mut state.level_modulation, operator - 1, modulation);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct FMInstrumentNoteState {
     pub state: CompiledAlgorithmState,
+    pub lfo_states: Vec<LfoState>,
+    /// Level modulation of the modulation matrix, see [ModulationDestination].
+    pub level_modulation: Vec<f32>,
 }
 
 impl SoundState for FMInstrumentNoteState {}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::{
+        digital_sound::{parameters::NoteParameters, sound::Sound},
+        envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease,
+        phase_time::PhaseTime,
+    };
+
+    use audio_engine_common::lfo::{rate::LfoRate, Lfo};
+
+    use crate::{
+        algorithm::preset::Algorithm,
+        lfo::{FMLfo, FMLfoTarget},
+        operator::{Operator, Operators},
+    };
+
+    use audio_engine_fourier::{complex_number::ComplexNumberMethods, fft::RealFft};
+
+    use super::FMInstrument;
+
+    fn test_instrument(key_sync: bool) -> FMInstrument<DelayAttackHoldDecaySustainRelease> {
+        let mut instrument = FMInstrument {
+            operators: Operators::from(vec![Operator {
+                level: 1.0,
+                phase: PhaseTime { time: 0.25 },
+                key_sync,
+                ..Operator::default()
+            }]),
+            ..FMInstrument::default()
+        };
+        instrument.compile().unwrap();
+        instrument
+    }
+
+    fn play(instrument: &FMInstrument<DelayAttackHoldDecaySustainRelease>) -> f32 {
+        let mut state = instrument.init_sound_state();
+        for index in 0..50 {
+            instrument.sample(&parameters(index as f32 / 44100.0), &mut state);
+        }
+        instrument.restart_sound_state(&mut state);
+        state.state.execution_step_state[0].waveform.phase_time.time
+    }
+
+    fn parameters(note_time: f32) -> NoteParameters {
+        NoteParameters {
+            note_time,
+            note_off: None,
+            note_pitch: 441.0,
+            gain: 1.0,
+            sample_rate: 44100.0,
+        }
+    }
+
+    #[test]
+    fn start_phase() {
+        let instrument = test_instrument(true);
+        let state = instrument.init_sound_state();
+        assert_eq!(
+            state.state.execution_step_state[0].waveform.phase_time.time,
+            0.25
+        );
+    }
+
+    #[test]
+    fn key_sync() {
+        assert_eq!(play(&test_instrument(true)), 0.25);
+        // 50 samples at 441 Hz is half a period.
+        assert!((play(&test_instrument(false)) - 0.75).abs() < 0.001);
+    }
+
+    #[test]
+    fn amplitude_modulation() {
+        let mut instrument = test_instrument(true);
+        instrument.operators.operators[0].amplitude_modulation_sensitivity = 1.0;
+        instrument.lfos.push(FMLfo {
+            lfo: Lfo {
+                depth: 1.0,
+                ..Lfo::default()
+            },
+            target: FMLfoTarget::Voice {
+                pitch_modulation_depth: 0.0,
+                amplitude_modulation_depth: 0.5,
+            },
+            key_sync: true,
+        });
+        let mut state = instrument.init_sound_state();
+        instrument.sample(&parameters(0.0), &mut state);
+        // Lfo starts at 0.0, which is halfway the amplitude modulation.
+        assert_eq!(state.state.level_modulation, vec![0.75]);
+    }
+
+    #[test]
+    fn lfo_key_sync() {
+        for key_sync in [true, false] {
+            let mut instrument = test_instrument(true);
+            instrument.lfos.push(FMLfo {
+                lfo: Lfo {
+                    rate: LfoRate::Hertz(441.0),
+                    depth: 1.0,
+                    ..Lfo::default()
+                },
+                target: FMLfoTarget::Gain,
+                key_sync,
+            });
+            let mut state = instrument.init_sound_state();
+            for index in 0..50 {
+                instrument.sample(&parameters(index as f32 / 44100.0), &mut state);
+            }
+            instrument.restart_sound_state(&mut state);
+            let phase = state.lfo_states[0].waveform.phase_time.time;
+            if key_sync {
+                assert_eq!(phase, 0.0);
+            } else {
+                // 50 samples at 441 Hz is half a period.
+                assert!((phase - 0.5).abs() < 0.001);
+            }
+        }
+    }
+
+    /// A second of a note of DX7 algorithm 32, where only operator 6 (with feedback to
+    /// itself) is audible.
+    fn render_feedback(feedback: u8) -> Vec<f32> {
+        let mut operators = Operators::<DelayAttackHoldDecaySustainRelease>::default();
+        operators.operators[5].level = 1.0;
+        let mut instrument = FMInstrument {
+            feedback,
+            algorithm_preset: Algorithm::DX7(32),
+            operators,
+            ..FMInstrument::default()
+        };
+        instrument.compile().unwrap();
+        let mut state = instrument.init_sound_state();
+        (0..44100)
+            .map(|index| instrument.sample(&parameters(index as f32 / 44100.0), &mut state))
+            .collect()
+    }
+
+    /// Amplitude of the first harmonics of a second of a note at 441Hz.
+    fn harmonics(samples: &[f32]) -> Vec<f32> {
+        let spectrum = RealFft::new(samples.len()).forward(samples);
+        (1..=4)
+            .map(|harmonic| spectrum[harmonic * 441].amplitude())
+            .collect()
+    }
+
+    #[test]
+    fn feedback_adds_harmonics() {
+        let without_feedback = harmonics(&render_feedback(0));
+        assert!(
+            without_feedback[1..]
+                .iter()
+                .all(|amplitude| *amplitude < without_feedback[0] * 0.01),
+            "{without_feedback:?}"
+        );
+
+        let samples = render_feedback(7);
+        assert!(
+            samples
+                .iter()
+                .all(|sample| sample.is_finite() && sample.abs() <= 1.0 + 1e-6),
+            "output should stay bounded"
+        );
+        let with_feedback = harmonics(&samples);
+        assert!(
+            with_feedback[1] > with_feedback[0] * 0.1 && with_feedback[2] > with_feedback[0] * 0.05,
+            "{with_feedback:?}"
+        );
+    }
+
+    /// First samples of a note with the given gain, operator 2 modulates operator 1 with
+    /// a deviation of 440Hz.
+    fn render_velocity(velocity_sensitivity: f32, gain: f32) -> Vec<f32> {
+        let mut instrument = FMInstrument {
+            algorithm_preset: Algorithm::Custom("2 -> 1\n1 -> out".parse().unwrap()),
+            operators: Operators::<DelayAttackHoldDecaySustainRelease>::from(vec![
+                Operator {
+                    level: 1.0,
+                    velocity_sensitivity,
+                    ..Operator::default()
+                },
+                Operator {
+                    level: 440.0,
+                    velocity_sensitivity,
+                    ..Operator::default()
+                },
+            ]),
+            ..FMInstrument::default()
+        };
+        instrument.compile().unwrap();
+        let mut state = instrument.init_sound_state();
+        (0..100)
+            .map(|index| {
+                instrument.sample(
+                    &NoteParameters {
+                        gain,
+                        ..parameters(index as f32 / 44100.0)
+                    },
+                    &mut state,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn carrier_follows_gain_once() {
+        // When the modulator ignores the velocity the output is linear in the gain.
+        for velocity_sensitivity in [0.0, 1.0] {
+            let loud = render_velocity(velocity_sensitivity, 1.0);
+            let soft = render_velocity(velocity_sensitivity, 0.5);
+            if velocity_sensitivity == 0.0 {
+                for (loud, soft) in loud.iter().zip(&soft) {
+                    assert!((loud * 0.5 - soft).abs() < 1e-6, "{loud} {soft}");
+                }
+            } else {
+                // The modulator follows the velocity, which changes the timbre.
+                assert!(loud
+                    .iter()
+                    .zip(&soft)
+                    .any(|(loud, soft)| (loud * 0.5 - soft).abs() > 1e-6));
+            }
+        }
+    }
+}
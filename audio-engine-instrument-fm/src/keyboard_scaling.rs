@@ -0,0 +1,97 @@
+use audio_engine_common::level::Level;
+
+/// Pitch of middle C, the default break point.
+pub const MIDDLE_C: f32 = 261.62558;
+
+/// Pitch from where rate scaling starts to shorten the envelope (A0, the lowest key of a
+/// piano).
+pub const RATE_SCALING_PITCH: f32 = 27.5;
+
+/// How the level of an operator changes when moving away from the break point.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum ScalingCurve {
+    /// Level changes by `depth` for each octave.
+    #[default]
+    Linear,
+    /// Level is multiplied by `2^depth` for each octave.
+    Exponential,
+}
+
+/// Level scaling at one side of the break point.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct LevelScaling {
+    pub curve: ScalingCurve,
+    /// Positive values increase the level, negative values decrease it.
+    pub depth: f32,
+}
+
+impl LevelScaling {
+    /// Factor to multiply the level with at the given distance in octaves.
+    pub fn factor(&self, octaves: f32) -> Level {
+        match self.curve {
+            ScalingCurve::Linear => (1.0 + self.depth * octaves).max(0.0),
+            ScalingCurve::Exponential => (self.depth * octaves).exp2(),
+        }
+    }
+}
+
+/// Change the level and envelope speed of an operator depending on the pitch of the note.
+///
+/// ```
+/// use audio_engine_instrument_fm::keyboard_scaling::*;
+///
+/// let scaling = KeyboardScaling {
+///     break_point: 440.0,
+///     right: LevelScaling {
+///         curve: ScalingCurve::Linear,
+///         depth: -0.5,
+///     },
+///     ..KeyboardScaling::default()
+/// };
+/// assert_eq!(scaling.level(220.0), 1.0);
+/// assert_eq!(scaling.level(880.0), 0.5);
+/// assert_eq!(scaling.level(1760.0), 0.0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KeyboardScaling {
+    /// Pitch where the level isn't scaled.
+    pub break_point: f32,
+    /// Scaling of notes below the break point.
+    pub left: LevelScaling,
+    /// Scaling of notes above the break point.
+    pub right: LevelScaling,
+    /// Envelope speed doubles every `1/rate` octaves above [RATE_SCALING_PITCH].
+    pub rate: f32,
+}
+
+impl Default for KeyboardScaling {
+    fn default() -> Self {
+        KeyboardScaling {
+            break_point: MIDDLE_C,
+            left: LevelScaling::default(),
+            right: LevelScaling::default(),
+            rate: 0.0,
+        }
+    }
+}
+
+impl KeyboardScaling {
+    /// Factor to multiply the level of the operator with.
+    pub fn level(&self, note_pitch: f32) -> Level {
+        let octaves = (note_pitch / self.break_point).log2();
+        if octaves < 0.0 {
+            self.left.factor(-octaves)
+        } else {
+            self.right.factor(octaves)
+        }
+    }
+
+    /// Factor to multiply the time passed to the envelope with.
+    pub fn time_scale(&self, note_pitch: f32) -> f32 {
+        if self.rate == 0.0 {
+            return 1.0;
+        }
+        let octaves = (note_pitch / RATE_SCALING_PITCH).log2().max(0.0);
+        (self.rate * octaves).exp2()
+    }
+}
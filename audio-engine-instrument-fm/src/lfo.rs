@@ -0,0 +1,48 @@
+//! Low frequency oscillators of an FM instrument.
+use audio_engine_common::{level::Level, lfo::Lfo};
+
+use crate::operator::OperatorID;
+
+/// Parameter of an FM instrument that can be modulated by an [Lfo].
+#[derive(Debug, Copy, Clone)]
+pub enum FMLfoTarget {
+    /// Pitch of the note. Depth of the lfo is in semitones.
+    Pitch,
+    /// Gain of the instrument. Depth of the lfo is relative to the gain.
+    Gain,
+    /// Level of a single operator. Depth of the lfo is relative to the level.
+    OperatorLevel(OperatorID),
+    /// Pitch of the note and the level of each operator depending on its
+    /// [crate::operator::Operator::amplitude_modulation_sensitivity], like the lfo of the
+    /// DX7. The output of the lfo (-1.0..=1.0 for a depth of 1.0) is scaled by the depths.
+    Voice {
+        /// Pitch modulation in semitones.
+        pitch_modulation_depth: f32,
+        /// Maximum reduction of the level of operators (0.0-1.0).
+        amplitude_modulation_depth: Level,
+    },
+}
+
+/// Lfo modulating a parameter of an FM instrument.
+#[derive(Debug, Copy, Clone)]
+pub struct FMLfo {
+    pub lfo: Lfo,
+    pub target: FMLfoTarget,
+    /// Restart the lfo for every note. When disabled the lfo continues from the phase
+    /// where the previous note ended.
+    pub key_sync: bool,
+}
+
+/// Level of an operator with the given sensitivity for the output of an lfo with
+/// [FMLfoTarget::Voice].
+///
+/// The level is reduced when the lfo output is high, at the lowest output the level
+/// isn't changed.
+pub fn amplitude_modulation(
+    lfo_output: f32,
+    amplitude_modulation_depth: Level,
+    sensitivity: Level,
+) -> Level {
+    let amount = ((lfo_output + 1.0) * 0.5).clamp(0.0, 1.0);
+    1.0 - amount * amplitude_modulation_depth * sensitivity
+}
@@ -1,4 +1,7 @@
 pub mod algorithm;
 pub mod instrument;
+pub mod keyboard_scaling;
+pub mod lfo;
 pub mod operator;
 pub mod operator_frequency;
+pub mod sysex;
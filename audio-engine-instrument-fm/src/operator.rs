@@ -1,10 +1,12 @@
-use crate::operator_frequency::{OperatorFrequency, RATED_1};
+use crate::{
+    keyboard_scaling::KeyboardScaling,
+    operator_frequency::{OperatorFrequency, RATED_1},
+};
 use audio_engine_common::{
     digital_sound::{parameters::NoteParameters, sound::Sound},
     envelope::Envelope,
     id::ID,
     level::Level,
-    note_time::NoteTime,
     phase_time::PhaseTime,
     waveform::{state::WaveformState, Waveform},
 };
@@ -19,7 +21,22 @@ where
     pub envelope: E,
     pub frequency: OperatorFrequency,
     pub level: Level,
+    /// Phase of the waveform at the start of the note.
     pub phase: PhaseTime,
+    /// Restart the waveform at [Operator::phase] for every note. When disabled the
+    /// waveform continues from the phase where the previous note ended.
+    pub key_sync: bool,
+    /// How much the amplitude modulation of the instrument lfo reduces the level of the
+    /// operator (0.0-1.0). See [crate::lfo::FMLfoTarget::Voice].
+    pub amplitude_modulation_sensitivity: Level,
+    /// How much the gain of the note (velocity) influences the level of the operator when
+    /// it modulates other operators, which changes the timbre. At 0.0 the level is
+    /// independent of the gain, at 1.0 the level is multiplied by the gain.
+    ///
+    /// Carriers don't use the sensitivity, the output of the algorithm is multiplied by
+    /// the gain.
+    pub velocity_sensitivity: Level,
+    pub keyboard_scaling: KeyboardScaling,
 }
 pub type OperatorID = ID;
 pub const OPERATOR_A: OperatorID = OperatorID::Index(0);
@@ -47,6 +64,10 @@ where
             frequency: RATED_1,
             level: 0.0,
             phase: PhaseTime::default(),
+            key_sync: true,
+            amplitude_modulation_sensitivity: 0.0,
+            velocity_sensitivity: 0.0,
+            keyboard_scaling: KeyboardScaling::default(),
         }
     }
 }
@@ -56,111 +77,296 @@ impl<E> Operator<E>
 where
     E: Envelope,
 {
+    /// Sample the operator.
+    ///
+    /// The pitch of the parameters can be modulated by the instrument. Velocity
+    /// sensitivity and keyboard scaling use the note as it was played: the pitch of the
+    /// key and the velocity (0.0-1.0).
     pub fn sample(
         &self,
-        note_time: NoteTime,
-        note_off: Option<NoteTime>,
-        note_pitch_base: f32,
+        parameters: &NoteParameters,
+        key_pitch: f32,
+        velocity: f32,
         note_pitch_modulator: f32,
-        sample_rate: f32,
         state: &mut OperatorNoteState,
     ) -> f32 {
         if !self.enable {
             return 0.0;
         }
-        let note_pitch = self.frequency.apply(note_pitch_base) + note_pitch_modulator;
-        // TODO: add phase when initializing the note_state
+        let note_pitch = self.frequency.apply(parameters.note_pitch) + note_pitch_modulator;
+        let time_scale = self.keyboard_scaling.time_scale(key_pitch);
+        let envelope_level = self.envelope.level(
+            parameters.note_time * time_scale,
+            parameters.note_off.map(|note_off| note_off * time_scale),
+        );
         self.waveform.sample(
             &NoteParameters {
-                note_time,
-                note_off,
+                note_time: parameters.note_time,
+                note_off: parameters.note_off,
                 note_pitch,
-                gain: self.envelope.level(note_time, note_off) * self.level,
-                sample_rate,
+                gain: envelope_level * self.level * self.scale_level(key_pitch, velocity),
+                sample_rate: parameters.sample_rate,
             },
             &mut state.waveform,
         )
     }
+
+    /// Factor to multiply the level with based on velocity and keyboard scaling.
+    fn scale_level(&self, key_pitch: f32, velocity: f32) -> Level {
+        let velocity = 1.0 - self.velocity_sensitivity * (1.0 - velocity.clamp(0.0, 1.0));
+        velocity * self.keyboard_scaling.level(key_pitch)
+    }
 }
 
+/// Operators of an instrument.
+///
+/// The number of operators isn't fixed. Algorithms that refer to operators that don't
+/// exist treat them as silent.
 #[derive(Debug, Clone)]
 pub struct Operators<E>
 where
     E: Envelope,
 {
-    pub a: Operator<E>,
-    pub b: Operator<E>,
-    pub c: Operator<E>,
-    pub d: Operator<E>,
-    pub e: Operator<E>,
-    pub f: Operator<E>,
+    pub operators: Vec<Operator<E>>,
 }
 
+/// Default number of operators, equal to the DX7.
+pub const DEFAULT_NUM_OPERATORS: usize = 6;
+
 impl<E> Operators<E>
 where
     E: Envelope,
 {
     pub fn get_operator(&self, operator_index: OperatorID) -> Option<&Operator<E>> {
         if let OperatorID::Index(index) = operator_index {
-            match index {
-                0 => Some(&self.a),
-                1 => Some(&self.b),
-                2 => Some(&self.c),
-                3 => Some(&self.d),
-                4 => Some(&self.e),
-                5 => Some(&self.f),
-                _ => None,
-            }
+            self.operators.get(index as usize)
         } else {
             None
         }
     }
+
+    pub fn get_operator_mut(&mut self, operator_index: OperatorID) -> Option<&mut Operator<E>> {
+        if let OperatorID::Index(index) = operator_index {
+            self.operators.get_mut(index as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.operators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+
+    pub fn init_note_state(&self) -> OperatorsNoteState {
+        OperatorsNoteState {
+            operators: self
+                .operators
+                .iter()
+                .map(Operator::init_note_state)
+                .collect(),
+        }
+    }
+}
+
+impl<E> Operators<E>
+where
+    E: Envelope + Default,
+{
+    /// Create the given number of default operators.
+    pub fn with_count(count: usize) -> Operators<E> {
+        Operators {
+            operators: (0..count).map(|_| Operator::default()).collect(),
+        }
+    }
+}
+
+impl<E> From<Vec<Operator<E>>> for Operators<E>
+where
+    E: Envelope,
+{
+    fn from(operators: Vec<Operator<E>>) -> Self {
+        Operators { operators }
+    }
 }
 
 impl<E> Copy for Operator<E> where E: Envelope + Copy {}
-impl<E> Copy for Operators<E> where E: Envelope + Copy {}
 
 impl<E> Default for Operators<E>
 where
     E: Envelope + Default,
 {
     fn default() -> Self {
-        Operators {
-            a: Operator::<E>::default(),
-            b: Operator::<E>::default(),
-            c: Operator::<E>::default(),
-            d: Operator::<E>::default(),
-            e: Operator::<E>::default(),
-            f: Operator::<E>::default(),
-        }
+        Operators::with_count(DEFAULT_NUM_OPERATORS)
     }
 }
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct OperatorNoteState {
     pub waveform: WaveformState,
+    /// Last two outputs of the operator, most recent first. Used for feedback.
+    pub previous_output: [f32; 2],
 }
 
 impl OperatorNoteState {
     pub fn reset(&mut self) {
-        // TODO should use create_state, as the state is getting more settings.
         self.waveform = WaveformState::default();
+        self.previous_output = [0.0; 2];
+    }
+}
+
+impl<E> Operator<E>
+where
+    E: Envelope,
+{
+    /// Note state at the start of a note.
+    pub fn init_note_state(&self) -> OperatorNoteState {
+        OperatorNoteState {
+            waveform: WaveformState {
+                phase_time: self.phase,
+            },
+            previous_output: [0.0; 2],
+        }
+    }
+
+    /// Reset the note state of the previous note for a new note.
+    pub fn restart_note_state(&self, state: &mut OperatorNoteState) {
+        let phase_time = state.waveform.phase_time;
+        *state = self.init_note_state();
+        if !self.key_sync {
+            state.waveform.phase_time = phase_time;
+        }
     }
 }
 
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Clone)]
 pub struct OperatorsNoteState {
-    pub a: OperatorNoteState,
-    pub b: OperatorNoteState,
-    pub c: OperatorNoteState,
-    pub d: OperatorNoteState,
+    pub operators: Vec<OperatorNoteState>,
 }
 
 impl OperatorsNoteState {
     pub fn reset(&mut self) {
-        self.a.reset();
-        self.b.reset();
-        self.c.reset();
-        self.d.reset();
+        self.operators.iter_mut().for_each(OperatorNoteState::reset);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::{
+        digital_sound::parameters::NoteParameters,
+        envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease,
+        phase_time::PhaseTime,
+    };
+
+    use crate::keyboard_scaling::{KeyboardScaling, LevelScaling, ScalingCurve};
+
+    use super::Operator;
+
+    /// Sine operator that starts at its peak, the first sample is the level.
+    fn operator() -> Operator<DelayAttackHoldDecaySustainRelease> {
+        Operator {
+            level: 1.0,
+            phase: PhaseTime { time: 0.25 },
+            ..Operator::default()
+        }
+    }
+
+    /// First sample of a note.
+    fn sample(
+        operator: &Operator<DelayAttackHoldDecaySustainRelease>,
+        note_time: f32,
+        note_pitch: f32,
+        key_pitch: f32,
+        velocity: f32,
+    ) -> f32 {
+        let mut state = operator.init_note_state();
+        operator.sample(
+            &NoteParameters {
+                note_time,
+                note_off: None,
+                note_pitch,
+                gain: 1.0,
+                sample_rate: 44100.0,
+            },
+            key_pitch,
+            velocity,
+            0.0,
+            &mut state,
+        )
+    }
+
+    #[test]
+    fn velocity_sensitivity() {
+        let insensitive = operator();
+        assert_eq!(sample(&insensitive, 0.0, 440.0, 440.0, 0.5), 1.0);
+        assert_eq!(sample(&insensitive, 0.0, 440.0, 440.0, 0.0), 1.0);
+
+        let sensitive = Operator {
+            velocity_sensitivity: 1.0,
+            ..operator()
+        };
+        assert_eq!(sample(&sensitive, 0.0, 440.0, 440.0, 1.0), 1.0);
+        assert_eq!(sample(&sensitive, 0.0, 440.0, 440.0, 0.5), 0.5);
+        assert_eq!(sample(&sensitive, 0.0, 440.0, 440.0, 0.0), 0.0);
+
+        let half = Operator {
+            velocity_sensitivity: 0.5,
+            ..operator()
+        };
+        assert_eq!(sample(&half, 0.0, 440.0, 440.0, 0.0), 0.5);
+    }
+
+    #[test]
+    fn keyboard_level_scaling() {
+        let operator = Operator {
+            keyboard_scaling: KeyboardScaling {
+                break_point: 440.0,
+                left: LevelScaling {
+                    curve: ScalingCurve::Exponential,
+                    depth: -1.0,
+                },
+                right: LevelScaling {
+                    curve: ScalingCurve::Linear,
+                    depth: -0.5,
+                },
+                rate: 0.0,
+            },
+            ..operator()
+        };
+        assert_eq!(sample(&operator, 0.0, 440.0, 440.0, 1.0), 1.0);
+        assert_eq!(sample(&operator, 0.0, 880.0, 880.0, 1.0), 0.5);
+        assert_eq!(sample(&operator, 0.0, 220.0, 220.0, 1.0), 0.5);
+        assert_eq!(sample(&operator, 0.0, 110.0, 110.0, 1.0), 0.25);
+        // Pitch modulation doesn't change the scaling, the key does.
+        assert_eq!(sample(&operator, 0.0, 880.0, 440.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn keyboard_rate_scaling() {
+        let envelope = DelayAttackHoldDecaySustainRelease {
+            attack: 1.0,
+            ..DelayAttackHoldDecaySustainRelease::default()
+        };
+        let unscaled = Operator {
+            envelope,
+            ..operator()
+        };
+        assert_eq!(sample(&unscaled, 0.25, 55.0, 55.0, 1.0), 0.25);
+
+        // One octave above A0 the envelope is twice as fast.
+        let scaled = Operator {
+            envelope,
+            keyboard_scaling: KeyboardScaling {
+                rate: 1.0,
+                ..KeyboardScaling::default()
+            },
+            ..operator()
+        };
+        assert_eq!(sample(&scaled, 0.25, 27.5, 27.5, 1.0), 0.25);
+        assert_eq!(sample(&scaled, 0.25, 55.0, 55.0, 1.0), 0.5);
+        assert_eq!(sample(&scaled, 0.25, 110.0, 55.0, 1.0), 0.5);
     }
 }
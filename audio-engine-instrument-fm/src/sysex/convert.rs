@@ -0,0 +1,349 @@
+//! Convert a DX7 voice to an FM instrument.
+
+use audio_engine_common::{
+    envelope::multi_segment::{Curve, MultiSegment, Segment},
+    lfo::{rate::LfoRate, Lfo},
+    waveform::Waveform,
+};
+
+use crate::{
+    algorithm::{execution_step::MAX_MODULATION_INDEX, preset::Algorithm},
+    instrument::FMInstrument,
+    keyboard_scaling::{KeyboardScaling, LevelScaling, ScalingCurve, RATE_SCALING_PITCH},
+    lfo::{FMLfo, FMLfoTarget},
+    operator::{Operator, OperatorID, Operators},
+    operator_frequency::OperatorFrequency,
+};
+
+use super::voice::{Dx7Operator, Dx7Voice};
+
+/// Pitch at which the modulation depth of modulators is matched with the DX7.
+///
+/// The DX7 uses phase modulation where the modulation index doesn't depend on the pitch.
+/// The engine adds the output of modulators to the frequency, so the depth is only
+/// correct at this pitch.
+pub const REFERENCE_PITCH: f32 = 440.0;
+
+/// Pitch change in cents of a single detune step.
+const DETUNE_CENTS: f32 = 1.0;
+
+/// Time in seconds for an envelope segment to go from level 0 to 99 at rate 99.
+const FASTEST_SEGMENT_TIME: f32 = 0.003;
+
+/// Increase of rate that halves the time of an envelope segment.
+const RATE_PER_OCTAVE: f32 = 7.2;
+
+/// Level change per octave of the linear keyboard level scaling at depth 99.
+const MAX_LINEAR_SCALING_DEPTH: f32 = 0.3;
+
+/// Level change per octave of the exponential keyboard level scaling at depth 99.
+const MAX_EXPONENTIAL_SCALING_DEPTH: f32 = 3.0;
+
+/// Envelope speed up per octave at rate scaling 7.
+const MAX_RATE_SCALING: f32 = 0.5;
+
+/// Pitch envelope level that doesn't change the pitch.
+const PITCH_LEVEL_CENTER: u8 = 50;
+
+/// Pitch change in semitones of the pitch envelope at level 99.
+const MAX_PITCH_ENVELOPE_SEMITONES: f32 = 48.0;
+
+/// Lfo frequency in Hz at speed 0.
+const SLOWEST_LFO_FREQUENCY: f32 = 0.062;
+
+/// Lfo frequency is multiplied by `e^LFO_SPEED_STEP` for each step of the speed.
+const LFO_SPEED_STEP: f32 = 0.068;
+
+/// Time in seconds of the lfo delay at 99, including fading in.
+const MAX_LFO_DELAY: f32 = 4.0;
+
+/// Pitch modulation in semitones at full depth for each pitch modulation sensitivity.
+const PITCH_MODULATION_SENSITIVITY: [f32; 8] = [0.0, 0.08, 0.14, 0.23, 0.41, 0.69, 1.38, 12.0];
+
+/// Amplitude modulation sensitivity for each sensitivity of an operator (0-3).
+const AMPLITUDE_MODULATION_SENSITIVITY: [f32; 4] = [0.0, 0.3, 0.6, 1.0];
+
+/// Lfo waveform that can't be converted.
+const LFO_WAVEFORM_SAMPLE_AND_HOLD: u8 = 5;
+
+/// Parameter of a voice that the engine can't represent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedParameter {
+    /// Operator (1-6) of the parameter or None when it is a parameter of the voice.
+    pub operator: Option<u8>,
+    /// Name of the parameter, equal to the field in [Dx7Voice] or [Dx7Operator].
+    pub parameter: &'static str,
+    pub value: u8,
+}
+
+/// Result of converting a DX7 voice.
+#[derive(Debug, Clone)]
+pub struct Dx7Patch {
+    pub name: String,
+    /// Compiled instrument.
+    pub instrument: FMInstrument<MultiSegment>,
+    /// Parameters of the voice that are ignored during conversion.
+    pub unsupported: Vec<UnsupportedParameter>,
+}
+
+impl Dx7Voice {
+    /// Convert the voice to an FM instrument.
+    pub fn to_patch(&self) -> Dx7Patch {
+        let algorithm_preset = Algorithm::DX7(self.algorithm.min(31) + 1);
+        let compiled_algorithm = algorithm_preset
+            .compile(self.feedback)
+            .expect("DX7 algorithms are valid");
+        let carriers = compiled_algorithm
+            .execution_steps
+            .iter()
+            .filter(|step| compiled_algorithm.carrier_output.contains(&step.stack_out))
+            .map(|step| step.operator_index)
+            .collect::<Vec<OperatorID>>();
+
+        let operators = self
+            .operators
+            .iter()
+            .enumerate()
+            .map(|(index, operator)| {
+                let is_carrier = carriers.contains(&OperatorID::from(index as u8));
+                let mut result = convert_operator(operator, self.transpose, is_carrier);
+                result.key_sync = self.oscillator_key_sync;
+                result
+            })
+            .collect::<Vec<Operator<MultiSegment>>>();
+
+        let mut instrument = FMInstrument {
+            feedback: self.feedback,
+            algorithm_preset,
+            operators: Operators::from(operators),
+            lfos: self.convert_lfo().into_iter().collect(),
+            pitch_envelope: self.convert_pitch_envelope(),
+            ..FMInstrument::default()
+        };
+        instrument.compile().expect("DX7 algorithms are valid");
+
+        Dx7Patch {
+            name: self.name(),
+            instrument,
+            unsupported: self.unsupported_parameters(),
+        }
+    }
+
+    /// Lfo of the voice, None when it doesn't modulate anything.
+    fn convert_lfo(&self) -> Option<FMLfo> {
+        let pitch_modulation_depth = self.lfo_pitch_modulation_depth.min(99) as f32 / 99.0
+            * PITCH_MODULATION_SENSITIVITY[self.pitch_modulation_sensitivity.min(7) as usize];
+        let amplitude_modulation_depth = self.lfo_amplitude_modulation_depth.min(99) as f32 / 99.0;
+        if pitch_modulation_depth == 0.0 && amplitude_modulation_depth == 0.0 {
+            return None;
+        }
+
+        let delay = MAX_LFO_DELAY * (self.lfo_delay.min(99) as f32 / 99.0).powi(2);
+        Some(FMLfo {
+            lfo: Lfo {
+                waveform: match self.lfo_waveform {
+                    0 => Waveform::Triangle,
+                    1 => Waveform::Saw(true),
+                    2 => Waveform::Saw(false),
+                    3 => Waveform::Square,
+                    _ => Waveform::Sine,
+                },
+                rate: LfoRate::Hertz(
+                    SLOWEST_LFO_FREQUENCY * (self.lfo_speed.min(99) as f32 * LFO_SPEED_STEP).exp(),
+                ),
+                depth: 1.0,
+                delay: delay * 0.5,
+                fade_in: delay * 0.5,
+                ..Lfo::default()
+            },
+            target: FMLfoTarget::Voice {
+                pitch_modulation_depth,
+                amplitude_modulation_depth,
+            },
+            key_sync: self.lfo_key_sync,
+        })
+    }
+
+    /// Pitch envelope of the voice, None when all levels are 50 (no pitch change).
+    ///
+    /// Follows the same shape as the operator envelopes, see [convert_envelope].
+    fn convert_pitch_envelope(&self) -> Option<MultiSegment> {
+        if self
+            .pitch_levels
+            .iter()
+            .all(|level| *level == PITCH_LEVEL_CENTER)
+        {
+            return None;
+        }
+        let mut previous_level = self.pitch_levels[3];
+        let segments = self
+            .pitch_rates
+            .iter()
+            .zip(self.pitch_levels.iter())
+            .map(|(rate, level)| {
+                let segment = Segment {
+                    time: segment_time(*rate, previous_level, *level),
+                    level: pitch_level_semitones(*level),
+                    curve: Curve::Linear,
+                };
+                previous_level = *level;
+                segment
+            })
+            .collect::<Vec<Segment>>();
+        Some(MultiSegment {
+            start: pitch_level_semitones(self.pitch_levels[3]),
+            segments,
+            sustain: Some(2),
+            loop_start: None,
+        })
+    }
+
+    fn unsupported_parameters(&self) -> Vec<UnsupportedParameter> {
+        let mut result = Vec::default();
+        let mut report = |operator: Option<u8>, parameter: &'static str, value: u8| {
+            result.push(UnsupportedParameter {
+                operator,
+                parameter,
+                value,
+            })
+        };
+
+        if self.lfo_waveform == LFO_WAVEFORM_SAMPLE_AND_HOLD {
+            report(None, "lfo_waveform", self.lfo_waveform);
+        }
+        result
+    }
+}
+
+fn convert_operator(
+    operator: &Dx7Operator,
+    transpose: u8,
+    is_carrier: bool,
+) -> Operator<MultiSegment> {
+    let frequency = convert_frequency(operator, transpose);
+    let output_level = level_amplitude(operator.output_level);
+    let level = if is_carrier {
+        output_level
+    } else {
+        output_level * MAX_MODULATION_INDEX * frequency.apply(REFERENCE_PITCH)
+    };
+    Operator {
+        envelope: convert_envelope(operator),
+        frequency,
+        level,
+        velocity_sensitivity: operator.velocity_sensitivity.min(7) as f32 / 7.0,
+        amplitude_modulation_sensitivity: AMPLITUDE_MODULATION_SENSITIVITY
+            [operator.amplitude_modulation_sensitivity.min(3) as usize],
+        keyboard_scaling: convert_keyboard_scaling(operator),
+        ..Operator::default()
+    }
+}
+
+fn convert_frequency(operator: &Dx7Operator, transpose: u8) -> OperatorFrequency {
+    let fine = operator.frequency_fine.min(99) as f32;
+    if operator.fixed_frequency {
+        let coarse = (operator.frequency_coarse % 4) as f32;
+        OperatorFrequency::Fixed(10.0_f32.powf(coarse + fine / 100.0))
+    } else {
+        let coarse = match operator.frequency_coarse {
+            0 => 0.5,
+            coarse => coarse as f32,
+        };
+        let detune = (operator.detune.min(14) as f32 - 7.0) * DETUNE_CENTS / 1200.0;
+        let transpose = (transpose.min(48) as f32 - 24.0) / 12.0;
+        OperatorFrequency::Rated(coarse * (1.0 + fine / 100.0) * (detune + transpose).exp2())
+    }
+}
+
+/// Convert keyboard level and rate scaling.
+///
+/// Break point 0 is A-1 (the lowest key of the DX7 keyboard) and 39 is middle C.
+fn convert_keyboard_scaling(operator: &Dx7Operator) -> KeyboardScaling {
+    KeyboardScaling {
+        break_point: RATE_SCALING_PITCH * (operator.break_point.min(99) as f32 / 12.0).exp2(),
+        left: convert_level_scaling(operator.left_curve, operator.left_depth),
+        right: convert_level_scaling(operator.right_curve, operator.right_depth),
+        rate: operator.rate_scaling.min(7) as f32 / 7.0 * MAX_RATE_SCALING,
+    }
+}
+
+/// Convert a curve (0: -LIN, 1: -EXP, 2: +EXP, 3: +LIN) and depth (0-99).
+fn convert_level_scaling(curve: u8, depth: u8) -> LevelScaling {
+    let depth = depth.min(99) as f32 / 99.0;
+    let (curve, depth) = match curve & 0x03 {
+        0 => (ScalingCurve::Linear, -depth * MAX_LINEAR_SCALING_DEPTH),
+        1 => (
+            ScalingCurve::Exponential,
+            -depth * MAX_EXPONENTIAL_SCALING_DEPTH,
+        ),
+        2 => (
+            ScalingCurve::Exponential,
+            depth * MAX_EXPONENTIAL_SCALING_DEPTH,
+        ),
+        _ => (ScalingCurve::Linear, depth * MAX_LINEAR_SCALING_DEPTH),
+    };
+    LevelScaling { curve, depth }
+}
+
+/// Pitch change in semitones of a pitch envelope level (0-99).
+fn pitch_level_semitones(level: u8) -> f32 {
+    (level.min(99) as f32 - PITCH_LEVEL_CENTER as f32) / 49.0 * MAX_PITCH_ENVELOPE_SEMITONES
+}
+
+/// Amplitude of an output or envelope level (0-99). Each step is 0.75 dB.
+pub fn level_amplitude(level: u8) -> f32 {
+    if level == 0 {
+        0.0
+    } else {
+        ((level.min(99) as f32 - 99.0) / 8.0).exp2()
+    }
+}
+
+/// Convert the 4 rate/4 level envelope generator.
+///
+/// The envelope starts at level 4, goes to level 1, 2 and sustains at level 3. When the
+/// note is released it goes back to level 4. Levels change linear in decibels.
+fn convert_envelope(operator: &Dx7Operator) -> MultiSegment {
+    let mut previous_level = operator.levels[3];
+    let segments = operator
+        .rates
+        .iter()
+        .zip(operator.levels.iter())
+        .map(|(rate, level)| {
+            let segment = Segment {
+                time: segment_time(*rate, previous_level, *level),
+                level: level_amplitude(*level),
+                curve: segment_curve(previous_level, *level),
+            };
+            previous_level = *level;
+            segment
+        })
+        .collect::<Vec<Segment>>();
+
+    MultiSegment {
+        start: level_amplitude(operator.levels[3]),
+        segments,
+        sustain: Some(2),
+        loop_start: None,
+    }
+}
+
+fn segment_time(rate: u8, from: u8, to: u8) -> f32 {
+    let distance = (to as f32 - from as f32).abs() / 99.0;
+    let full_time = FASTEST_SEGMENT_TIME * ((99.0 - rate.min(99) as f32) / RATE_PER_OCTAVE).exp2();
+    distance * full_time
+}
+
+fn segment_curve(from: u8, to: u8) -> Curve {
+    let min_amplitude = level_amplitude(1);
+    let from = level_amplitude(from).max(min_amplitude);
+    let to = level_amplitude(to).max(min_amplitude);
+    let steepness = (from / to).ln();
+    if steepness > f32::EPSILON {
+        Curve::Exponential(steepness)
+    } else if steepness < -f32::EPSILON {
+        Curve::Logarithmic(-steepness)
+    } else {
+        Curve::Linear
+    }
+}
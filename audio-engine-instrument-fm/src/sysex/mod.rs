@@ -0,0 +1,117 @@
+//! Import DX7 voices from SysEx messages.
+//!
+//! Both 32 voice banks and single voices are supported.
+//!
+//! ```no_run
+//! use audio_engine_instrument_fm::sysex::read_sysex_file;
+//!
+//! let voices = read_sysex_file("rom1a.syx").unwrap();
+//! for voice in voices {
+//!     let patch = voice.to_patch();
+//!     println!("{}: {} unsupported parameters", patch.name, patch.unsupported.len());
+//! }
+//! ```
+use std::{fmt::Display, path::Path};
+
+use self::voice::{Dx7Voice, PACKED_VOICE_LEN, UNPACKED_VOICE_LEN};
+
+pub mod convert;
+pub mod voice;
+
+#[cfg(test)]
+mod test;
+
+const SYSEX_START: u8 = 0xf0;
+const SYSEX_END: u8 = 0xf7;
+const YAMAHA_ID: u8 = 0x43;
+const FORMAT_SINGLE_VOICE: u8 = 0x00;
+const FORMAT_32_VOICES: u8 = 0x09;
+const HEADER_LEN: usize = 6;
+const NUM_BANK_VOICES: usize = 32;
+
+#[derive(Debug)]
+pub enum SysExError {
+    Io(std::io::Error),
+    /// Data doesn't start with a Yamaha SysEx header.
+    InvalidHeader,
+    /// SysEx message doesn't contain voice data.
+    UnsupportedFormat(u8),
+    /// Message is shorter than its byte count.
+    InvalidLength {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidChecksum,
+}
+
+impl Display for SysExError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SysExError::Io(error) => write!(f, "unable to read SysEx file: {error}"),
+            SysExError::InvalidHeader => write!(f, "not a Yamaha SysEx message"),
+            SysExError::UnsupportedFormat(format) => {
+                write!(f, "unsupported SysEx format {format:#04x}")
+            }
+            SysExError::InvalidLength { expected, actual } => {
+                write!(f, "expected {expected} bytes, got {actual}")
+            }
+            SysExError::InvalidChecksum => write!(f, "checksum mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for SysExError {}
+
+impl From<std::io::Error> for SysExError {
+    fn from(error: std::io::Error) -> Self {
+        SysExError::Io(error)
+    }
+}
+
+/// Read all voices from a SysEx file.
+pub fn read_sysex_file(path: impl AsRef<Path>) -> Result<Vec<Dx7Voice>, SysExError> {
+    parse_sysex(&std::fs::read(path)?)
+}
+
+/// Parse all voices from a SysEx message.
+pub fn parse_sysex(data: &[u8]) -> Result<Vec<Dx7Voice>, SysExError> {
+    if data.len() < HEADER_LEN || data[0] != SYSEX_START || data[1] != YAMAHA_ID {
+        return Err(SysExError::InvalidHeader);
+    }
+    let format = data[3];
+    let byte_count = ((data[4] as usize) << 7) | data[5] as usize;
+    let expected_len = match format {
+        FORMAT_SINGLE_VOICE => UNPACKED_VOICE_LEN,
+        FORMAT_32_VOICES => PACKED_VOICE_LEN * NUM_BANK_VOICES,
+        _ => return Err(SysExError::UnsupportedFormat(format)),
+    };
+    // Checksum and end of message follow the voice data.
+    let message_len = HEADER_LEN + byte_count + 2;
+    if byte_count != expected_len || data.len() < message_len {
+        return Err(SysExError::InvalidLength {
+            expected: HEADER_LEN + expected_len + 2,
+            actual: data.len(),
+        });
+    }
+    if data[message_len - 1] != SYSEX_END {
+        return Err(SysExError::InvalidHeader);
+    }
+
+    let voice_data = &data[HEADER_LEN..HEADER_LEN + byte_count];
+    let checksum = data[HEADER_LEN + byte_count];
+    let sum = voice_data
+        .iter()
+        .fold(0_u8, |sum, byte| sum.wrapping_add(*byte));
+    if (sum.wrapping_add(checksum)) & 0x7f != 0 {
+        return Err(SysExError::InvalidChecksum);
+    }
+
+    let voices = match format {
+        FORMAT_SINGLE_VOICE => vec![Dx7Voice::from_unpacked(voice_data.try_into().unwrap())],
+        _ => voice_data
+            .chunks_exact(PACKED_VOICE_LEN)
+            .map(|voice| Dx7Voice::from_packed(voice.try_into().unwrap()))
+            .collect(),
+    };
+    Ok(voices)
+}
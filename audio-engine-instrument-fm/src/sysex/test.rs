@@ -0,0 +1,217 @@
+use audio_engine_common::envelope::Envelope;
+
+use crate::{
+    algorithm::preset::Algorithm,
+    keyboard_scaling::{ScalingCurve, MIDDLE_C},
+    lfo::FMLfoTarget,
+    operator::{OPERATOR_1, OPERATOR_2},
+    operator_frequency::OperatorFrequency,
+    sysex::{
+        convert::{level_amplitude, UnsupportedParameter},
+        parse_sysex,
+        voice::{Dx7Voice, PACKED_VOICE_LEN, UNPACKED_VOICE_LEN},
+        SysExError,
+    },
+};
+
+/// Unpacked init voice with a few changes to operator 1 and 2.
+fn test_voice_unpacked() -> [u8; UNPACKED_VOICE_LEN] {
+    let mut data = [0; UNPACKED_VOICE_LEN];
+    for operator in 0..6 {
+        let operator_data = &mut data[operator * 21..(operator + 1) * 21];
+        operator_data[0..4].copy_from_slice(&[99, 99, 99, 99]);
+        operator_data[4..8].copy_from_slice(&[99, 99, 99, 0]);
+        operator_data[8] = 39;
+        operator_data[18] = 1;
+        operator_data[20] = 7;
+    }
+    // Operator 1 is stored last.
+    let operator_1 = &mut data[5 * 21..6 * 21];
+    operator_1[4..8].copy_from_slice(&[99, 90, 80, 0]);
+    operator_1[15] = 3;
+    operator_1[16] = 99;
+    operator_1[10] = 99;
+    operator_1[12] = 1;
+    operator_1[18] = 2;
+    // Operator 2
+    let operator_2 = &mut data[4 * 21..5 * 21];
+    operator_2[14] = 1;
+    operator_2[16] = 75;
+    operator_2[17] = 1;
+    operator_2[18] = 1;
+    operator_2[19] = 0;
+
+    data[126..130].copy_from_slice(&[99, 99, 99, 99]);
+    data[130..134].copy_from_slice(&[50, 50, 50, 50]);
+    data[134] = 4;
+    data[135] = 6;
+    data[136] = 1;
+    data[137] = 35;
+    data[144] = 24;
+    data[145..155].copy_from_slice(b"TEST VOICE");
+    data
+}
+
+/// Pack an unpacked voice to the format used by 32 voice banks.
+fn pack(data: &[u8; UNPACKED_VOICE_LEN]) -> [u8; PACKED_VOICE_LEN] {
+    let mut result = [0; PACKED_VOICE_LEN];
+    for operator in 0..6 {
+        let from = &data[operator * 21..];
+        let to = &mut result[operator * 17..];
+        to[0..11].copy_from_slice(&from[0..11]);
+        to[11] = from[11] | (from[12] << 2);
+        to[12] = from[13] | (from[20] << 3);
+        to[13] = from[14] | (from[15] << 2);
+        to[14] = from[16];
+        to[15] = from[17] | (from[18] << 1);
+        to[16] = from[19];
+    }
+    result[102..110].copy_from_slice(&data[126..134]);
+    result[110] = data[134];
+    result[111] = data[135] | (data[136] << 3);
+    result[112..116].copy_from_slice(&data[137..141]);
+    result[116] = data[141] | (data[142] << 1) | (data[143] << 4);
+    result[117] = data[144];
+    result[118..128].copy_from_slice(&data[145..155]);
+    result
+}
+
+fn sysex(format: u8, data: &[u8]) -> Vec<u8> {
+    let mut result = vec![0xf0, 0x43, 0x00, format];
+    result.push((data.len() >> 7) as u8);
+    result.push((data.len() & 0x7f) as u8);
+    result.extend_from_slice(data);
+    let sum = data.iter().fold(0_u8, |sum, byte| sum.wrapping_add(*byte));
+    result.push(sum.wrapping_neg() & 0x7f);
+    result.push(0xf7);
+    result
+}
+
+#[test]
+fn parse_single_voice() {
+    let voices = parse_sysex(&sysex(0x00, &test_voice_unpacked())).unwrap();
+    assert_eq!(voices.len(), 1);
+    let voice = voices[0];
+    assert_eq!(voice.name(), "TEST VOICE");
+    assert_eq!(voice.algorithm, 4);
+    assert_eq!(voice.feedback, 6);
+    assert_eq!(voice.operators[0].levels, [99, 90, 80, 0]);
+    assert_eq!(voice.operators[0].velocity_sensitivity, 3);
+    assert_eq!(voice.operators[1].output_level, 75);
+    assert!(voice.operators[1].fixed_frequency);
+    assert_eq!(voice.operators[5].output_level, 0);
+}
+
+#[test]
+fn parse_bank() {
+    let packed = pack(&test_voice_unpacked());
+    let bank = packed.repeat(32);
+    let voices = parse_sysex(&sysex(0x09, &bank)).unwrap();
+    assert_eq!(voices.len(), 32);
+    let single = parse_sysex(&sysex(0x00, &test_voice_unpacked())).unwrap();
+    assert!(voices.iter().all(|voice| *voice == single[0]));
+}
+
+#[test]
+fn parse_errors() {
+    let mut message = sysex(0x00, &test_voice_unpacked());
+    assert!(matches!(
+        parse_sysex(&message[..100]),
+        Err(SysExError::InvalidLength { .. })
+    ));
+    message[10] += 1;
+    assert!(matches!(
+        parse_sysex(&message),
+        Err(SysExError::InvalidChecksum)
+    ));
+    message[3] = 0x12;
+    assert!(matches!(
+        parse_sysex(&message),
+        Err(SysExError::UnsupportedFormat(0x12))
+    ));
+    assert!(matches!(
+        parse_sysex(&[0x00, 0x01]),
+        Err(SysExError::InvalidHeader)
+    ));
+}
+
+#[test]
+fn convert_voice() {
+    let voice = Dx7Voice::from_unpacked(&test_voice_unpacked());
+    let patch = voice.to_patch();
+    assert_eq!(patch.name, "TEST VOICE");
+    assert!(matches!(
+        patch.instrument.algorithm_preset,
+        Algorithm::DX7(5)
+    ));
+    assert_eq!(patch.instrument.feedback, 6);
+    assert!(patch.instrument.algorithm.is_some());
+
+    let operator_1 = patch.instrument.operators.get_operator(OPERATOR_1).unwrap();
+    assert!(matches!(operator_1.frequency, OperatorFrequency::Rated(ratio) if ratio == 2.0));
+    // Operator 1 is a carrier in algorithm 5.
+    assert_eq!(operator_1.level, 1.0);
+    assert_eq!(operator_1.envelope.level(0.0, None), 0.0);
+    assert_eq!(operator_1.envelope.level(10.0, None), level_amplitude(80));
+    assert_eq!(operator_1.envelope.level(20.0, Some(10.0)), 0.0);
+
+    let operator_2 = patch.instrument.operators.get_operator(OPERATOR_2).unwrap();
+    assert!(
+        matches!(operator_2.frequency, OperatorFrequency::Fixed(frequency) if frequency == 10.0)
+    );
+    // Operator 2 modulates operator 1.
+    assert_eq!(
+        operator_2.level,
+        level_amplitude(75) * 4.0 * std::f32::consts::PI * 10.0
+    );
+
+    assert_eq!(operator_1.velocity_sensitivity, 3.0 / 7.0);
+    assert!((operator_1.keyboard_scaling.break_point - MIDDLE_C).abs() < 0.01);
+    assert_eq!(
+        operator_1.keyboard_scaling.right.curve,
+        ScalingCurve::Exponential
+    );
+    assert_eq!(operator_1.keyboard_scaling.right.depth, -3.0);
+    assert_eq!(operator_1.keyboard_scaling.rate, 0.0);
+
+    assert_eq!(operator_2.amplitude_modulation_sensitivity, 0.3);
+    assert!(operator_2.key_sync);
+    assert!(patch.instrument.lfos.is_empty());
+    assert!(patch.instrument.pitch_envelope.is_none());
+    assert!(patch.unsupported.is_empty());
+}
+
+#[test]
+fn convert_lfo_and_pitch_envelope() {
+    let mut voice = Dx7Voice::from_unpacked(&test_voice_unpacked());
+    voice.oscillator_key_sync = false;
+    voice.pitch_levels = [99, 50, 50, 50];
+    voice.lfo_amplitude_modulation_depth = 99;
+    voice.lfo_waveform = 5;
+    let patch = voice.to_patch();
+
+    assert!(!patch.instrument.operators.operators[0].key_sync);
+    assert_eq!(patch.instrument.lfos.len(), 1);
+    let lfo = patch.instrument.lfos[0];
+    assert!(!lfo.key_sync);
+    assert!(matches!(
+        lfo.target,
+        FMLfoTarget::Voice {
+            pitch_modulation_depth: 0.0,
+            amplitude_modulation_depth: 1.0,
+        }
+    ));
+    let pitch_envelope = patch.instrument.pitch_envelope.unwrap();
+    assert_eq!(pitch_envelope.level(0.0, None), 0.0);
+    assert_eq!(pitch_envelope.segments[0].level, 48.0);
+    assert_eq!(pitch_envelope.level(10.0, None), 0.0);
+
+    assert_eq!(
+        patch.unsupported,
+        vec![UnsupportedParameter {
+            operator: None,
+            parameter: "lfo_waveform",
+            value: 5
+        }]
+    );
+}
@@ -0,0 +1,163 @@
+//! Parameters of a DX7 voice as stored in a SysEx message.
+
+/// Number of bytes of a voice in a 32 voice bank.
+pub const PACKED_VOICE_LEN: usize = 128;
+/// Number of bytes of a single voice.
+pub const UNPACKED_VOICE_LEN: usize = 155;
+
+/// Operator parameters of a DX7 voice. All values are stored as is.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Dx7Operator {
+    /// Envelope generator rates (0-99).
+    pub rates: [u8; 4],
+    /// Envelope generator levels (0-99).
+    pub levels: [u8; 4],
+    /// Keyboard level scaling break point (0-99), 39 is C3.
+    pub break_point: u8,
+    /// Keyboard level scaling depth left of the break point (0-99).
+    pub left_depth: u8,
+    /// Keyboard level scaling depth right of the break point (0-99).
+    pub right_depth: u8,
+    /// Keyboard level scaling curve left of the break point (0: -LIN, 1: -EXP, 2: +EXP, 3: +LIN).
+    pub left_curve: u8,
+    /// Keyboard level scaling curve right of the break point (0: -LIN, 1: -EXP, 2: +EXP, 3: +LIN).
+    pub right_curve: u8,
+    /// Keyboard rate scaling (0-7).
+    pub rate_scaling: u8,
+    /// Amplitude modulation sensitivity (0-3).
+    pub amplitude_modulation_sensitivity: u8,
+    /// Key velocity sensitivity (0-7).
+    pub velocity_sensitivity: u8,
+    /// Output level (0-99).
+    pub output_level: u8,
+    pub fixed_frequency: bool,
+    /// Coarse frequency (0-31).
+    pub frequency_coarse: u8,
+    /// Fine frequency (0-99).
+    pub frequency_fine: u8,
+    /// Detune (0-14), 7 is no detune.
+    pub detune: u8,
+}
+
+/// DX7 voice as stored in a SysEx message. All values are stored as is.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Dx7Voice {
+    /// Operators 1 to 6. Note that SysEx messages store operator 6 first.
+    pub operators: [Dx7Operator; 6],
+    /// Pitch envelope generator rates (0-99).
+    pub pitch_rates: [u8; 4],
+    /// Pitch envelope generator levels (0-99), 50 is no pitch change.
+    pub pitch_levels: [u8; 4],
+    /// Algorithm (0-31), 0 is algorithm 1.
+    pub algorithm: u8,
+    /// Feedback (0-7).
+    pub feedback: u8,
+    pub oscillator_key_sync: bool,
+    /// Lfo speed (0-99).
+    pub lfo_speed: u8,
+    /// Lfo delay (0-99).
+    pub lfo_delay: u8,
+    /// Lfo pitch modulation depth (0-99).
+    pub lfo_pitch_modulation_depth: u8,
+    /// Lfo amplitude modulation depth (0-99).
+    pub lfo_amplitude_modulation_depth: u8,
+    pub lfo_key_sync: bool,
+    /// Lfo waveform (0: triangle, 1: saw down, 2: saw up, 3: square, 4: sine, 5: sample and hold).
+    pub lfo_waveform: u8,
+    /// Pitch modulation sensitivity (0-7).
+    pub pitch_modulation_sensitivity: u8,
+    /// Transpose (0-48), 24 is C3.
+    pub transpose: u8,
+    pub name: [u8; 10],
+}
+
+impl Dx7Voice {
+    /// Name of the voice with trailing spaces removed.
+    pub fn name(&self) -> String {
+        self.name
+            .iter()
+            .map(|byte| match byte {
+                0x20..=0x7e => *byte as char,
+                _ => ' ',
+            })
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    /// Read a voice from the packed format used in 32 voice banks.
+    pub fn from_packed(data: &[u8; PACKED_VOICE_LEN]) -> Dx7Voice {
+        let mut voice = Dx7Voice::default();
+        for (operator_index, operator) in voice.operators.iter_mut().rev().enumerate() {
+            let data = &data[operator_index * 17..];
+            operator.rates.copy_from_slice(&data[0..4]);
+            operator.levels.copy_from_slice(&data[4..8]);
+            operator.break_point = data[8];
+            operator.left_depth = data[9];
+            operator.right_depth = data[10];
+            operator.left_curve = data[11] & 0x03;
+            operator.right_curve = (data[11] >> 2) & 0x03;
+            operator.rate_scaling = data[12] & 0x07;
+            operator.detune = (data[12] >> 3) & 0x0f;
+            operator.amplitude_modulation_sensitivity = data[13] & 0x03;
+            operator.velocity_sensitivity = (data[13] >> 2) & 0x07;
+            operator.output_level = data[14];
+            operator.fixed_frequency = data[15] & 0x01 != 0;
+            operator.frequency_coarse = (data[15] >> 1) & 0x1f;
+            operator.frequency_fine = data[16];
+        }
+        voice.pitch_rates.copy_from_slice(&data[102..106]);
+        voice.pitch_levels.copy_from_slice(&data[106..110]);
+        voice.algorithm = data[110] & 0x1f;
+        voice.feedback = data[111] & 0x07;
+        voice.oscillator_key_sync = data[111] & 0x08 != 0;
+        voice.lfo_speed = data[112];
+        voice.lfo_delay = data[113];
+        voice.lfo_pitch_modulation_depth = data[114];
+        voice.lfo_amplitude_modulation_depth = data[115];
+        voice.lfo_key_sync = data[116] & 0x01 != 0;
+        voice.lfo_waveform = (data[116] >> 1) & 0x07;
+        voice.pitch_modulation_sensitivity = (data[116] >> 4) & 0x07;
+        voice.transpose = data[117];
+        voice.name.copy_from_slice(&data[118..128]);
+        voice
+    }
+
+    /// Read a voice from the unpacked format used for single voices.
+    pub fn from_unpacked(data: &[u8; UNPACKED_VOICE_LEN]) -> Dx7Voice {
+        let mut voice = Dx7Voice::default();
+        for (operator_index, operator) in voice.operators.iter_mut().rev().enumerate() {
+            let data = &data[operator_index * 21..];
+            operator.rates.copy_from_slice(&data[0..4]);
+            operator.levels.copy_from_slice(&data[4..8]);
+            operator.break_point = data[8];
+            operator.left_depth = data[9];
+            operator.right_depth = data[10];
+            operator.left_curve = data[11];
+            operator.right_curve = data[12];
+            operator.rate_scaling = data[13];
+            operator.amplitude_modulation_sensitivity = data[14];
+            operator.velocity_sensitivity = data[15];
+            operator.output_level = data[16];
+            operator.fixed_frequency = data[17] != 0;
+            operator.frequency_coarse = data[18];
+            operator.frequency_fine = data[19];
+            operator.detune = data[20];
+        }
+        voice.pitch_rates.copy_from_slice(&data[126..130]);
+        voice.pitch_levels.copy_from_slice(&data[130..134]);
+        voice.algorithm = data[134];
+        voice.feedback = data[135];
+        voice.oscillator_key_sync = data[136] != 0;
+        voice.lfo_speed = data[137];
+        voice.lfo_delay = data[138];
+        voice.lfo_pitch_modulation_depth = data[139];
+        voice.lfo_amplitude_modulation_depth = data[140];
+        voice.lfo_key_sync = data[141] != 0;
+        voice.lfo_waveform = data[142];
+        voice.pitch_modulation_sensitivity = data[143];
+        voice.transpose = data[144];
+        voice.name.copy_from_slice(&data[145..155]);
+        voice
+    }
+}
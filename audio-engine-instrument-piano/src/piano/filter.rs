@@ -1,5 +1,7 @@
 use std::f32::consts::{PI, TAU};
 
+use audio_engine_discrete_time::biquad::{Biquad, BiquadType};
+
 #[derive(Debug, Clone, Default)]
 pub struct Filter {
     pub x: Vec<f32>,
@@ -149,51 +151,12 @@ fn complex_divide(hn: [f32; 2], hd: [f32; 2]) -> [f32; 2] {
     [mag * arg.cos(), mag * arg.sin()]
 }
 
-pub enum BiquadType {
-    Pass,
-    Low,
-    High,
-    Notch,
-}
-
 pub fn biquad(f0: f32, sample_rate: f32, q: f32, biquad_type: BiquadType, c: &mut Filter) {
+    let coefficients =
+        Biquad::new(biquad_type, f0 as f64, q as f64).coefficients(sample_rate as f64);
     c.x = vec![0.0; 3];
     c.y = vec![0.0; 3];
-    c.a = vec![0.0; 3];
-    c.b = vec![0.0; 3];
-
-    let a = 1.0 / (2.0 * (PI * f0 / sample_rate).tan());
-    let a2 = a * a;
-    let aoq = a / q;
-    let d = 4.0 * a2 + 2.0 * aoq + 1.0;
-
-    // TODO: init inline
-    c.a[0] = 1.0;
-    c.a[1] = -(8.0 * a2 - 2.0) / d;
-    c.a[2] = (4.0 * a2 - 2.0 * aoq + 1.0) / d;
-
-    match biquad_type {
-        BiquadType::Pass => {
-            c.b[0] = 2.0 * aoq / d;
-            c.b[1] = 0.0;
-            c.b[2] = -2.0 * aoq / d;
-        }
-        BiquadType::Low => {
-            c.b[0] = 1.0 / d;
-            c.b[1] = 2.0 / d;
-            c.b[2] = 1.0 / d;
-        }
-        BiquadType::High => {
-            c.b[0] = 4.0 * a2 / d;
-            c.b[1] = -8.0 * a2 / d;
-            c.b[2] = 4.0 * a2 / d;
-        }
-        BiquadType::Notch => {
-            c.b[0] = (1.0 + 4.0 * a2) / d;
-            c.b[1] = (2.0 - 8.0 * a2) / d;
-            c.b[2] = (1.0 + 4.0 * a2) / d;
-        }
-    }
-
+    c.a = coefficients.a.map(|a| a as f32).to_vec();
+    c.b = coefficients.b.map(|b| b as f32).to_vec();
     c.n = 2;
 }
@@ -8,8 +8,10 @@ mod test;
 
 use std::f32::consts::PI;
 
+use audio_engine_discrete_time::biquad::BiquadType;
+
 use self::{
-    filter::{biquad, BiquadType, Filter},
+    filter::{biquad, Filter},
     hammer::Hammer,
     reverb::Reverb,
     string::PianoString,
@@ -114,10 +116,16 @@ impl Piano {
             200.0,
             sample_rate,
             1.0,
-            BiquadType::High,
+            BiquadType::HighPass,
             &mut self.shaping2,
         );
-        biquad(800.0, sample_rate, 1.0, BiquadType::Low, &mut self.shaping3);
+        biquad(
+            800.0,
+            sample_rate,
+            1.0,
+            BiquadType::LowPass,
+            &mut self.shaping3,
+        );
     }
 
     pub fn go(&mut self, samples_out: &mut [f32]) {
@@ -1,9 +1,11 @@
 //! Port of the Piano model implemented by Lorenzoncina
 
-use std::f64::consts::TAU;
+use std::{f64::consts::TAU, sync::Arc};
 
-use audio_engine_common::convolve::Convolution;
 use audio_engine_discrete_time::transfer_function::TransferFunction;
+use audio_engine_effect_convolution::partitioned_convolution::{
+    PartitionedConvolution, PartitionedImpulseResponse,
+};
 
 use crate::piano2::piano_string::PianoString;
 
@@ -20,6 +22,8 @@ mod test;
 
 /// Input length of the precalculated forces, before switching to wave guiding.
 const INPUT_LENGTH: usize = 150;
+/// Partition size of the soundboard convolution.
+const SOUNDBOARD_BLOCK_SIZE: usize = 128;
 
 #[derive(Debug, Default, Clone)]
 pub struct Piano {
@@ -49,6 +53,8 @@ pub struct Piano {
     pub epsilon: f64,
 
     pub string_configurations: StringGroupConfigurations,
+    /// Impulse response of the soundboard, shared by the notes.
+    pub soundboard: Option<Arc<PartitionedImpulseResponse>>,
     pub note: PianoNote,
 }
 
@@ -71,6 +77,13 @@ impl Piano {
             damping_coefficient_a: 0.5,
             damping_coefficient_b: 6.25e-9,
             epsilon: 3.82e-5,
+            soundboard: Some(Arc::new(PartitionedImpulseResponse::new(
+                &PIANO_IR_SAMPLES
+                    .iter()
+                    .map(|sample| *sample as f32)
+                    .collect::<Vec<f32>>(),
+                SOUNDBOARD_BLOCK_SIZE,
+            ))),
             ..Piano::default()
         };
 
@@ -204,11 +217,7 @@ impl Piano {
 
     fn init_velocity(&mut self, forces: &[f64]) -> Vec<f64> {
         let r0 = (self.string_tension * self.string_mass / self.string_length).sqrt();
-        let velocities = forces
-            .iter()
-            .map(|force| force / (2.0 * r0))
-            .collect::<Vec<f64>>();
-        PIANO_IR_SAMPLES.convolve(&velocities)
+        forces.iter().map(|force| force / (2.0 * r0)).collect()
     }
     // #endregion
 
@@ -259,6 +268,7 @@ impl Piano {
         self.note.strings.push(PianoString::from(dw3));
         let forces = self.init_force_out(hammer_velocity);
         self.note.input_velocities = self.init_velocity(&forces);
+        self.note.soundboard = self.soundboard.clone().map(PartitionedConvolution::new);
         self.note.sample_index = 0;
     }
     // #endregion
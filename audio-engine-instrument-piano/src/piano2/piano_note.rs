@@ -1,3 +1,5 @@
+use audio_engine_effect_convolution::partitioned_convolution::PartitionedConvolution;
+
 use super::PianoString;
 
 #[derive(Debug, Default, Clone)]
@@ -6,15 +8,28 @@ pub struct PianoNote {
     pub sample_index: usize,
     /// Hammer input velocity over time.
     pub input_velocities: Vec<f64>,
+    /// Convolves the input velocities with the impulse response of the soundboard.
+    pub soundboard: Option<PartitionedConvolution>,
 }
 
 impl PianoNote {
     pub fn sample(&mut self) -> f64 {
-        let sample_in = if self.sample_index < self.input_velocities.len() {
+        let velocity = if self.sample_index < self.input_velocities.len() {
             self.input_velocities[self.sample_index]
         } else {
             0.0
         };
+        let sample_in = match &mut self.soundboard {
+            // The soundboard only has output while it is still ringing.
+            Some(soundboard)
+                if self.sample_index
+                    < self.input_velocities.len() + soundboard.impulse_response().len() =>
+            {
+                soundboard.process(velocity as f32) as f64
+            }
+            Some(_) => 0.0,
+            None => velocity,
+        };
         let sample_out = self.filter(sample_in);
         self.sample_index += 1;
         sample_out / 1000.0
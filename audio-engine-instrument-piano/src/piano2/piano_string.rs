@@ -1,8 +1,8 @@
-use audio_engine_discrete_time::filter::Filter;
+use audio_engine_discrete_time::{direct_form::DirectFormFilter, filter::Filter};
 
 #[derive(Debug, Clone)]
 pub struct PianoString {
-    filter: Filter<f64>,
+    filter: DirectFormFilter<f64>,
 }
 
 impl PianoString {
@@ -17,7 +17,7 @@ where
 {
     fn from(value: F) -> Self {
         PianoString {
-            filter: value.into(),
+            filter: DirectFormFilter::from(&value.into()),
         }
     }
 }
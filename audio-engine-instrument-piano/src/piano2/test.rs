@@ -1,4 +1,4 @@
-use super::Piano;
+use super::{piano_ir::PIANO_IR_SAMPLES, Piano};
 
 #[test]
 fn test_52326hz_10ms() {
@@ -29,3 +29,33 @@ fn test_52325hz_10ms() {
     }
     println!("{m}");
 }
+
+/// The soundboard is convolved causally: the note sounds like strings excited by the full
+/// convolution of the hammer velocities with the soundboard impulse response.
+#[test]
+fn soundboard_is_causal() {
+    let mut piano = Piano::new(44100.0);
+    piano.start_note(440.0, 4.0);
+    let mut reference = piano.note.clone();
+    reference.soundboard = None;
+    reference.input_velocities =
+        vec![0.0; piano.note.input_velocities.len() + PIANO_IR_SAMPLES.len() - 1];
+    for (index, velocity) in piano.note.input_velocities.iter().enumerate() {
+        for (delay, factor) in PIANO_IR_SAMPLES.iter().enumerate() {
+            reference.input_velocities[index + delay] += velocity * *factor as f32 as f64;
+        }
+    }
+
+    let expected = (0..reference.input_velocities.len())
+        .map(|_| reference.sample())
+        .collect::<Vec<f64>>();
+    let max = expected
+        .iter()
+        .fold(0.0_f64, |max, sample| max.max(sample.abs()));
+    assert!(max > 0.0);
+    let mut error = 0.0_f64;
+    for expected in &expected {
+        error = error.max((piano.sample() - expected).abs());
+    }
+    assert!(error < 1e-5 * max, "{error} of {max}");
+}
@@ -1,10 +1,15 @@
 use audio_engine_common::digital_sound::{parameters::NoteParameters, sound::Sound};
+use audio_engine_fourier::phase_vocoder::{pitch_shift, time_stretch, PhaseVocoderParameters};
 use audio_engine_notes::{ChromaticNote, ChromaticTone};
 
 use crate::sample_note_state::SampleNoteState;
 
+/// Sample data with the positions to play.
+///
+/// The data is borrowed for samples that are compiled into the binary, samples created at
+/// runtime (e.g. by [Sample::time_stretch]) own their data.
 #[derive(Debug, Copy, Clone)]
-pub struct Sample {
+pub struct Sample<D = &'static [f32]> {
     pub start: usize,
     pub end: usize,
 
@@ -14,10 +19,59 @@ pub struct Sample {
 
     pub sample_rate_c4: f32,
 
-    pub data: &'static [f32],
+    pub data: D,
 }
 
-impl Sound for Sample {
+impl<D> Sample<D>
+where
+    D: AsRef<[f32]>,
+{
+    /// Change the duration of the sample without changing the pitch, see [time_stretch].
+    ///
+    /// Start, end and the loop points are moved with the stretched data.
+    pub fn time_stretch(
+        &self,
+        stretch: f32,
+        parameters: PhaseVocoderParameters,
+    ) -> Sample<Vec<f32>> {
+        let data = time_stretch(self.data.as_ref(), stretch, parameters);
+        let len = data.len();
+        let position = |position: usize| ((position as f32 * stretch).round() as usize).min(len);
+        Sample {
+            start: position(self.start),
+            end: position(self.end),
+            is_looped: self.is_looped,
+            loop_start: position(self.loop_start),
+            loop_end: position(self.loop_end),
+            sample_rate_c4: self.sample_rate_c4,
+            data,
+        }
+    }
+
+    /// Change the pitch of the sample without changing the duration, see [pitch_shift].
+    ///
+    /// Start, end and the loop points stay at the same position.
+    pub fn pitch_shift(
+        &self,
+        pitch_ratio: f32,
+        parameters: PhaseVocoderParameters,
+    ) -> Sample<Vec<f32>> {
+        Sample {
+            start: self.start,
+            end: self.end,
+            is_looped: self.is_looped,
+            loop_start: self.loop_start,
+            loop_end: self.loop_end,
+            sample_rate_c4: self.sample_rate_c4,
+            data: pitch_shift(self.data.as_ref(), pitch_ratio, parameters),
+        }
+    }
+}
+
+impl<D> Sound for Sample<D>
+where
+    D: AsRef<[f32]>,
+{
     type SoundState = SampleNoteState;
     type Parameters = NoteParameters;
 
@@ -34,7 +88,7 @@ impl Sound for Sample {
             return 0.0;
         }
 
-        let result = self.data[sample_offset];
+        let result = self.data.as_ref()[sample_offset];
 
         let is_note_released = match parameters.note_off {
             Some(note_off) => parameters.note_time > note_off,
@@ -63,3 +117,98 @@ impl Sound for Sample {
         result
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::TAU;
+
+    use audio_engine_analysis::pitch::{detect_pitch, PitchParameters};
+    use audio_engine_common::digital_sound::{parameters::NoteParameters, sound::Sound};
+    use audio_engine_fourier::phase_vocoder::PhaseVocoderParameters;
+    use audio_engine_notes::{ChromaticNote, ChromaticTone};
+
+    use super::Sample;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn sample() -> Sample<Vec<f32>> {
+        Sample {
+            start: 100,
+            end: 22050,
+            is_looped: true,
+            loop_start: 10000,
+            loop_end: 20000,
+            sample_rate_c4: SAMPLE_RATE,
+            data: (0..22050)
+                .map(|index| (TAU * 440.0 * index as f32 / SAMPLE_RATE).sin())
+                .collect(),
+        }
+    }
+
+    /// Samples played before the note is finished, without note off.
+    fn play(sample: &Sample<Vec<f32>>) -> Vec<f32> {
+        let sample = Sample {
+            is_looped: false,
+            ..sample.clone()
+        };
+        let mut state = sample.init_sound_state();
+        let mut result = Vec::default();
+        while !state.is_finished {
+            let value = sample.sample(
+                &NoteParameters {
+                    note_time: result.len() as f32 / SAMPLE_RATE,
+                    note_off: None,
+                    note_pitch: ChromaticNote {
+                        tone: ChromaticTone::C,
+                        octave: 4,
+                    }
+                    .pitch(),
+                    gain: 1.0,
+                    sample_rate: SAMPLE_RATE,
+                },
+                &mut state,
+            );
+            result.push(value);
+        }
+        result
+    }
+
+    #[test]
+    fn time_stretch() {
+        let original = sample();
+        let stretched = original.time_stretch(2.0, PhaseVocoderParameters::default());
+        assert_eq!(stretched.data.len(), 44100);
+        assert_eq!(stretched.start, 200);
+        assert_eq!(stretched.end, 44100);
+        assert_eq!(stretched.loop_start, 20000);
+        assert_eq!(stretched.loop_end, 40000);
+        assert!(stretched.is_looped);
+        assert_eq!(stretched.sample_rate_c4, original.sample_rate_c4);
+        assert_eq!(play(&stretched).len(), 2 * play(&original).len());
+    }
+
+    #[test]
+    fn pitch_shift() {
+        let original = sample();
+        let shifted = original.pitch_shift(2.0, PhaseVocoderParameters::default());
+        assert_eq!(shifted.data.len(), original.data.len());
+        assert_eq!(shifted.start, original.start);
+        assert_eq!(shifted.end, original.end);
+        assert_eq!(shifted.loop_start, original.loop_start);
+        assert_eq!(shifted.loop_end, original.loop_end);
+        let played = play(&shifted);
+        assert_eq!(played.len(), play(&original).len());
+
+        let estimate = detect_pitch(
+            &played[4096..8192],
+            SAMPLE_RATE,
+            &PitchParameters::default(),
+        )
+        .expect("shifted sample should have a pitch");
+        assert!(
+            (estimate.frequency - 880.0).abs() < 880.0 * 0.01,
+            "{}",
+            estimate.frequency
+        );
+    }
+}
@@ -19,27 +19,24 @@ pub mod triangle;
 
 fn create_fm_waveform_instrument(waveform: Waveform) -> Instrument {
     let mut instrument = FMInstrument::<DelayAttackHoldDecaySustainRelease> {
-        operators: Operators::<DelayAttackHoldDecaySustainRelease> {
-            a: Operator {
-                waveform,
-                frequency: RATED_1,
-                level: 1.0,
-                envelope: DelayAttackHoldDecaySustainRelease {
-                    delay: 0.0,
-                    attack: 0.1,
-                    hold: 0.0,
-                    decay: 0.1,
-                    sustain: 0.7,
-                    release: 1.0,
-                },
-                ..Operator::default()
+        operators: Operators::from(vec![Operator {
+            waveform,
+            frequency: RATED_1,
+            level: 1.0,
+            envelope: DelayAttackHoldDecaySustainRelease {
+                delay: 0.0,
+                attack: 0.1,
+                hold: 0.0,
+                decay: 0.1,
+                sustain: 0.7,
+                release: 1.0,
             },
-            ..Operators::default()
-        },
+            ..Operator::default()
+        }]),
         algorithm_preset: FM_ALGORITHM_BASIC_A,
         ..FMInstrument::<DelayAttackHoldDecaySustainRelease>::default()
     };
-    instrument.compile();
+    instrument.compile().unwrap();
 
     Instrument::FM(instrument)
 }
@@ -9,32 +9,31 @@ use audio_engine_sequencer::instrument::Instrument;
 
 pub fn create_fm_wip_instrument() -> Instrument {
     let mut instrument = FMInstrument::<DelayAttackHoldDecaySustainRelease> {
-        operators: Operators::<DelayAttackHoldDecaySustainRelease> {
-            a: Operator {
+        operators: Operators::from(vec![
+            Operator {
                 frequency: RATED_2,
                 level: 1.0,
                 ..Operator::default()
             },
-            b: Operator {
+            Operator {
                 frequency: RATED_4,
                 level: 1.0,
                 ..Operator::default()
             },
-            c: Operator {
+            Operator {
                 frequency: RATED_8,
                 level: 1.0,
                 ..Operator::default()
             },
-            d: Operator {
+            Operator {
                 frequency: RATED_1,
                 level: 16.0,
                 ..Operator::default()
             },
-            ..Operators::<DelayAttackHoldDecaySustainRelease>::default()
-        },
+        ]),
         algorithm_preset: FM_ALGORITHM_BASIC_D_MOD_ABC,
         ..FMInstrument::<DelayAttackHoldDecaySustainRelease>::default()
     };
-    instrument.compile();
+    instrument.compile().unwrap();
     Instrument::FM(instrument)
 }
@@ -1,5 +1,6 @@
 use crate::{ChromaticNote, NoteStep};
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ChromaticChordType {
     Major,
     Minor,
@@ -22,6 +23,28 @@ pub enum ChromaticChordType {
 }
 
 impl ChromaticChordType {
+    /// All chord types, ordered from simple to complex.
+    pub const ALL: [ChromaticChordType; 18] = [
+        Self::Major,
+        Self::Minor,
+        Self::Diminished,
+        Self::Augmented,
+        Self::Sus2,
+        Self::Sus4,
+        Self::SevenSus2,
+        Self::SevenSus4,
+        Self::Sixth,
+        Self::Seventh,
+        Self::Ninth,
+        Self::MajorSeventh,
+        Self::MajorNinth,
+        Self::MajorEleventh,
+        Self::MinorSixth,
+        Self::MinorSeventh,
+        Self::MinorNinth,
+        Self::MinorEleventh,
+    ];
+
     pub fn note_steps(&self) -> Vec<NoteStep> {
         match self {
             Self::Major => vec![0, 4, 7],
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use crate::{ChromaticTone, Note, Pitch};
 
@@ -93,16 +93,32 @@ where
     }
 }
 
+/// Parse a tone followed by an octave, e.g. `C4` or `A#3`.
 impl FromStr for ChromaticNote {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let octave = s[s.len() - 1..s.len()].parse::<u8>().unwrap();
-        let tone = s[0..s.len() - 1].parse::<ChromaticTone>().unwrap();
+        let octave_start = s.find(|c: char| c.is_ascii_digit()).ok_or(())?;
+        let tone = s[..octave_start].parse::<ChromaticTone>()?;
+        let octave = s[octave_start..].parse::<u8>().map_err(|_| ())?;
         Ok(ChromaticNote::new(tone, octave))
     }
 }
 
+/// Formats the note the same way as it is parsed.
+///
+/// ```
+/// use audio_engine_notes::{ChromaticNote, ChromaticTone};
+/// let note = ChromaticNote::new(ChromaticTone::FSharp, 3);
+/// assert_eq!(note.to_string(), "F#3");
+/// assert_eq!(ChromaticNote::from(note.to_string().as_str()), note);
+/// ```
+impl Display for ChromaticNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.tone, self.octave)
+    }
+}
+
 impl From<&str> for ChromaticNote {
     fn from(value: &str) -> Self {
         value.parse::<ChromaticNote>().unwrap()
@@ -1,4 +1,4 @@
-use crate::ChromaticNote;
+use crate::{ChromaticNote, ChromaticTone};
 
 /// Test that converts each chromatic note into a pitch and back. The chromatic notes should match.
 #[test]
@@ -12,3 +12,15 @@ fn to_pitch_and_back() {
         }
     }
 }
+
+#[test]
+fn parse() {
+    assert_eq!(
+        "A#3".parse(),
+        Ok(ChromaticNote::new(ChromaticTone::ASharp, 3))
+    );
+    assert_eq!("C10".parse(), Ok(ChromaticNote::new(ChromaticTone::C, 10)));
+    for invalid in ["", "4", "C", "H4", "C#", "Cb4", "C4x", "é4"] {
+        assert_eq!(invalid.parse::<ChromaticNote>(), Err(()), "{invalid}");
+    }
+}
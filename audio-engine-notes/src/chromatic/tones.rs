@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use crate::{tone::Tone, ChromaticScale};
 
@@ -110,3 +110,23 @@ impl FromStr for ChromaticTone {
         Ok(tone)
     }
 }
+
+impl Display for ChromaticTone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tone = match self {
+            ChromaticTone::C => "C",
+            ChromaticTone::CSharp => "C#",
+            ChromaticTone::D => "D",
+            ChromaticTone::DSharp => "D#",
+            ChromaticTone::E => "E",
+            ChromaticTone::F => "F",
+            ChromaticTone::FSharp => "F#",
+            ChromaticTone::G => "G",
+            ChromaticTone::GSharp => "G#",
+            ChromaticTone::A => "A",
+            ChromaticTone::ASharp => "A#",
+            ChromaticTone::B => "B",
+        };
+        f.write_str(tone)
+    }
+}
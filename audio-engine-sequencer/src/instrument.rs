@@ -1,7 +1,11 @@
 use audio_engine_common::{
     digital_sound::{parameters::NoteParameters, sound::Sound},
-    envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease,
+    envelope::{
+        delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease,
+        multi_segment::MultiSegment,
+    },
     id::ID,
+    modulation::destination::ModulationDestination,
 };
 use audio_engine_instrument_bowed_string::{
     instrument::BowedStringInstrument, processor::DefaultStringProcessor,
@@ -10,17 +14,24 @@ use audio_engine_instrument_fm::instrument::FMInstrument;
 use audio_engine_instrument_piano::{instrument::PianoInstrument, instrument2::PianoInstrument2};
 use audio_engine_instrument_sample::sample::Sample;
 
-use crate::instrument_note_state::InstrumentNoteState;
+use crate::{
+    instrument_note_state::InstrumentNoteState,
+    modulated_instrument::{ModulatedInstrument, ModulatedInstrumentNoteState},
+};
 
 #[derive(Debug, Default, Clone)]
 pub enum Instrument {
     #[default]
     None,
     FM(FMInstrument<DelayAttackHoldDecaySustainRelease>),
+    /// FM instrument using curved multi segment envelopes.
+    FMMultiSegment(FMInstrument<MultiSegment>),
     Sample(Sample),
     Piano(PianoInstrument),
     Piano2(PianoInstrument2),
     BowedString(BowedStringInstrument<DefaultStringProcessor>),
+    /// Instrument with a modulation matrix.
+    Modulated(Box<ModulatedInstrument>),
 }
 
 pub type InstrumentID = ID;
@@ -33,12 +44,19 @@ impl Sound for Instrument {
         match self {
             Self::None => InstrumentNoteState::None,
             Self::FM(fm) => InstrumentNoteState::FM(fm.init_sound_state()),
+            Self::FMMultiSegment(fm) => InstrumentNoteState::FM(fm.init_sound_state()),
             Self::Sample(sample) => InstrumentNoteState::Sample(sample.init_sound_state()),
             Self::Piano(piano) => InstrumentNoteState::Piano(piano.init_sound_state()),
             Self::Piano2(piano) => InstrumentNoteState::Piano2(piano.init_sound_state()),
             Self::BowedString(bowed_string) => {
                 InstrumentNoteState::BowedString(bowed_string.init_sound_state())
             }
+            Self::Modulated(modulated) => {
+                InstrumentNoteState::Modulated(Box::new(ModulatedInstrumentNoteState {
+                    instrument: modulated.instrument.init_sound_state(),
+                    modulation: modulated.modulation.init_state(),
+                }))
+            }
         }
     }
 
@@ -51,6 +69,13 @@ impl Sound for Instrument {
                     0.0
                 }
             }
+            Instrument::FMMultiSegment(instrument) => {
+                if let InstrumentNoteState::FM(state) = state {
+                    instrument.sample(parameters, state)
+                } else {
+                    0.0
+                }
+            }
             Instrument::Piano(piano) => {
                 if let InstrumentNoteState::Piano(state) = state {
                     piano.sample(parameters, state)
@@ -79,7 +104,68 @@ impl Sound for Instrument {
                     0.0
                 }
             }
+            Instrument::Modulated(modulated) => {
+                if let InstrumentNoteState::Modulated(state) = state {
+                    let parameters = modulated.modulation.apply(
+                        parameters,
+                        &mut state.modulation,
+                        &modulated.instrument,
+                        &mut state.instrument,
+                    );
+                    modulated
+                        .instrument
+                        .sample(&parameters, &mut state.instrument)
+                } else {
+                    0.0
+                }
+            }
             Instrument::None => 0.0,
         }
     }
 }
+
+/// Parameters of the instrument that can be modulated. Only FM instruments have parameters
+/// next to [audio_engine_common::modulation::destination::PITCH] and
+/// [audio_engine_common::modulation::destination::GAIN].
+impl ModulationDestination for Instrument {
+    type State = InstrumentNoteState;
+
+    fn parameter_names(&self) -> Vec<String> {
+        match self {
+            Instrument::FM(instrument) => instrument.parameter_names(),
+            Instrument::FMMultiSegment(instrument) => instrument.parameter_names(),
+            Instrument::Modulated(modulated) => modulated.instrument.parameter_names(),
+            _ => Vec::default(),
+        }
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        match (self, state) {
+            (Instrument::FM(instrument), InstrumentNoteState::FM(state)) => {
+                instrument.clear_modulation(state)
+            }
+            (Instrument::FMMultiSegment(instrument), InstrumentNoteState::FM(state)) => {
+                instrument.clear_modulation(state)
+            }
+            (Instrument::Modulated(modulated), InstrumentNoteState::Modulated(state)) => {
+                modulated.instrument.clear_modulation(&mut state.instrument)
+            }
+            _ => {}
+        }
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        match (self, state) {
+            (Instrument::FM(instrument), InstrumentNoteState::FM(state)) => {
+                instrument.modulate(parameter, modulation, state)
+            }
+            (Instrument::FMMultiSegment(instrument), InstrumentNoteState::FM(state)) => {
+                instrument.modulate(parameter, modulation, state)
+            }
+            (Instrument::Modulated(modulated), InstrumentNoteState::Modulated(state)) => modulated
+                .instrument
+                .modulate(parameter, modulation, &mut state.instrument),
+            _ => false,
+        }
+    }
+}
@@ -6,7 +6,10 @@ use audio_engine_instrument_fm::instrument::FMInstrumentNoteState;
 use audio_engine_instrument_piano::{note_state::PianoNoteState, note_state2::PianoNoteState2};
 use audio_engine_instrument_sample::sample_note_state::SampleNoteState;
 
-use crate::instrument::Instrument;
+use crate::{
+    instrument::Instrument,
+    modulated_instrument::{ModulatedInstrument, ModulatedInstrumentNoteState},
+};
 
 #[derive(Default, Clone)]
 pub enum InstrumentNoteState {
@@ -17,6 +20,7 @@ pub enum InstrumentNoteState {
     Piano(PianoNoteState),
     Piano2(PianoNoteState2),
     BowedString(BowedStringInstrumentState<DefaultStringProcessor>),
+    Modulated(Box<ModulatedInstrumentNoteState>),
 }
 
 impl InstrumentNoteState {
@@ -34,7 +38,14 @@ impl InstrumentNoteState {
     pub fn reset(&mut self, instrument: Option<&Instrument>) {
         match instrument {
             None | Some(Instrument::None) => *self = Self::None,
-            Some(Instrument::FM(instrument)) => *self = Self::FM(instrument.init_sound_state()),
+            Some(Instrument::FM(instrument)) => match self {
+                Self::FM(state) => instrument.restart_sound_state(state),
+                _ => *self = Self::FM(instrument.init_sound_state()),
+            },
+            Some(Instrument::FMMultiSegment(instrument)) => match self {
+                Self::FM(state) => instrument.restart_sound_state(state),
+                _ => *self = Self::FM(instrument.init_sound_state()),
+            },
             Some(Instrument::Sample(instrument)) => {
                 *self = Self::Sample(instrument.init_sound_state())
             }
@@ -45,6 +56,22 @@ impl InstrumentNoteState {
                     *self = Self::BowedString(instrument.init_sound_state());
                 }
             }
+            Some(Instrument::Modulated(modulated)) => match self {
+                Self::Modulated(state) => {
+                    state.instrument.reset(Some(&modulated.instrument));
+                    state.modulation = modulated.modulation.init_state();
+                }
+                _ => *self = modulated_instrument_state(modulated),
+            },
         }
     }
 }
+
+fn modulated_instrument_state(modulated: &ModulatedInstrument) -> InstrumentNoteState {
+    let mut instrument = InstrumentNoteState::None;
+    instrument.reset(Some(&modulated.instrument));
+    InstrumentNoteState::Modulated(Box::new(ModulatedInstrumentNoteState {
+        instrument,
+        modulation: modulated.modulation.init_state(),
+    }))
+}
@@ -1,2 +1,3 @@
 pub mod instrument;
 pub mod instrument_note_state;
+pub mod modulated_instrument;
@@ -0,0 +1,148 @@
+use audio_engine_common::{
+    envelope::multi_segment::MultiSegment,
+    modulation::{ModulationMatrix, ModulationMatrixState},
+};
+
+use crate::{instrument::Instrument, instrument_note_state::InstrumentNoteState};
+
+pub type InstrumentModulation = ModulationMatrix<MultiSegment>;
+
+/// Instrument with a modulation matrix.
+///
+/// The modulation matrix is evaluated every sample before the instrument is sampled.
+/// Any instrument can be modulated by pitch and gain, some instruments provide additional
+/// parameters. See [audio_engine_common::modulation::destination::ModulationDestination].
+#[derive(Debug, Clone)]
+pub struct ModulatedInstrument {
+    pub instrument: Instrument,
+    pub modulation: InstrumentModulation,
+}
+
+#[derive(Default, Clone)]
+pub struct ModulatedInstrumentNoteState {
+    pub instrument: InstrumentNoteState,
+    pub modulation: ModulationMatrixState,
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::{
+        digital_sound::{parameters::NoteParameters, sound::Sound},
+        modulation::{
+            destination::{ModulationDestination, PITCH},
+            source::ModulationSource,
+            ModulationRoute,
+        },
+    };
+    use audio_engine_instrument_sample::sample::Sample;
+
+    use crate::{instrument::Instrument, instrument_note_state::InstrumentNoteState};
+
+    use super::{InstrumentModulation, ModulatedInstrument};
+
+    const SAMPLE_RATE: f32 = 44100.0;
+    const PITCH_C4: f32 = 261.62558;
+    static RAMP: [f32; 16] = [
+        0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0,
+    ];
+
+    /// Sample that plays one sample of the ramp per output sample at C4. The sample rate is
+    /// slightly higher so rounding of the pitch doesn't skip a sample.
+    fn ramp() -> Instrument {
+        Instrument::Sample(Sample {
+            start: 0,
+            end: RAMP.len(),
+            is_looped: false,
+            loop_start: 0,
+            loop_end: RAMP.len(),
+            sample_rate_c4: SAMPLE_RATE * 1.001,
+            data: &RAMP,
+        })
+    }
+
+    /// Route the velocity of the note to the given parameter.
+    fn modulated(parameter: &str, amount: f32) -> Instrument {
+        Instrument::Modulated(Box::new(ModulatedInstrument {
+            instrument: ramp(),
+            modulation: InstrumentModulation {
+                routes: vec![ModulationRoute {
+                    source: ModulationSource::Velocity,
+                    parameter: String::from(parameter),
+                    amount,
+                    polarity: ModulationSource::Velocity.polarity(),
+                }],
+                ..InstrumentModulation::default()
+            },
+        }))
+    }
+
+    fn play(instrument: &Instrument, gain: f32, len: usize) -> Vec<f32> {
+        let mut state = InstrumentNoteState::None;
+        state.reset(Some(instrument));
+        (0..len)
+            .map(|index| {
+                instrument.sample(
+                    &NoteParameters {
+                        note_time: index as f32 / SAMPLE_RATE,
+                        note_off: None,
+                        note_pitch: PITCH_C4,
+                        gain,
+                        sample_rate: SAMPLE_RATE,
+                    },
+                    &mut state,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pitch_modulation() {
+        assert_eq!(vec![0.0, 1.0, 2.0, 3.0], play(&ramp(), 1.0, 4));
+        // Full velocity raises the pitch an octave, the sample plays twice as fast.
+        assert_eq!(
+            vec![0.0, 2.0, 4.0, 6.0],
+            play(&modulated(PITCH, 12.0), 1.0, 4)
+        );
+        // Without velocity the pitch isn't modulated.
+        assert_eq!(
+            vec![0.0, 1.0, 2.0, 3.0],
+            play(&modulated(PITCH, 12.0), 0.0, 4)
+        );
+    }
+
+    #[test]
+    fn reset_creates_modulated_state() {
+        let instrument = modulated(PITCH, 12.0);
+        let mut state = InstrumentNoteState::None;
+        state.reset(Some(&instrument));
+        match &state {
+            InstrumentNoteState::Modulated(state) => {
+                assert!(matches!(state.instrument, InstrumentNoteState::Sample(_)))
+            }
+            _ => panic!("expected a modulated note state"),
+        }
+        assert!(matches!(
+            instrument.init_sound_state(),
+            InstrumentNoteState::Modulated(_)
+        ));
+    }
+
+    #[test]
+    fn parameters_of_inner_instrument() {
+        // Samples don't have parameters next to pitch and gain.
+        let instrument = modulated("operator.1.level", 1.0);
+        let mut state = instrument.init_sound_state();
+        assert!(instrument.parameter_names().is_empty());
+        assert!(!instrument.modulate("operator.1.level", 1.0, &mut state));
+        if let Instrument::Modulated(modulated) = &instrument {
+            assert_eq!(
+                vec![String::from("operator.1.level")],
+                modulated
+                    .modulation
+                    .unknown_parameters(&modulated.instrument)
+            );
+        }
+        // Routes to unknown parameters are ignored.
+        assert_eq!(vec![0.0, 1.0, 2.0, 3.0], play(&instrument, 1.0, 4));
+    }
+}
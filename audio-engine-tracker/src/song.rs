@@ -28,7 +28,16 @@ impl Default for Song {
         Self {
             speed: 120.0,
             initial_speed: 4.0,
-            tracks: [Track::default(); 8],
+            tracks: [
+                Track::default(),
+                Track::default(),
+                Track::default(),
+                Track::default(),
+                Track::default(),
+                Track::default(),
+                Track::default(),
+                Track::default(),
+            ],
             patterns: [Pattern::default(); 255],
             phrases: [Phrase::default(); 255],
             instruments: [
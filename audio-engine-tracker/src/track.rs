@@ -1,16 +1,31 @@
-use audio_engine_common::level::Level;
+use audio_engine_common::{
+    envelope::multi_segment::MultiSegment,
+    level::Level,
+    modulation::{destination::ModulationDestination, ModulationMatrix},
+};
 use audio_engine_effect_delay::delay::Delay;
 use audio_engine_effect_distortion::distortion::Distortion;
+use audio_engine_effect_filter::filter::Filter;
+use audio_engine_effect_reverb::reverb::Reverb;
 
-use crate::phrase::PhraseID;
+use crate::{phrase::PhraseID, track_state::TrackState};
 
-#[derive(Copy, Clone)]
+pub type TrackModulation = ModulationMatrix<MultiSegment>;
+
+#[derive(Clone)]
 pub struct Track {
     pub level: Level,
     pub phrases: [PhraseID; 255],
 
     pub delay: Delay,
     pub distortion: Distortion,
+    pub filter: Filter,
+    pub reverb: Reverb,
+
+    /// Modulation of the track effects, evaluated every sample using the note that is
+    /// playing on the track. Parameters of the effects are prefixed with the name of the
+    /// effect, for example `delay.level`.
+    pub modulation: TrackModulation,
 }
 
 impl Default for Track {
@@ -20,6 +35,9 @@ impl Default for Track {
             phrases: [PhraseID::default(); 255],
             delay: Delay::default(),
             distortion: Distortion::default(),
+            filter: Filter::default(),
+            reverb: Reverb::default(),
+            modulation: TrackModulation::default(),
         }
     }
 }
@@ -33,3 +51,171 @@ impl Track {
         self.phrases[strings.len()] = PhraseID::NotSet;
     }
 }
+
+const DELAY_PREFIX: &str = "delay.";
+const DISTORTION_PREFIX: &str = "distortion.";
+const FILTER_PREFIX: &str = "filter.";
+const REVERB_PREFIX: &str = "reverb.";
+
+impl ModulationDestination for Track {
+    type State = TrackState;
+
+    fn parameter_names(&self) -> Vec<String> {
+        let delay = self
+            .delay
+            .parameter_names()
+            .into_iter()
+            .map(|name| format!("{DELAY_PREFIX}{name}"));
+        let distortion = self
+            .distortion
+            .parameter_names()
+            .into_iter()
+            .map(|name| format!("{DISTORTION_PREFIX}{name}"));
+        let filter = self
+            .filter
+            .parameter_names()
+            .into_iter()
+            .map(|name| format!("{FILTER_PREFIX}{name}"));
+        let reverb = self
+            .reverb
+            .parameter_names()
+            .into_iter()
+            .map(|name| format!("{REVERB_PREFIX}{name}"));
+        delay
+            .chain(distortion)
+            .chain(filter)
+            .chain(reverb)
+            .collect()
+    }
+
+    fn clear_modulation(&self, state: &mut Self::State) {
+        self.delay.clear_modulation(&mut state.delay_state);
+        self.distortion
+            .clear_modulation(&mut state.distortion_state);
+        self.filter.clear_modulation(&mut state.filter_state);
+        self.reverb.clear_modulation(&mut state.reverb_state);
+    }
+
+    fn modulate(&self, parameter: &str, modulation: f32, state: &mut Self::State) -> bool {
+        if let Some(parameter) = parameter.strip_prefix(DELAY_PREFIX) {
+            self.delay
+                .modulate(parameter, modulation, &mut state.delay_state)
+        } else if let Some(parameter) = parameter.strip_prefix(DISTORTION_PREFIX) {
+            self.distortion
+                .modulate(parameter, modulation, &mut state.distortion_state)
+        } else if let Some(parameter) = parameter.strip_prefix(FILTER_PREFIX) {
+            self.filter
+                .modulate(parameter, modulation, &mut state.filter_state)
+        } else if let Some(parameter) = parameter.strip_prefix(REVERB_PREFIX) {
+            self.reverb
+                .modulate(parameter, modulation, &mut state.reverb_state)
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::{
+        digital_sound::parameters::NoteParameters,
+        modulation::{
+            destination::ModulationDestination, source::ModulationSource, ModulationRoute,
+        },
+    };
+    use audio_engine_effect::effect::Effect;
+    use audio_engine_effect_delay::delay::Delay;
+
+    use crate::track_state::TrackState;
+
+    use super::{Track, TrackModulation};
+
+    const SAMPLE_RATE: f32 = 4.0;
+
+    /// Track with a delay of one second, the echo has half the level of the input.
+    fn track_with_delay(routes: Vec<ModulationRoute>) -> Track {
+        Track {
+            delay: Delay {
+                is_enabled: true,
+                delay_time: 1.0,
+                level: 0.5,
+                lfo: None,
+            },
+            modulation: TrackModulation {
+                routes,
+                ..TrackModulation::default()
+            },
+            ..Track::default()
+        }
+    }
+
+    fn velocity_route(parameter: &str, amount: f32) -> ModulationRoute {
+        ModulationRoute {
+            source: ModulationSource::Velocity,
+            parameter: String::from(parameter),
+            amount,
+            polarity: ModulationSource::Velocity.polarity(),
+        }
+    }
+
+    /// Apply the modulation of the track for a note with the given velocity, then return the
+    /// echo of an impulse.
+    fn echo(track: &Track, velocity: f32) -> f32 {
+        let mut state = TrackState::default();
+        let mut modulation_state = track.modulation.init_state();
+        track.modulation.apply(
+            &NoteParameters {
+                note_time: 0.0,
+                note_off: None,
+                note_pitch: 440.0,
+                gain: velocity,
+                sample_rate: SAMPLE_RATE,
+            },
+            &mut modulation_state,
+            track,
+            &mut state,
+        );
+        let mut samples = [1.0, 0.0, 0.0, 0.0, 0.0];
+        track
+            .delay
+            .effect_apply(&mut samples, SAMPLE_RATE, &mut state.delay_state);
+        samples[4]
+    }
+
+    #[test]
+    fn parameter_names_are_prefixed() {
+        let names = Track::default().parameter_names();
+        assert!(names.contains(&String::from("delay.level")));
+        assert!(names.contains(&String::from("distortion.level")));
+        assert!(!names.contains(&String::from("level")));
+    }
+
+    #[test]
+    fn modulate_effect_level() {
+        let unmodulated = track_with_delay(vec![]);
+        assert_eq!(0.5, echo(&unmodulated, 1.0));
+
+        // Full velocity mutes the echo, half velocity halves it.
+        let track = track_with_delay(vec![velocity_route("delay.level", -1.0)]);
+        assert_eq!(0.0, echo(&track, 1.0));
+        assert_eq!(0.25, echo(&track, 0.5));
+    }
+
+    #[test]
+    fn unknown_parameters() {
+        let track = track_with_delay(vec![
+            velocity_route("delay.level", 1.0),
+            velocity_route("level", 1.0),
+            velocity_route("chorus.mix", 1.0),
+        ]);
+        assert_eq!(
+            vec![String::from("level"), String::from("chorus.mix")],
+            track.modulation.unknown_parameters(&track)
+        );
+        let mut state = TrackState::default();
+        assert!(!track.modulate("level", 1.0, &mut state));
+        assert!(track.modulate("distortion.level", 1.0, &mut state));
+        track.clear_modulation(&mut state);
+        assert_eq!(0.0, state.distortion_state.level_modulation);
+    }
+}
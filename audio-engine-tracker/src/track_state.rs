@@ -1,6 +1,8 @@
-use audio_engine_common::{level::Level, note_time::NoteTime};
+use audio_engine_common::{level::Level, modulation::ModulationMatrixState, note_time::NoteTime};
 use audio_engine_effect_delay::delay_state::DelayState;
 use audio_engine_effect_distortion::distortion_state::DistortionState;
+use audio_engine_effect_filter::filter_state::FilterState;
+use audio_engine_effect_reverb::reverb_state::ReverbState;
 use audio_engine_sequencer::{
     instrument::InstrumentID, instrument_note_state::InstrumentNoteState,
 };
@@ -19,6 +21,9 @@ pub struct TrackState {
 
     pub delay_state: DelayState,
     pub distortion_state: DistortionState,
+    pub filter_state: FilterState,
+    pub reverb_state: ReverbState,
+    pub modulation_state: ModulationMatrixState,
 }
 
 impl Default for TrackState {
@@ -33,6 +38,9 @@ impl Default for TrackState {
             level: 0.0,
             delay_state: DelayState::default(),
             distortion_state: DistortionState::default(),
+            filter_state: FilterState::default(),
+            reverb_state: ReverbState::default(),
+            modulation_state: ModulationMatrixState::default(),
         }
     }
 }
@@ -1,3 +1,4 @@
+use audio_engine_common::modulation::destination::ModulationDestination;
 use audio_engine_common::{
     digital_sound::{parameters::NoteParameters, sound::Sound},
     song_time::SongTime,
@@ -59,7 +60,7 @@ pub fn sample_song(
         let track = &song.tracks[track_id];
         if let Some(row) = calc_track_position(song_state, song, track, global_row_index) {
             let track_state = &mut song_state.tracks[track_id];
-            apply_row(song, track_state, song_time, global_row_index, row);
+            apply_row(song, track, track_state, song_time, global_row_index, row);
             let track_result = sample_track(song, track, track_state, song_time, sample_rate);
             match (result, track_result) {
                 (None, sample) => result = Some(sample),
@@ -83,21 +84,27 @@ pub fn sample_track(
     let track_sample = if let Some(note_on) = track_state.note_on {
         if let Some(instrument) = song.get_instrument(track_state.instrument_id) {
             let note_time = song_time - note_on;
-            let note_off = track_state.note_off.map(|note_off| song_time - note_off);
-            instrument.sample(
-                &NoteParameters {
-                    note_time,
-                    note_off,
-                    note_pitch: track_state.note_pitch,
-                    gain: track_state.level,
-                    sample_rate,
-                },
-                &mut track_state.instrument_note_state,
-            ) * track.level
+            let note_off = track_state.note_off.map(|note_off| note_off - note_on);
+            let parameters = NoteParameters {
+                note_time,
+                note_off,
+                note_pitch: track_state.note_pitch,
+                gain: track_state.level,
+                sample_rate,
+            };
+            let mut modulation_state = std::mem::take(&mut track_state.modulation_state);
+            let parameters =
+                track
+                    .modulation
+                    .apply(&parameters, &mut modulation_state, track, track_state);
+            track_state.modulation_state = modulation_state;
+            instrument.sample(&parameters, &mut track_state.instrument_note_state) * track.level
         } else {
+            track.clear_modulation(track_state);
             0.0
         }
     } else {
+        track.clear_modulation(track_state);
         0.0
     };
 
@@ -122,6 +129,12 @@ pub fn apply_track_effects(
         sample_rate,
         &mut track_state.distortion_state,
     );
+    track
+        .filter
+        .effect_apply(track_samples, sample_rate, &mut track_state.filter_state);
+    track
+        .reverb
+        .effect_apply(track_samples, sample_rate, &mut track_state.reverb_state);
 }
 
 pub fn calc_track_position<'a>(
@@ -169,6 +182,7 @@ where
 
 fn apply_row(
     song: &Song,
+    track: &Track,
     track_state: &mut TrackState,
     song_time: SongTime,
     global_row_index: u32,
@@ -180,6 +194,7 @@ fn apply_row(
         match row.event {
             Some(Event::NoteOn(note, instrument_id)) => {
                 track_state.note_on = Some(song_time);
+                track_state.note_off = None;
                 if instrument_id != InstrumentID::NotSet {
                     track_state.instrument_id = instrument_id;
                 }
@@ -187,6 +202,7 @@ fn apply_row(
                 track_state.note_pitch = note.pitch();
                 let instrument = song.get_instrument(track_state.instrument_id);
                 track_state.instrument_note_state.reset(instrument);
+                track_state.modulation_state = track.modulation.init_state();
             }
             Some(Event::NoteRelease) => {
                 track_state.note_off = Some(song_time);
@@ -0,0 +1,87 @@
+use audio_engine_notes::ChromaticNote;
+use clap::Parser;
+
+#[derive(Debug, Clone, Parser)]
+#[command(
+    about = "Search an FM instrument that sounds like a (.wav) file",
+    long_about = "Search an FM instrument that sounds like <INPUT_FILENAME> played at <ROOT_NOTE> using a genetic algorithm. The distance between the sample and an instrument is measured on their spectra. The found instrument is written as a rust source module that can be added to the instrument library. Multichannel wave files will be joined into a single channel."
+)]
+pub struct Arguments {
+    /// Seed of the random number generator. Searches with the same seed and the same
+    /// number of generations give the same result.
+    #[arg(long, default_value_t = 1)]
+    pub seed: u64,
+
+    /// Maximum time in seconds to search.
+    #[arg(long, default_value_t = 60.0)]
+    pub time_budget: f32,
+
+    /// Maximum number of generations to search.
+    #[arg(long, default_value_t = 1000)]
+    pub generations: usize,
+
+    /// Number of instruments in each generation.
+    #[arg(long, default_value_t = 32)]
+    pub population: usize,
+
+    /// Number of frames of the sample to compare.
+    #[arg(long, default_value_t = 8)]
+    pub frames: usize,
+
+    /// Note that is played in the input file (e.g. C4, A#3)
+    #[arg(long, default_value = "C4", value_parser = parse_note)]
+    pub root_note: ChromaticNote,
+
+    /// Name of the instrument, used for the generated function name.
+    #[arg(long, default_value = "resynthesized")]
+    pub name: String,
+
+    /// Write the rust source module to this file in stead of standard out.
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Write a (.wav) file with the original sample followed by the FM resynthesis.
+    #[arg(long)]
+    pub comparison: Option<String>,
+
+    /// Input wave file to resynthesize
+    pub input_filename: String,
+}
+
+/// Parse a note like `C4` or `A#3`.
+fn parse_note(value: &str) -> Result<ChromaticNote, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{value} isn't a note (e.g. C4, A#3)"))
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_notes::{ChromaticNote, ChromaticTone};
+    use clap::Parser;
+
+    use super::Arguments;
+
+    #[test]
+    fn root_note() {
+        let arguments =
+            Arguments::try_parse_from(["sample2fm", "--root-note", "A#3", "input.wav"]).unwrap();
+        assert_eq!(
+            arguments.root_note,
+            ChromaticNote::new(ChromaticTone::ASharp, 3)
+        );
+
+        let defaults = Arguments::try_parse_from(["sample2fm", "input.wav"]).unwrap();
+        assert_eq!(defaults.root_note, ChromaticNote::new(ChromaticTone::C, 4));
+    }
+
+    #[test]
+    fn invalid_root_note() {
+        for value in ["H4", "C", ""] {
+            assert!(
+                Arguments::try_parse_from(["sample2fm", "--root-note", value, "input.wav"])
+                    .is_err()
+            );
+        }
+    }
+}
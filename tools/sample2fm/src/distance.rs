@@ -0,0 +1,89 @@
+use audio_engine_fourier::{
+    complex_number::ComplexNumberMethods, fft::RealFft, window::WindowFunction,
+};
+
+/// Number of samples in a frame.
+pub const FRAME_LEN: usize = 1024;
+
+/// Highest frequency to compare, in harmonics of the note.
+const MAX_HARMONIC: f32 = 16.0;
+
+/// Magnitude spectra of consecutive frames of a signal. A last partial frame is zero
+/// padded.
+///
+/// Comparing magnitudes ignores the phase of the partials, so small phase differences
+/// don't influence the distance.
+pub struct Spectrogram {
+    frames: Vec<Vec<f32>>,
+}
+
+impl Spectrogram {
+    pub fn new(samples: &[f32], note_pitch: f32, sample_rate: f32) -> Spectrogram {
+        let fft = RealFft::new(FRAME_LEN);
+        let max_bin = ((note_pitch * MAX_HARMONIC * FRAME_LEN as f32 / sample_rate) as usize)
+            .min(fft.spectrum_len() - 1);
+        let window = WindowFunction::Hann.create(FRAME_LEN);
+
+        let frames = samples
+            .chunks(FRAME_LEN)
+            .map(|frame| {
+                let mut windowed = frame
+                    .iter()
+                    .zip(window.iter())
+                    .map(|(sample, window)| sample * window)
+                    .collect::<Vec<f32>>();
+                windowed.resize(FRAME_LEN, 0.0);
+                fft.forward(&windowed)[..=max_bin]
+                    .iter()
+                    .map(|bin| (1.0 + bin.amplitude()).ln())
+                    .collect()
+            })
+            .collect();
+        Spectrogram { frames }
+    }
+
+    /// Sum of the differences between the log magnitudes of both spectrograms.
+    pub fn distance(&self, other: &Spectrogram) -> f32 {
+        self.frames
+            .iter()
+            .zip(other.frames.iter())
+            .map(|(a, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| (a - b).abs())
+                    .sum::<f32>()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::TAU;
+
+    use super::{Spectrogram, FRAME_LEN};
+
+    fn sine(frequency: f32, phase: f32) -> Vec<f32> {
+        (0..FRAME_LEN * 2)
+            .map(|index| (TAU * (frequency * index as f32 / 44100.0 + phase)).sin())
+            .collect()
+    }
+
+    #[test]
+    fn phase_has_little_influence() {
+        let reference = Spectrogram::new(&sine(440.0, 0.0), 440.0, 44100.0);
+        let shifted = Spectrogram::new(&sine(440.0, 0.3), 440.0, 44100.0);
+        let octave = Spectrogram::new(&sine(880.0, 0.0), 440.0, 44100.0);
+        assert_eq!(reference.distance(&reference), 0.0);
+        assert!(reference.distance(&shifted) * 10.0 < reference.distance(&octave));
+    }
+
+    #[test]
+    fn short_input_is_padded() {
+        let short = &sine(440.0, 0.0)[..FRAME_LEN / 4];
+        let reference = Spectrogram::new(short, 440.0, 44100.0);
+        let octave = Spectrogram::new(&sine(880.0, 0.0)[..FRAME_LEN / 4], 440.0, 44100.0);
+        assert_eq!(reference.frames.len(), 1);
+        assert!(reference.distance(&octave) > 0.0);
+    }
+}
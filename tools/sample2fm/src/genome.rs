@@ -0,0 +1,132 @@
+use audio_engine_common::{
+    envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease, id::ID,
+    waveform::Waveform,
+};
+use audio_engine_instrument_fm::{
+    algorithm::preset::Algorithm,
+    instrument::FMInstrument,
+    operator::{Operator, Operators, DEFAULT_NUM_OPERATORS},
+    operator_frequency::OperatorFrequency,
+};
+
+use crate::random::Random;
+
+const NUM_ALGORITHMS: usize = 32;
+const MAX_FEEDBACK: u8 = 7;
+/// Release of each operator, not part of the search as the sample is compared without
+/// note off.
+const RELEASE: f32 = 0.2;
+/// Highest level of a modulator, carriers have a level up to 1.0.
+const MAX_MODULATION_LEVEL: f32 = 4.0;
+
+const INSTRUMENT_GENES: usize = 2;
+const OPERATOR_GENES: usize = 5;
+const RATIO_GENE: usize = 0;
+const LEVEL_GENE: usize = 1;
+const ATTACK_GENE: usize = 2;
+const DECAY_GENE: usize = 3;
+const SUSTAIN_GENE: usize = 4;
+
+pub type Instrument = FMInstrument<DelayAttackHoldDecaySustainRelease>;
+
+/// Parameters of an FM instrument, each gene is in the range `0.0..=1.0`.
+///
+/// Keeping genes in the same range lets crossover and mutation treat all parameters the
+/// same way. [Genome::decode] maps the genes to the actual parameter ranges.
+#[derive(Debug, Clone)]
+pub struct Genome {
+    pub genes: Vec<f32>,
+}
+
+impl Genome {
+    pub fn random(random: &mut Random) -> Genome {
+        let genes = (0..INSTRUMENT_GENES + DEFAULT_NUM_OPERATORS * OPERATOR_GENES)
+            .map(|_| random.next_f32())
+            .collect();
+        Genome { genes }
+    }
+
+    /// Take each gene from one of both parents.
+    pub fn crossover(&self, other: &Genome, random: &mut Random) -> Genome {
+        let genes = self
+            .genes
+            .iter()
+            .zip(other.genes.iter())
+            .map(|(a, b)| if random.next_f32() < 0.5 { *a } else { *b })
+            .collect();
+        Genome { genes }
+    }
+
+    /// Change each gene with the given probability by a normal distributed amount.
+    pub fn mutate(&mut self, rate: f32, strength: f32, random: &mut Random) {
+        for gene in &mut self.genes {
+            if random.next_f32() < rate {
+                *gene = (*gene + random.next_gaussian() * strength).clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    pub fn decode(&self) -> Instrument {
+        let algorithm = Algorithm::DX7(
+            1 + ((self.genes[0] * NUM_ALGORITHMS as f32) as u8).min(NUM_ALGORITHMS as u8 - 1),
+        );
+        let feedback = ((self.genes[1] * (MAX_FEEDBACK + 1) as f32) as u8).min(MAX_FEEDBACK);
+        let carriers = carriers(&algorithm, feedback);
+
+        let operators = self.genes[INSTRUMENT_GENES..]
+            .chunks_exact(OPERATOR_GENES)
+            .enumerate()
+            .map(|(index, genes)| {
+                let max_level = if carriers.contains(&index) {
+                    1.0
+                } else {
+                    MAX_MODULATION_LEVEL
+                };
+                Operator::<DelayAttackHoldDecaySustainRelease> {
+                    enable: true,
+                    waveform: Waveform::Sine,
+                    // 0.5 upto 16.0
+                    frequency: OperatorFrequency::Rated(0.5 * (5.0 * genes[RATIO_GENE]).exp2()),
+                    level: genes[LEVEL_GENE] * max_level,
+                    envelope: DelayAttackHoldDecaySustainRelease {
+                        // 0.5ms upto 256ms
+                        attack: 0.0005 * (9.0 * genes[ATTACK_GENE]).exp2(),
+                        // 10ms upto 2.56s
+                        decay: 0.01 * (8.0 * genes[DECAY_GENE]).exp2(),
+                        sustain: genes[SUSTAIN_GENE],
+                        release: RELEASE,
+                        ..DelayAttackHoldDecaySustainRelease::default()
+                    },
+                    ..Operator::<DelayAttackHoldDecaySustainRelease>::default()
+                }
+            })
+            .collect::<Vec<Operator<DelayAttackHoldDecaySustainRelease>>>();
+
+        let mut instrument = Instrument {
+            algorithm: None,
+            algorithm_preset: algorithm,
+            feedback,
+            operators: Operators::from(operators),
+            lfos: Vec::default(),
+            pitch_envelope: None,
+        };
+        instrument.compile().expect("DX7 algorithms are valid");
+        instrument
+    }
+}
+
+/// Indices of the operators that write to the output of the algorithm.
+fn carriers(algorithm: &Algorithm, feedback: u8) -> Vec<usize> {
+    let compiled = algorithm
+        .compile(feedback)
+        .expect("DX7 algorithms are valid");
+    compiled
+        .execution_steps
+        .iter()
+        .filter(|step| compiled.carrier_output.contains(&step.stack_out))
+        .filter_map(|step| match step.operator_index {
+            ID::Index(index) => Some(index as usize),
+            _ => None,
+        })
+        .collect()
+}
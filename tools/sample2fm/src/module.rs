@@ -0,0 +1,181 @@
+use std::fmt::{Display, Write};
+
+use audio_engine_instrument_fm::{
+    algorithm::{graph::GraphError, preset::Algorithm},
+    operator_frequency::OperatorFrequency,
+};
+
+use crate::genome::Instrument;
+
+/// Reason an instrument cannot be written as source code.
+#[derive(Debug)]
+pub enum ModuleError {
+    /// Only DX7 and custom algorithms can be written.
+    UnsupportedAlgorithm(Algorithm),
+    /// The custom algorithm wouldn't parse in the generated module.
+    InvalidGraph(GraphError),
+}
+
+impl Display for ModuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModuleError::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "{algorithm:?} cannot be written as source code")
+            }
+            ModuleError::InvalidGraph(error) => write!(f, "invalid algorithm: {error}"),
+        }
+    }
+}
+
+/// Generate a rust source module shaped like the modules in `audio-engine-instruments`.
+///
+/// The module contains a single function `create_fm_<name>_instrument` that returns the
+/// compiled instrument.
+pub fn to_rust_module(instrument: &Instrument, name: &str) -> Result<String, ModuleError> {
+    let mut result = String::new();
+    let operators = instrument
+        .operators
+        .operators
+        .iter()
+        .map(|operator| {
+            let frequency = match operator.frequency {
+                OperatorFrequency::Rated(ratio) => format!("OperatorFrequency::Rated({ratio:?})"),
+                OperatorFrequency::Fixed(frequency) => {
+                    format!("OperatorFrequency::Fixed({frequency:?})")
+                }
+            };
+            let envelope = &operator.envelope;
+            format!(
+                "            Operator {{
+                waveform: Waveform::Sine,
+                frequency: {frequency},
+                level: {:?},
+                envelope: DelayAttackHoldDecaySustainRelease {{
+                    delay: {:?},
+                    attack: {:?},
+                    hold: {:?},
+                    decay: {:?},
+                    sustain: {:?},
+                    release: {:?},
+                }},
+                ..Operator::default()
+            }},
+",
+                operator.level,
+                envelope.delay,
+                envelope.attack,
+                envelope.hold,
+                envelope.decay,
+                envelope.sustain,
+                envelope.release
+            )
+        })
+        .collect::<String>();
+    let algorithm = match &instrument.algorithm_preset {
+        Algorithm::DX7(number) => format!("Algorithm::DX7({number})"),
+        Algorithm::Custom(graph) => {
+            graph.validate().map_err(ModuleError::InvalidGraph)?;
+            format!(
+                "Algorithm::Custom(\"{}\".parse().unwrap())",
+                graph.to_string().replace('\n', "; ")
+            )
+        }
+        algorithm => return Err(ModuleError::UnsupportedAlgorithm(algorithm.clone())),
+    };
+    let function_name = name.to_lowercase().replace(['-', ' '], "_");
+
+    writeln!(
+        result,
+        "use audio_engine_common::{{
+    envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease,
+    waveform::Waveform,
+}};
+use audio_engine_instrument_fm::{{
+    algorithm::preset::Algorithm,
+    instrument::FMInstrument,
+    operator::{{Operator, Operators}},
+    operator_frequency::OperatorFrequency,
+}};
+use audio_engine_sequencer::instrument::Instrument;
+
+/// Generated by sample2fm.
+pub fn create_fm_{function_name}_instrument() -> Instrument {{
+    let mut instrument = FMInstrument::<DelayAttackHoldDecaySustainRelease> {{
+        operators: Operators::from(vec![
+{operators}        ]),
+        algorithm_preset: {algorithm},
+        feedback: {},
+        ..FMInstrument::<DelayAttackHoldDecaySustainRelease>::default()
+    }};
+    instrument.compile().unwrap();
+
+    Instrument::FM(instrument)
+}}",
+        instrument.feedback
+    )
+    .unwrap();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use audio_engine_common::envelope::delay_attack_hold_decay_sustain_release::DelayAttackHoldDecaySustainRelease;
+    use audio_engine_instrument_fm::{
+        algorithm::preset::{basic::Basic, Algorithm},
+        operator::{Operator, Operators},
+        operator_frequency::OperatorFrequency,
+    };
+
+    use crate::genome::Instrument;
+
+    use super::{to_rust_module, ModuleError};
+
+    fn instrument(algorithm_preset: Algorithm) -> Instrument {
+        Instrument {
+            algorithm_preset,
+            feedback: 3,
+            operators: Operators::from(vec![Operator {
+                frequency: OperatorFrequency::Rated(2.0),
+                level: 0.5,
+                envelope: DelayAttackHoldDecaySustainRelease {
+                    attack: 0.25,
+                    ..DelayAttackHoldDecaySustainRelease::default()
+                },
+                ..Operator::default()
+            }]),
+            ..Instrument::default()
+        }
+    }
+
+    #[test]
+    fn dx7_algorithm() {
+        let module = to_rust_module(&instrument(Algorithm::DX7(5)), "Electric piano").unwrap();
+        assert!(module.contains("pub fn create_fm_electric_piano_instrument() -> Instrument {"));
+        assert!(module.contains("algorithm_preset: Algorithm::DX7(5),"));
+        assert!(module.contains("feedback: 3,"));
+        assert!(module.contains("frequency: OperatorFrequency::Rated(2.0),"));
+        assert!(module.contains("level: 0.5,"));
+        assert!(module.contains("attack: 0.25,"));
+        assert!(module.contains("Instrument::FM(instrument)"));
+    }
+
+    #[test]
+    fn custom_algorithm() {
+        let module = to_rust_module(
+            &instrument(Algorithm::Custom("2 -> 1\n1 -> out".parse().unwrap())),
+            "custom",
+        )
+        .unwrap();
+        assert!(module.contains(
+            "algorithm_preset: Algorithm::Custom(\"2 -> 1; 1 -> out\".parse().unwrap()),"
+        ));
+    }
+
+    #[test]
+    fn unsupported_algorithm() {
+        assert!(matches!(
+            to_rust_module(&instrument(Algorithm::Basic(Basic::A)), "basic"),
+            Err(ModuleError::UnsupportedAlgorithm(_))
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+/// Xorshift random number generator.
+///
+/// Small and deterministic: the same seed always produces the same sequence.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        // State may not be zero.
+        Random {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+        }
+        .with_nonzero_state()
+    }
+
+    fn with_nonzero_state(mut self) -> Random {
+        if self.state == 0 {
+            self.state = 1;
+        }
+        self
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Random value in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1_u64 << 24) as f32
+    }
+
+    /// Random index in `0..len`.
+    pub fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Normal distributed value with a mean of 0.0 and a standard deviation of 1.0.
+    pub fn next_gaussian(&mut self) -> f32 {
+        let u1 = self.next_f32().max(f32::MIN_POSITIVE);
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
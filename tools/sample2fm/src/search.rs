@@ -0,0 +1,140 @@
+use std::time::Instant;
+
+use audio_engine_common::digital_sound::{parameters::NoteParameters, sound::Sound};
+
+use crate::{
+    arguments::Arguments,
+    distance::Spectrogram,
+    genome::{Genome, Instrument},
+    random::Random,
+};
+
+/// Number of best genomes that are copied unchanged to the next generation.
+const ELITE: usize = 2;
+/// Number of genomes competing to become a parent.
+const TOURNAMENT_SIZE: usize = 3;
+const MUTATION_STRENGTH: f32 = 0.1;
+
+/// Genetic search for the instrument that sounds closest to the target.
+///
+/// The time budget is only checked between generations. With the same seed the search
+/// produces the same generations, so the result only depends on how many generations
+/// fit in the budget.
+pub struct Search {
+    target: Spectrogram,
+    note_pitch: f32,
+    sample_rate: f32,
+    num_samples: usize,
+    random: Random,
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub genome: Genome,
+    pub distance: f32,
+}
+
+impl Search {
+    pub fn new(target: &[f32], note_pitch: f32, sample_rate: f32, seed: u64) -> Search {
+        Search {
+            target: Spectrogram::new(target, note_pitch, sample_rate),
+            note_pitch,
+            sample_rate,
+            num_samples: target.len(),
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn run(&mut self, arguments: &Arguments) -> Candidate {
+        let start = Instant::now();
+        let population_size = arguments.population.max(ELITE + 1);
+        let genomes = (0..population_size)
+            .map(|_| Genome::random(&mut self.random))
+            .collect::<Vec<Genome>>();
+        let mut population = genomes
+            .into_iter()
+            .map(|genome| self.evaluate(genome))
+            .collect::<Vec<Candidate>>();
+        sort(&mut population);
+
+        for generation in 0..arguments.generations {
+            if start.elapsed().as_secs_f32() > arguments.time_budget {
+                break;
+            }
+            population = self.next_generation(&population);
+            eprintln!(
+                "generation: {generation} distance: {:.3}",
+                population[0].distance
+            );
+        }
+
+        population.swap_remove(0)
+    }
+
+    fn next_generation(&mut self, population: &[Candidate]) -> Vec<Candidate> {
+        let mutation_rate = 1.0 / population[0].genome.genes.len() as f32;
+        let mut result = population[..ELITE].to_vec();
+        while result.len() < population.len() {
+            let a = self.select(population);
+            let b = self.select(population);
+            let mut child = a.crossover(b, &mut self.random);
+            child.mutate(mutation_rate, MUTATION_STRENGTH, &mut self.random);
+            result.push(self.evaluate(child));
+        }
+        sort(&mut result);
+        result
+    }
+
+    /// Tournament selection: the best of a few random genomes.
+    fn select<'a>(&mut self, population: &'a [Candidate]) -> &'a Genome {
+        let best = (0..TOURNAMENT_SIZE)
+            .map(|_| self.random.next_index(population.len()))
+            .min()
+            .unwrap();
+        // Population is sorted, so the lowest index has the lowest distance.
+        &population[best].genome
+    }
+
+    fn evaluate(&self, genome: Genome) -> Candidate {
+        let samples = render(
+            &genome.decode(),
+            self.note_pitch,
+            self.sample_rate,
+            self.num_samples,
+        );
+        let distance = self.target.distance(&Spectrogram::new(
+            &samples,
+            self.note_pitch,
+            self.sample_rate,
+        ));
+        Candidate { genome, distance }
+    }
+}
+
+/// Render a note without note off.
+pub fn render(
+    instrument: &Instrument,
+    note_pitch: f32,
+    sample_rate: f32,
+    num_samples: usize,
+) -> Vec<f32> {
+    let mut sound_state = instrument.init_sound_state();
+    (0..num_samples)
+        .map(|index| {
+            instrument.sample(
+                &NoteParameters {
+                    note_time: index as f32 / sample_rate,
+                    note_off: None,
+                    note_pitch,
+                    gain: 1.0,
+                    sample_rate,
+                },
+                &mut sound_state,
+            )
+        })
+        .collect()
+}
+
+fn sort(population: &mut [Candidate]) {
+    population.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+}
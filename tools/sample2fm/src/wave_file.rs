@@ -0,0 +1,39 @@
+use std::{fs::File, io, path::Path};
+
+use audio_engine_common::convert_f32s::Convert2F32;
+use wav::{header::WAV_FORMAT_IEEE_FLOAT, BitDepth, Header};
+
+/// Mono audio read from or written to a (.wav) file.
+pub struct WaveFile {
+    pub sample_rate: f32,
+    pub samples: Vec<f32>,
+}
+
+impl WaveFile {
+    /// Read a wave file, multiple channels are averaged into a single channel.
+    pub fn read(path: &Path) -> io::Result<WaveFile> {
+        let mut file = File::open(path)?;
+        let (header, data) = wav::read(&mut file)?;
+        let channel_count = header.channel_count.max(1) as usize;
+        let samples = data
+            .to_f32s()
+            .chunks(channel_count)
+            .map(|chunk| chunk.iter().sum::<f32>() / channel_count as f32)
+            .collect();
+        Ok(WaveFile {
+            sample_rate: header.sampling_rate as f32,
+            samples,
+        })
+    }
+
+    /// Write the samples as a mono 32 bit float wave file.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 1, self.sample_rate as u32, 32);
+        let mut file = File::create(path)?;
+        wav::write(
+            header,
+            &BitDepth::ThirtyTwoFloat(self.samples.clone()),
+            &mut file,
+        )
+    }
+}
@@ -1,10 +1,10 @@
 use audio_engine_notes::ChromaticNote;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
-#[derive(Debug, Copy, Clone, Parser)]
+#[derive(Debug, Clone, Parser)]
 #[command(
     about = "Instrument tuner",
-    long_about = "Tool for tuning instruments by listening to an audio source and determine the pitch with the most amplitude."
+    long_about = "Tool for tuning instruments by listening to an audio source and determine its pitch. Shows the deviation from the wanted note in cents. With --input a (.wav) file is analysed in stead and a pitch track is printed."
 )]
 pub struct Arguments {
     /// Note to tune towards.
@@ -17,16 +17,31 @@ pub struct Arguments {
     #[arg(long, default_value_t = 4096)]
     pub buffer_size: usize,
 
-    /// Number of steps to use for detecting the pitch of the recorded audio.
-    ///
-    /// The higher the number the more steps are used, but would lead to lower
-    /// performance.
-    #[arg(long, default_value_t = 4096)]
-    pub steps: usize,
-
-    /// Minimum threshold of the volume to start tuning
+    /// Minimum threshold of the volume (RMS) to start tuning
     #[arg(long, default_value_t = 0.001)]
     pub threshold: f32,
+
+    /// Minimum confidence of the detected pitch (0.0 - 1.0)
+    #[arg(long, default_value_t = 0.8)]
+    pub confidence: f32,
+
+    /// Analyse this (.wav) file in stead of listening to the default input device.
+    #[arg(long)]
+    pub input: Option<String>,
+
+    /// Number of samples between two analysed windows of the input file.
+    #[arg(long, default_value_t = 1024)]
+    pub hop_size: usize,
+
+    /// Format of the pitch track of the input file.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Csv,
 }
 
 impl Arguments {}
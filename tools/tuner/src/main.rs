@@ -1,12 +1,35 @@
+use std::path::Path;
+
 use arguments::Arguments;
 use clap::Parser;
-use tuner::Tuner;
+use offline::analyse_file;
 
 mod arguments;
+mod offline;
+mod pitch;
+#[cfg(feature = "live")]
 mod tuner;
 
 fn main() {
     let arguments = Arguments::parse();
-    let mut tuner = Tuner::new(arguments);
+    if let Some(input) = &arguments.input {
+        if let Err(error) = analyse_file(Path::new(input), &arguments, &mut std::io::stdout()) {
+            eprintln!("error: {input}: {error}");
+            std::process::exit(1);
+        }
+    } else {
+        listen(arguments);
+    }
+}
+
+#[cfg(feature = "live")]
+fn listen(arguments: Arguments) {
+    let mut tuner = tuner::Tuner::new(arguments);
     tuner.start();
 }
+
+#[cfg(not(feature = "live"))]
+fn listen(_arguments: Arguments) {
+    eprintln!("error: tuner is built without the live feature, use --input to analyse a file");
+    std::process::exit(1);
+}
@@ -0,0 +1,103 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+};
+
+use audio_engine_common::convert_f32s::Convert2F32;
+
+use crate::{
+    arguments::{Arguments, OutputFormat},
+    pitch::estimate_pitch,
+};
+
+/// Analyse a wave file window by window and write the pitch track to the output.
+///
+/// Windows without a confident pitch are written without pitch, so the time line stays
+/// complete.
+pub fn analyse_file(input: &Path, args: &Arguments, output: &mut impl Write) -> io::Result<()> {
+    let mut file = File::open(input)?;
+    let (header, data) = wav::read(&mut file)?;
+    let sample_rate = header.sampling_rate as f32;
+    let mono_samples = data
+        .to_f32s()
+        .chunks(header.channel_count.max(1) as usize)
+        .map(|samples| samples[0])
+        .collect::<Vec<f32>>();
+
+    if args.format == OutputFormat::Csv {
+        writeln!(output, "time,frequency,note,cents,confidence")?;
+    }
+    let hop_size = args.hop_size.max(1);
+    let mut start = 0;
+    while start + args.buffer_size <= mono_samples.len() {
+        let window = &mono_samples[start..start + args.buffer_size];
+        let time = start as f32 / sample_rate;
+        let estimate = estimate_pitch(window, sample_rate, args);
+        match (args.format, estimate) {
+            (OutputFormat::Text, Some(estimate)) => writeln!(
+                output,
+                "{time:8.3}s {:8.2}Hz {:<4} {:+6.1} cents",
+                estimate.frequency,
+                estimate.note.to_string(),
+                estimate.cents
+            ),
+            (OutputFormat::Text, None) => writeln!(output, "{time:8.3}s        -"),
+            (OutputFormat::Csv, Some(estimate)) => writeln!(
+                output,
+                "{time:.3},{:.2},{},{:.1},{:.3}",
+                estimate.frequency, estimate.note, estimate.cents, estimate.confidence
+            ),
+            (OutputFormat::Csv, None) => writeln!(output, "{time:.3},,,,"),
+        }?;
+        start += hop_size;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{f32::consts::TAU, fs::File};
+
+    use clap::Parser;
+    use wav::{header::WAV_FORMAT_PCM, BitDepth, Header};
+
+    use crate::arguments::Arguments;
+
+    use super::analyse_file;
+
+    #[test]
+    fn analyse_sine() {
+        // 445Hz is 19.6 cents above A4.
+        let path =
+            std::env::temp_dir().join(format!("tuner-analyse-sine-{}.wav", std::process::id()));
+        let samples = (0..44100)
+            .map(|index| ((TAU * 445.0 * index as f32 / 44100.0).sin() * 16384.0) as i16)
+            .collect::<Vec<i16>>();
+        wav::write(
+            Header::new(WAV_FORMAT_PCM, 1, 44100, 16),
+            &BitDepth::Sixteen(samples),
+            &mut File::create(&path).unwrap(),
+        )
+        .unwrap();
+
+        let args = Arguments::try_parse_from(["tuner", "A4", "--format", "csv"]).unwrap();
+        let mut output = Vec::new();
+        analyse_file(&path, &args, &mut output).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(Some("time,frequency,note,cents,confidence"), lines.next());
+        let rows = lines
+            .map(|line| line.split(',').collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+        // (44100 - 4096) / 1024 + 1 windows.
+        assert_eq!(40, rows.len());
+        for row in rows {
+            assert_eq!("A4", row[2], "{row:?}");
+            let cents = row[3].parse::<f32>().unwrap();
+            assert!((cents - 19.6).abs() < 1.0, "{row:?}");
+        }
+    }
+}
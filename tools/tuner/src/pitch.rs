@@ -0,0 +1,31 @@
+use audio_engine_analysis::pitch::{detect_pitch, PitchEstimate, PitchParameters};
+
+use crate::arguments::Arguments;
+
+/// Detect the pitch of a mono buffer. Returns `None` when the buffer is too quiet or the
+/// detected pitch isn't confident enough.
+pub fn estimate_pitch(
+    mono_samples: &[f32],
+    sample_rate: f32,
+    args: &Arguments,
+) -> Option<PitchEstimate> {
+    let volume = (mono_samples
+        .iter()
+        .map(|sample| sample * sample)
+        .sum::<f32>()
+        / mono_samples.len().max(1) as f32)
+        .sqrt();
+    if volume < args.threshold {
+        return None;
+    }
+
+    // Searching one octave around the wanted note, like tuning a single string.
+    let pitch = args.note.pitch();
+    let parameters = PitchParameters {
+        min_frequency: pitch / 2.0,
+        max_frequency: pitch * 2.0,
+        ..PitchParameters::default()
+    };
+    detect_pitch(mono_samples, sample_rate, &parameters)
+        .filter(|estimate| estimate.confidence >= args.confidence)
+}
@@ -1,16 +1,12 @@
 use std::{thread::sleep, time::Duration};
 
-use audio_engine_fourier::{
-    parameters::{FrequencyRange, Parameters, StepType},
-    to_frequency_domain::ToFrequencyDomain,
-};
-use audio_engine_notes::ChromaticNote;
+use audio_engine_analysis::pitch::cents;
 use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
     BufferSize, Device, StreamConfig, SupportedStreamConfig,
 };
 
-use crate::arguments::Arguments;
+use crate::{arguments::Arguments, pitch::estimate_pitch};
 
 pub struct Tuner {
     arguments: Arguments,
@@ -33,7 +29,9 @@ impl Tuner {
 
     pub fn start(&mut self) {
         let config = StreamConfig {
-            buffer_size: BufferSize::Fixed(4096 * self.config.channels() as u32),
+            buffer_size: BufferSize::Fixed(
+                (self.arguments.buffer_size * self.config.channels() as usize) as u32,
+            ),
             ..self.config.config()
         };
         let sample_rate = config.sample_rate.0 as f32;
@@ -44,7 +42,7 @@ impl Tuner {
             .build_input_stream(
                 &config,
                 move |data: &[f32], _: &_| {
-                    process_samples(data, sample_rate, num_channels, args);
+                    process_samples(data, sample_rate, num_channels, &args);
                 },
                 move |_error| {},
                 None,
@@ -57,34 +55,20 @@ impl Tuner {
     }
 }
 
-fn process_samples(input_samples: &[f32], sample_rate: f32, num_channels: usize, args: Arguments) {
+fn process_samples(input_samples: &[f32], sample_rate: f32, num_channels: usize, args: &Arguments) {
     let mono_samples = input_samples
         .chunks(num_channels)
         .map(|samples| samples[0])
         .collect::<Vec<f32>>();
-    let pitch = args.note.pitch();
-    let start_frequency = pitch / 2.0;
-    let end_frequency = pitch * 2.0;
-    let parameters = Parameters {
-        data_len: mono_samples.len(),
-        steps: args.steps,
-        step_type: StepType::FrequencyRange(FrequencyRange {
-            sample_rate,
-            start_frequency,
-            end_frequency,
-        }),
-    };
-    let frequency_domain = mono_samples
-        .as_slice()
-        .to_frequency_domain_with_parameters(parameters);
-    let step = frequency_domain.find_largest_amplitude();
-    let frequency = frequency_domain.parameters.frequency(step);
-    let amplitude = frequency_domain.amplitude(step);
-    if amplitude > args.threshold {
-        let closest_note = ChromaticNote::from(frequency);
+    if let Some(estimate) = estimate_pitch(&mono_samples, sample_rate, args) {
+        let pitch = args.note.pitch();
         println!(
-            "current={closest_note:?}({frequency}Hz),wanted={:?}({pitch}Hz)",
-            args.note
+            "current={}({:.2}Hz, {:+.1} cents),wanted={}({pitch}Hz, {:+.1} cents)",
+            estimate.note,
+            estimate.frequency,
+            estimate.cents,
+            args.note,
+            cents(estimate.frequency, pitch),
         );
     }
 }
@@ -0,0 +1,84 @@
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Convert a monophonic (.wav) file to tracker patterns",
+    long_about = "Detect the notes of a monophonic <INPUT_FILENAME> and print them as tracker patterns. Note starts are quantized to the rows of the given tempo, the loudness of a note is used as row level. Multichannel wave files use the first channel."
+)]
+pub struct Arguments {
+    /// Tempo in beats per minute.
+    #[arg(long, default_value_t = 120.0)]
+    pub bpm: f32,
+
+    /// Number of rows per beat.
+    #[arg(long, default_value_t = 4.0)]
+    pub rows_per_beat: f32,
+
+    /// Number of rows in each pattern (max 254).
+    #[arg(long, default_value_t = 16)]
+    pub rows_per_pattern: usize,
+
+    /// Instrument (hex) used for each note.
+    #[arg(long, default_value = "00", value_parser = parse_hex)]
+    pub instrument: u8,
+
+    /// Index (hex) of the first pattern.
+    #[arg(long, default_value = "00", value_parser = parse_hex)]
+    pub first_pattern: u8,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Rust)]
+    pub format: OutputFormat,
+
+    /// Input wave file to convert
+    pub input_filename: String,
+}
+
+/// Parse a hexadecimal byte like `1F`.
+fn parse_hex(value: &str) -> Result<u8, String> {
+    u8::from_str_radix(value, 16).map_err(|_| format!("{value} isn't a hexadecimal byte (00-FF)"))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// `song.patterns[..].init(&[..]);` statements.
+    Rust,
+    /// One row per line, patterns separated by an empty line.
+    Rows,
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser;
+
+    use super::Arguments;
+
+    #[test]
+    fn hex_arguments() {
+        let arguments = Arguments::try_parse_from([
+            "wav2pattern",
+            "--instrument",
+            "1F",
+            "--first-pattern",
+            "a0",
+            "input.wav",
+        ])
+        .unwrap();
+        assert_eq!(arguments.instrument, 0x1F);
+        assert_eq!(arguments.first_pattern, 0xA0);
+
+        let defaults = Arguments::try_parse_from(["wav2pattern", "input.wav"]).unwrap();
+        assert_eq!(defaults.instrument, 0);
+        assert_eq!(defaults.first_pattern, 0);
+    }
+
+    #[test]
+    fn invalid_hex_arguments() {
+        for value in ["G1", "100", ""] {
+            assert!(
+                Arguments::try_parse_from(["wav2pattern", "--instrument", value, "input.wav"])
+                    .is_err()
+            );
+        }
+    }
+}
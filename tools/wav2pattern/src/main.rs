@@ -0,0 +1,54 @@
+use std::{fs::File, path::Path};
+
+use arguments::{Arguments, OutputFormat};
+use audio_engine_common::convert_f32s::Convert2F32;
+use audio_engine_sequencer::instrument::InstrumentID;
+use audio_engine_tracker::row::Row;
+use clap::Parser;
+use transcribe::{to_rows, transcribe};
+
+mod arguments;
+mod transcribe;
+
+/// Rows per pattern, one row is needed to mark the end of the pattern.
+const MAX_ROWS_PER_PATTERN: usize = 254;
+
+fn main() {
+    let args = Arguments::parse();
+
+    let mut wav_file = File::open(Path::new(&args.input_filename)).unwrap();
+    let (header, data) = wav::read(&mut wav_file).unwrap();
+    let samples = data
+        .to_f32s()
+        .chunks(header.channel_count.max(1) as usize)
+        .map(|samples| samples[0])
+        .collect::<Vec<f32>>();
+
+    let instrument = InstrumentID::from(args.instrument);
+    let notes = transcribe(&samples, header.sampling_rate as f32);
+    let rows = to_rows(&notes, args.bpm, args.rows_per_beat, instrument);
+
+    let rows_per_pattern = args.rows_per_pattern.clamp(1, MAX_ROWS_PER_PATTERN);
+    let first_pattern = args.first_pattern as usize;
+    for (index, pattern) in rows.chunks(rows_per_pattern).enumerate() {
+        let mut pattern = pattern.to_vec();
+        pattern.resize(rows_per_pattern, Row::default());
+        match args.format {
+            OutputFormat::Rust => {
+                println!("song.patterns[0x{:02X}].init(&[", first_pattern + index);
+                for row in pattern {
+                    println!("    \"{row}\",");
+                }
+                println!("]);");
+            }
+            OutputFormat::Rows => {
+                if index > 0 {
+                    println!();
+                }
+                for row in pattern {
+                    println!("{row}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use audio_engine_analysis::{
+    onset::{detect_onsets, OnsetParameters},
+    pitch::{detect_pitch, PitchParameters},
+};
+use audio_engine_notes::ChromaticNote;
+use audio_engine_sequencer::instrument::InstrumentID;
+use audio_engine_tracker::{event::Event, row::Row};
+
+/// Number of samples to detect the pitch and level of.
+const WINDOW_LEN: usize = 2048;
+const HOP_SIZE: usize = 512;
+/// Time in seconds after the onset to skip, the attack of a note often has no clear pitch.
+const ATTACK_TIME: f32 = 0.02;
+const MIN_CONFIDENCE: f32 = 0.8;
+/// A note ends when its level drops below this fraction of its loudest level.
+const RELEASE_LEVEL: f32 = 0.1;
+
+/// A note found in the audio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TranscribedNote {
+    pub note: ChromaticNote,
+    /// Start time in seconds.
+    pub start: f32,
+    /// End time in seconds.
+    pub end: f32,
+    /// RMS level of the loudest window of the note.
+    pub level: f32,
+}
+
+/// Detect the notes of a monophonic signal.
+///
+/// The signal is split at the onsets. Each part is a note when it has a clear pitch, the
+/// median of the detected frequencies decides the note.
+pub fn transcribe(samples: &[f32], sample_rate: f32) -> Vec<TranscribedNote> {
+    let onsets = detect_onsets(samples, sample_rate, &OnsetParameters::default());
+    let pitch_parameters = PitchParameters {
+        min_frequency: 60.0,
+        max_frequency: 1500.0,
+        ..PitchParameters::default()
+    };
+
+    onsets
+        .iter()
+        .enumerate()
+        .filter_map(|(index, onset)| {
+            let next_onset = onsets.get(index + 1).copied();
+            let start = ((onset + ATTACK_TIME) * sample_rate) as usize;
+            let end = next_onset.map_or(samples.len(), |next| (next * sample_rate) as usize);
+            let windows = (start..end.saturating_sub(WINDOW_LEN))
+                .step_by(HOP_SIZE)
+                .map(|window_start| &samples[window_start..window_start + WINDOW_LEN])
+                .collect::<Vec<&[f32]>>();
+
+            let levels = windows
+                .iter()
+                .map(|window| rms(window))
+                .collect::<Vec<f32>>();
+            let level = levels.iter().copied().fold(0.0, f32::max);
+            let mut frequencies = windows
+                .iter()
+                .filter_map(|window| detect_pitch(window, sample_rate, &pitch_parameters))
+                .filter(|estimate| estimate.confidence >= MIN_CONFIDENCE)
+                .map(|estimate| estimate.frequency)
+                .collect::<Vec<f32>>();
+            if frequencies.is_empty() {
+                return None;
+            }
+            frequencies.sort_by(f32::total_cmp);
+            let frequency = frequencies[frequencies.len() / 2];
+
+            let released = levels
+                .iter()
+                .position(|window_level| *window_level < level * RELEASE_LEVEL)
+                .map(|window| (start + window * HOP_SIZE + WINDOW_LEN / 2) as f32 / sample_rate);
+            Some(TranscribedNote {
+                note: ChromaticNote::from(frequency),
+                start: *onset,
+                end: released.unwrap_or(end as f32 / sample_rate),
+                level,
+            })
+        })
+        .collect()
+}
+
+/// Place the notes on the rows of the given tempo.
+///
+/// Levels are relative to the loudest note. A note off is added when a note ends before
+/// the next note starts.
+pub fn to_rows(
+    notes: &[TranscribedNote],
+    beats_per_minute: f32,
+    rows_per_beat: f32,
+    instrument: InstrumentID,
+) -> Vec<Row> {
+    let row_duration = 60.0 / (beats_per_minute * rows_per_beat);
+    let to_row = |time: f32| (time / row_duration).round() as usize;
+    let max_level = notes
+        .iter()
+        .map(|note| note.level)
+        .fold(f32::EPSILON, f32::max);
+
+    let mut rows = Vec::<Row>::new();
+    for (index, note) in notes.iter().enumerate() {
+        let row = to_row(note.start);
+        let end_row = to_row(note.end);
+        let next_row = notes.get(index + 1).map(|next| to_row(next.start));
+        rows.resize(rows.len().max(end_row + 1), Row::default());
+
+        // Keep the first note when two notes are quantized to the same row.
+        if rows[row].event.is_none() {
+            rows[row] = Row {
+                event: Some(Event::NoteOn(note.note, instrument)),
+                level: Some(note.level / max_level),
+            };
+        }
+        if end_row > row && next_row.is_none_or(|next_row| end_row < next_row) {
+            rows[end_row].event = Some(Event::NoteOff);
+        }
+    }
+    rows
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::TAU;
+
+    use audio_engine_notes::{ChromaticNote, ChromaticTone};
+    use audio_engine_sequencer::instrument::InstrumentID;
+    use audio_engine_tracker::row::Row;
+
+    use super::{to_rows, transcribe};
+
+    #[test]
+    fn melody() {
+        let sample_rate = 44100.0;
+        // Eighth notes at 120 bpm, with a rest at the end.
+        let melody = [
+            (ChromaticNote::new(ChromaticTone::C, 4), 1.0),
+            (ChromaticNote::new(ChromaticTone::E, 4), 0.5),
+            (ChromaticNote::new(ChromaticTone::G, 4), 1.0),
+            (ChromaticNote::new(ChromaticTone::C, 5), 0.5),
+        ];
+        let note_duration = 0.25;
+        let samples = (0..(1.5 * sample_rate) as usize)
+            .map(|index| index as f32 / sample_rate)
+            .map(|time| {
+                let index = (time / note_duration) as usize;
+                melody.get(index).map_or(0.0, |(note, level)| {
+                    let note_time = time - index as f32 * note_duration;
+                    level * (TAU * note.pitch() * time).sin() * (-note_time * 4.0).exp()
+                })
+            })
+            .collect::<Vec<f32>>();
+
+        let notes = transcribe(&samples, sample_rate);
+        assert_eq!(
+            notes
+                .iter()
+                .map(|note| note.note)
+                .collect::<Vec<ChromaticNote>>(),
+            melody
+                .iter()
+                .map(|(note, _)| *note)
+                .collect::<Vec<ChromaticNote>>()
+        );
+
+        let rows = to_rows(&notes, 120.0, 4.0, InstrumentID::from(1));
+        assert_eq!(
+            rows.iter()
+                .map(|row| row.to_string()[..6].to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "C 4 01", "--- --", "E 4 01", "--- --", "G 4 01", "--- --", "C 5 01", "--- --",
+                "OFF --"
+            ]
+        );
+        let levels = rows
+            .iter()
+            .filter_map(|row| row.level)
+            .collect::<Vec<f32>>();
+        for (level, (_, expected)) in levels.iter().zip(melody) {
+            assert!((level - expected).abs() < 0.05, "{level} != {expected}");
+        }
+
+        // Rows can be parsed by the tracker.
+        for row in rows {
+            let mut parsed = Row::default();
+            parsed.init(&row.to_string());
+            assert_eq!(parsed.to_string(), row.to_string());
+        }
+    }
+}
@@ -1,13 +1,12 @@
 use std::{fs::File, path::Path};
 
+use audio_engine_common::convert_f32s::Convert2F32;
 use clap::Parser;
-use convert_f32s::Convert2F32;
 use wav::Header;
 
 use crate::argument::Arguments;
 
 mod argument;
-mod convert_f32s;
 
 fn main() {
     let args = Arguments::parse();